@@ -0,0 +1,103 @@
+//! Counts the heap allocations made while building a 10k-route table, with repeated `insert`
+//! against `insert_many`.
+//!
+//! Run with `cargo bench --bench allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use wildcard_trie::Trie;
+
+/// Wraps the system allocator, counting allocations and the bytes they request
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Generates 10,000 shuffled routes spread across several API versions and resources
+fn routes() -> Vec<(String, usize)> {
+    let mut routes = Vec::new();
+    for version in 0..20 {
+        for resource in 0..100 {
+            for action in ["list", "create", "update", "delete"] {
+                routes.push(format!("/api/v{version}/resource{resource}/{action}"));
+            }
+            routes.push(format!("/api/v{version}/resource{resource}/*"));
+        }
+    }
+
+    // Deterministic shuffle so both strategies see the same arbitrary order
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    for i in (1..routes.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        routes.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    routes
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| (r, i))
+        .collect()
+}
+
+/// Builds a trie from a copy of `routes`, counting only the allocations made by `build`
+fn count(
+    name: &str,
+    routes: &[(String, usize)],
+    build: impl Fn(Vec<(String, usize)>) -> Trie<usize>,
+) {
+    let input = routes.to_vec();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+
+    let trie = black_box(build(input));
+
+    println!(
+        "{name:<16} {:>8} allocations {:>10} bytes allocated ({} routes, {} nodes)",
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+        routes.len(),
+        trie.node_count()
+    );
+}
+
+fn main() {
+    let routes = routes();
+
+    count("insert", &routes, |items| {
+        let mut trie = Trie::new();
+        for (path, value) in items {
+            trie.insert(&path, value);
+        }
+        trie
+    });
+
+    count("insert_many", &routes, |items| {
+        let mut trie = Trie::new();
+        trie.insert_many(items);
+        trie
+    });
+}
@@ -0,0 +1,127 @@
+//! Measures lookups and memory on a REST-style route table, where most nodes have a handful
+//! of children and a few (resource ids, the API root) have many.
+//!
+//! Run with `cargo bench --bench children`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use wildcard_trie::Trie;
+
+/// Wraps the system allocator, tracking the bytes currently allocated
+struct CountingAllocator;
+
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const LOOKUPS: u32 = 1_000_000;
+
+/// Generates routes for a set of resources with nested collections and actions
+fn routes() -> Vec<String> {
+    let resources = [
+        "users",
+        "orgs",
+        "repos",
+        "teams",
+        "issues",
+        "pulls",
+        "gists",
+        "events",
+        "projects",
+        "packages",
+        "apps",
+        "markdown",
+        "search",
+        "notifications",
+        "licenses",
+        "emojis",
+    ];
+    let nested = ["comments", "labels", "members", "hooks", "keys", "releases"];
+    let actions = ["list", "create", "update", "delete"];
+
+    let mut routes = vec![
+        "/".to_string(),
+        "/health".to_string(),
+        "/static/*".to_string(),
+    ];
+    for version in ["v1", "v2", "v3"] {
+        for resource in resources {
+            let base = format!("/api/{version}/{resource}");
+            routes.push(base.clone());
+            routes.push(format!("{base}/*"));
+            for action in actions {
+                routes.push(format!("{base}/{action}"));
+            }
+            for collection in nested {
+                routes.push(format!("{base}/{collection}"));
+                routes.push(format!("{base}/{collection}/*"));
+            }
+        }
+    }
+    routes
+}
+
+fn bench(name: &str, lookup: impl Fn() -> usize) {
+    let start = Instant::now();
+    for _ in 0..LOOKUPS {
+        black_box(lookup());
+    }
+    println!("{name:<32} {:>10.3?} per lookup", start.elapsed() / LOOKUPS);
+}
+
+fn main() {
+    let routes = routes();
+    let live_before = LIVE_BYTES.load(Ordering::Relaxed);
+    let mut trie = Trie::new();
+    for (i, route) in routes.iter().enumerate() {
+        trie.insert(route, i);
+    }
+    let live = LIVE_BYTES.load(Ordering::Relaxed) - live_before;
+    println!(
+        "{} routes, {} nodes: {live} bytes allocated, {} bytes estimated by memory_usage",
+        routes.len(),
+        trie.node_count(),
+        trie.memory_usage()
+    );
+
+    let paths = [
+        "/api/v2/repos/comments",
+        "/api/v3/users/update",
+        "/api/v1/notifications/hooks/42",
+        "/api/v2/licenses/mit",
+        "/static/css/app.css",
+        "/health",
+        "/api/v4/users",
+    ];
+    for path in paths {
+        bench(path, || trie.get(black_box(path)).copied().unwrap_or(0));
+    }
+    bench("all paths", || {
+        paths
+            .iter()
+            .map(|path| trie.get(black_box(path)).copied().unwrap_or(0))
+            .sum()
+    });
+}
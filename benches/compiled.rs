@@ -0,0 +1,60 @@
+//! Compares repeated lookups of the same path with `get` against a key compiled once with
+//! `compile` and looked up with `get_compiled`.
+//!
+//! Run with `cargo bench --bench compiled`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use wildcard_trie::Trie;
+
+const LOOKUPS: u32 = 1_000_000;
+
+/// Builds a route table spread across several API versions and resources
+fn build(mut trie: Trie<usize>) -> Trie<usize> {
+    for version in 0..10 {
+        for resource in 0..100 {
+            trie.insert(
+                &format!("/api/v{version}/resource{resource}/list"),
+                resource,
+            );
+            trie.insert(&format!("/api/v{version}/resource{resource}/*"), resource);
+        }
+    }
+    trie
+}
+
+fn bench(name: &str, lookup: impl Fn() -> Option<usize>) {
+    let start = Instant::now();
+    for _ in 0..LOOKUPS {
+        black_box(lookup());
+    }
+    println!("{name:<32} {:>10.3?} per lookup", start.elapsed() / LOOKUPS);
+}
+
+fn main() {
+    let tries = [
+        ("default", build(Trie::new()), "/api/v7/resource42/list"),
+        (
+            "case-insensitive",
+            build(Trie::with_case_insensitive(true)),
+            "/API/v7/Resource42/List",
+        ),
+        (
+            "percent-decoding",
+            build(Trie::with_percent_decoding(true)),
+            "/api/v7/resource42/%6Cist",
+        ),
+    ];
+
+    for (name, trie, path) in &tries {
+        let path = *path;
+        let key = trie.compile(path);
+        bench(&format!("{name} get"), || {
+            trie.get(black_box(path)).copied()
+        });
+        bench(&format!("{name} get_compiled"), || {
+            trie.get_compiled(black_box(&key)).copied()
+        });
+    }
+}
@@ -0,0 +1,48 @@
+//! Compares lookups in a dynamic trie against the same routes after `freeze`.
+//!
+//! Run with `cargo bench --bench frozen`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use wildcard_trie::Trie;
+
+const LOOKUPS: u32 = 1_000_000;
+
+/// Builds a route table spread across several API versions and resources
+fn build() -> Trie<usize> {
+    let mut trie = Trie::new();
+    for version in 0..10 {
+        for resource in 0..100 {
+            for action in ["list", "create", "update", "delete"] {
+                trie.insert(
+                    &format!("/api/v{version}/resource{resource}/{action}"),
+                    resource,
+                );
+            }
+            trie.insert(&format!("/api/v{version}/resource{resource}/*"), resource);
+        }
+    }
+    trie
+}
+
+fn bench(name: &str, lookup: impl Fn(&str) -> Option<usize>) {
+    let paths = [
+        "/api/v7/resource42/update",
+        "/api/v3/resource99/items/7",
+        "/api/v0/resource1/list",
+        "/static/app.css",
+    ];
+    let start = Instant::now();
+    for i in 0..LOOKUPS {
+        black_box(lookup(black_box(paths[i as usize % paths.len()])));
+    }
+    println!("{name:<10} {:>10.3?} per lookup", start.elapsed() / LOOKUPS);
+}
+
+fn main() {
+    let trie = build();
+    bench("dynamic", |path| trie.get(path).copied());
+    let frozen = build().freeze();
+    bench("frozen", |path| frozen.get(path).copied());
+}
@@ -0,0 +1,74 @@
+//! Compares building a large route table with `insert_many` against repeated `insert`.
+//!
+//! Run with `cargo bench --bench insert_many`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use wildcard_trie::Trie;
+
+const ITERATIONS: u32 = 20;
+
+/// Generates a shuffled set of routes spread across several API versions and resources
+fn routes() -> Vec<(String, usize)> {
+    let mut routes = Vec::new();
+    for version in 0..10 {
+        for resource in 0..100 {
+            for action in ["list", "create", "update", "delete"] {
+                routes.push(format!("/api/v{version}/resource{resource}/{action}"));
+            }
+            routes.push(format!("/api/v{version}/resource{resource}/*"));
+        }
+    }
+
+    // Deterministic shuffle so both strategies see the same arbitrary order
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    for i in (1..routes.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        routes.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    routes
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| (r, i))
+        .collect()
+}
+
+fn bench(
+    name: &str,
+    routes: &[(String, usize)],
+    build: impl Fn(Vec<(String, usize)>) -> Trie<usize>,
+) {
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let input = routes.to_vec();
+        let start = Instant::now();
+        black_box(build(input));
+        total += start.elapsed();
+    }
+    println!(
+        "{name:<16} {:>10.3?} per build ({} routes)",
+        total / ITERATIONS,
+        routes.len()
+    );
+}
+
+fn main() {
+    let routes = routes();
+
+    bench("insert", &routes, |items| {
+        let mut trie = Trie::new();
+        for (path, value) in items {
+            trie.insert(&path, value);
+        }
+        trie
+    });
+
+    bench("insert_many", &routes, |items| {
+        let mut trie = Trie::new();
+        trie.insert_many(items);
+        trie
+    });
+}
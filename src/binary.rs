@@ -0,0 +1,382 @@
+use std::io::{self, Read, Write};
+
+use crate::snapshot::Child;
+use crate::{MatchPolicy, RadixNode, Trie};
+
+/// Version of the format written by [`Trie::save_binary`], stored in its first byte
+const FORMAT_VERSION: u8 = 2;
+/// Oldest version [`Trie::load_binary`] still reads, whose routes have no priorities
+const UNPRIORITIZED_VERSION: u8 = 1;
+
+/// Flag set in the settings byte when a missing leading `/` is prepended to paths
+const REQUIRE_LEADING_SLASH: u8 = 1 << 0;
+/// Flag set in the settings byte when paths are matched regardless of case
+const CASE_INSENSITIVE: u8 = 1 << 1;
+/// Flag set in the settings byte when `%XX` escapes in paths are decoded
+const PERCENT_DECODING: u8 = 1 << 2;
+/// Flag set in the settings byte when runs of the separator in paths are collapsed
+const COLLAPSE_SLASHES: u8 = 1 << 3;
+/// Flag set in the settings byte when `:name` segments are named parameters
+const NAMED_PARAMS: u8 = 1 << 4;
+
+/// A value that can be stored with [`Trie::save_binary`] and read back with
+/// [`Trie::load_binary`]
+///
+/// Implemented for integers, `bool`, `char`, `String`, `()` and vectors of such values.
+///
+/// # Examples
+/// ```rust
+/// # use std::io::{self, Read, Write};
+/// # use wildcard_trie::BinaryValue;
+/// struct Handler(u32);
+///
+/// impl BinaryValue for Handler {
+///     fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+///         self.0.write_binary(w)
+///     }
+///
+///     fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+///         u32::read_binary(r).map(Handler)
+///     }
+/// }
+/// ```
+pub trait BinaryValue: Sized {
+    /// Writes the value to `w`
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()>;
+
+    /// Reads a value written by [`BinaryValue::write_binary`] from `r`
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_binary_value_for_int {
+    ($($int:ty),*) => {$(
+        impl BinaryValue for $int {
+            fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+                w.write_all(&self.to_le_bytes())
+            }
+
+            fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; size_of::<$int>()];
+                r.read_exact(&mut bytes)?;
+                Ok(<$int>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+impl_binary_value_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// Stored as a `u64`, so the format doesn't depend on the platform's pointer width
+impl BinaryValue for usize {
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        (*self as u64).write_binary(w)
+    }
+
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        usize::try_from(u64::read_binary(r)?).map_err(invalid_data)
+    }
+}
+
+/// Stored as an `i64`, so the format doesn't depend on the platform's pointer width
+impl BinaryValue for isize {
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        (*self as i64).write_binary(w)
+    }
+
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        isize::try_from(i64::read_binary(r)?).map_err(invalid_data)
+    }
+}
+
+impl BinaryValue for bool {
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        u8::from(*self).write_binary(w)
+    }
+
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        match u8::read_binary(r)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(invalid_data(format!("invalid bool byte {byte}"))),
+        }
+    }
+}
+
+impl BinaryValue for char {
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        u32::from(*self).write_binary(w)
+    }
+
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        char::try_from(u32::read_binary(r)?).map_err(invalid_data)
+    }
+}
+
+impl BinaryValue for () {
+    fn write_binary<W: Write>(&self, _: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_binary<R: Read>(_: &mut R) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+/// Stored as its length in bytes followed by its UTF-8 bytes
+impl BinaryValue for String {
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.len().write_binary(w)?;
+        w.write_all(self.as_bytes())
+    }
+
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        let length = u64::read_binary(r)?;
+        let mut bytes = Vec::new();
+        // Read through `take` rather than pre-allocating, so a corrupt length can't exhaust
+        // memory before the data runs out
+        r.take(length).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != length {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(bytes).map_err(invalid_data)
+    }
+}
+
+/// Stored as its length followed by each element
+impl<T: BinaryValue> BinaryValue for Vec<T> {
+    fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.len().write_binary(w)?;
+        self.iter().try_for_each(|element| element.write_binary(w))
+    }
+
+    fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        let length = usize::read_binary(r)?;
+        (0..length).map(|_| T::read_binary(r)).collect()
+    }
+}
+
+/// Wraps a decoding failure in an [`io::ErrorKind::InvalidData`] error
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+impl<T: BinaryValue> Trie<T> {
+    /// Writes the routes and settings of the trie in a compact binary format
+    ///
+    /// The format starts with a version byte and the trie's settings, followed by the number
+    /// of routes and each route as its slot, its length-prefixed path, its value and its
+    /// priority. The change callback and prefix length limit aren't saved.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", 1u32);
+    /// trie.insert("/api/users", 2);
+    ///
+    /// let mut buffer = Vec::new();
+    /// trie.save_binary(&mut buffer)?;
+    ///
+    /// let loaded = Trie::<u32>::load_binary(&mut buffer.as_slice())?;
+    /// assert_eq!(loaded.get("/api/users"), Some(&2));
+    /// assert_eq!(loaded.get("/api/posts"), Some(&1));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn save_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let policy = match self.policy {
+            MatchPolicy::ExactFirst => 0u8,
+            MatchPolicy::WildcardFirst => 1,
+        };
+        let mut flags = 0u8;
+        if self.require_leading_slash {
+            flags |= REQUIRE_LEADING_SLASH;
+        }
+        if self.case_insensitive {
+            flags |= CASE_INSENSITIVE;
+        }
+        if self.percent_decoding {
+            flags |= PERCENT_DECODING;
+        }
+        if self.collapse_slashes {
+            flags |= COLLAPSE_SLASHES;
+        }
+        if self.named_params {
+            flags |= NAMED_PARAMS;
+        }
+        w.write_all(&[FORMAT_VERSION, policy, flags, self.separator as u8])?;
+        self.root.value_count().write_binary(w)?;
+
+        let mut stack = vec![(self.root.prefix.clone(), &*self.root)];
+        while let Some((path, node)) = stack.pop() {
+            for (is_wildcard, slot) in [(false, &node.exact_value), (true, &node.wildcard_value)] {
+                if let Some(value) = slot {
+                    is_wildcard.write_binary(w)?;
+                    path.write_binary(w)?;
+                    value.write_binary(w)?;
+                    node.priority_of(value)
+                        .unwrap_or_default()
+                        .write_binary(w)?;
+                }
+            }
+            for child in node.children.values() {
+                stack.push(([path.as_str(), &child.prefix].concat(), child));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a trie written by [`Trie::save_binary`]
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the data was written by a newer version
+    /// of the format or is malformed, and with the reader's error if it fails or runs out of
+    /// data. Data from version 1 of the format, which had no priorities, loads with every
+    /// route at priority 0.
+    pub fn load_binary<R: Read>(r: &mut R) -> io::Result<Trie<T>> {
+        let mut header = [0u8; 4];
+        r.read_exact(&mut header)?;
+        let [version, policy, flags, separator] = header;
+        if !(UNPRIORITIZED_VERSION..=FORMAT_VERSION).contains(&version) {
+            return Err(invalid_data(format!(
+                "unsupported format version {version}, expected {FORMAT_VERSION}"
+            )));
+        }
+        let policy = match policy {
+            0 => MatchPolicy::ExactFirst,
+            1 => MatchPolicy::WildcardFirst,
+            _ => return Err(invalid_data(format!("invalid match policy {policy}"))),
+        };
+        if !separator.is_ascii() || separator == b'*' {
+            return Err(invalid_data(format!("invalid separator byte {separator}")));
+        }
+
+        let mut root = RadixNode::new(String::new());
+        let mut prioritized = false;
+        for _ in 0..u64::read_binary(r)? {
+            let is_wildcard = bool::read_binary(r)?;
+            let path = String::read_binary(r)?;
+            let value = T::read_binary(r)?;
+            let priority = if version == UNPRIORITIZED_VERSION {
+                0
+            } else {
+                i32::read_binary(r)?
+            };
+            prioritized |= priority != 0;
+            // Paths were normalized when they were first inserted, and are stored verbatim
+            root.node_mut(&path)
+                .store_prioritized(value, is_wildcard, priority);
+        }
+
+        Ok(Trie {
+            root: Child::new(root),
+            policy,
+            require_leading_slash: flags & REQUIRE_LEADING_SLASH != 0,
+            case_insensitive: flags & CASE_INSENSITIVE != 0,
+            percent_decoding: flags & PERCENT_DECODING != 0,
+            collapse_slashes: flags & COLLAPSE_SLASHES != 0,
+            named_params: flags & NAMED_PARAMS != 0,
+            separator: char::from(separator),
+            max_prefix_len: None,
+            prioritized,
+            on_change: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{MatchPolicy, Trie};
+
+    fn round_trip<T: super::BinaryValue + Clone>(trie: &Trie<T>) -> Trie<T> {
+        let mut buffer = Vec::new();
+        trie.save_binary(&mut buffer).unwrap();
+        Trie::load_binary(&mut buffer.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut trie = Trie::with_policy(MatchPolicy::WildcardFirst);
+        trie.insert("/api/*", vec!["api".to_string()]);
+        trie.insert("/api/users", vec!["auth".to_string(), "users".to_string()]);
+        trie.insert("/", vec![]);
+        trie.insert_exact("/static/*", vec!["literal".to_string()]);
+
+        let loaded = round_trip(&trie);
+        assert_eq!(
+            loaded.iter_sorted().collect::<Vec<_>>(),
+            trie.iter_sorted().collect::<Vec<_>>()
+        );
+        assert_eq!(loaded.policy(), MatchPolicy::WildcardFirst);
+        assert_eq!(
+            loaded.get_exact("/static/*"),
+            Some(&vec!["literal".to_string()])
+        );
+        assert_eq!(loaded.get("/static/app.css"), None);
+
+        let mut dotted = Trie::with_separator('.');
+        dotted.insert("com.example.*", 'e');
+        let loaded = round_trip(&dotted);
+        assert_eq!(loaded.separator(), '.');
+        assert_eq!(loaded.get("com.example.app"), Some(&'e'));
+
+        let mut prioritized = Trie::new();
+        prioritized.insert("/files/?/raw", 1u8);
+        prioritized.insert_with_priority("/files/*/raw", 2, 4);
+        let loaded = round_trip(&prioritized);
+        assert_eq!(loaded, prioritized);
+        assert_eq!(loaded.get("/files/a/raw"), Some(&2));
+
+        let mut named = Trie::with_named_params(true);
+        named.insert("/users/:id", 1u8);
+        let loaded = round_trip(&named);
+        assert_eq!(loaded.get("/users/42"), Some(&1));
+        assert!(!round_trip(&Trie::<u8>::new()).named_params);
+
+        assert!(round_trip(&Trie::<u8>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_load_binary_reads_unprioritized_version() {
+        let mut trie = Trie::new();
+        trie.insert_with_priority("/api/*", 7u64, 3);
+        let mut buffer = Vec::new();
+        trie.save_binary(&mut buffer).unwrap();
+
+        // Version 1 wrote the same data without the trailing priority of each route
+        buffer[0] = 1;
+        buffer.truncate(buffer.len() - size_of::<i32>());
+        let loaded = Trie::<u64>::load_binary(&mut buffer.as_slice()).unwrap();
+        assert_eq!(loaded.get("/api/users"), Some(&7));
+        assert_ne!(loaded, trie);
+        trie.insert("/api/*", 7);
+        assert_eq!(loaded, trie);
+    }
+
+    #[test]
+    fn test_load_binary_rejects_bad_data() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", 7u64);
+        let mut buffer = Vec::new();
+        trie.save_binary(&mut buffer).unwrap();
+
+        let load = |bytes: &[u8]| {
+            Trie::<u64>::load_binary(&mut &bytes[..])
+                .unwrap_err()
+                .kind()
+        };
+        let mut newer = buffer.clone();
+        newer[0] += 1;
+        assert_eq!(load(&newer), io::ErrorKind::InvalidData);
+        let mut unknown = buffer.clone();
+        unknown[0] = 0;
+        assert_eq!(load(&unknown), io::ErrorKind::InvalidData);
+        assert_eq!(
+            load(&buffer[..buffer.len() - 1]),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(load(&[]), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::string::ToString;
+
+use crate::{DuplicateRoute, Trie};
+
+/// A builder for a [`Trie`] that rejects routes registered more than once
+///
+/// [`Trie::insert`] silently replaces an existing value, which hides mistakes when a route
+/// table is assembled from configuration. The builder fails fast instead.
+///
+/// # Examples
+/// ```rust
+/// # use wildcard_trie::TrieBuilder;
+/// let mut builder = TrieBuilder::new();
+/// builder.insert("/api/*", "api")?.insert("/api/users", "users")?;
+///
+/// let error = builder.insert("/api/*", "other").unwrap_err();
+/// assert_eq!(error.path(), "/api/*");
+///
+/// let trie = builder.build();
+/// assert_eq!(trie.get("/api/posts"), Some(&"api"));
+/// # Ok::<(), wildcard_trie::DuplicateRoute>(())
+/// ```
+#[derive(Debug)]
+pub struct TrieBuilder<T> {
+    trie: Trie<T>,
+}
+
+impl<T> Default for TrieBuilder<T> {
+    fn default() -> Self {
+        Self { trie: Trie::new() }
+    }
+}
+
+impl<T> TrieBuilder<T> {
+    /// Creates a builder for an empty trie
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value at the given path, failing if the path already has a value
+    ///
+    /// On failure the value registered first is kept.
+    pub fn insert(&mut self, path: &str, value: T) -> Result<&mut Self, DuplicateRoute> {
+        self.trie
+            .try_insert(path, value)
+            .map_err(|_| DuplicateRoute::new(path.to_string()))?;
+        Ok(self)
+    }
+
+    /// Finishes building, returning the trie
+    pub fn build(self) -> Trie<T> {
+        self.trie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_route_rejected() {
+        let mut builder = TrieBuilder::new();
+        assert!(builder.insert("/a", 1).is_ok());
+        assert!(builder.insert("/a/*", 2).is_ok());
+        assert!(builder.insert("/b", 3).is_ok());
+
+        let error = builder.insert("/a", 4).unwrap_err();
+        assert_eq!(error.path(), "/a");
+
+        let trie = builder.build();
+        assert_eq!(trie.get("/a"), Some(&1));
+        assert_eq!(trie.get("/a/x"), Some(&2));
+        assert_eq!(trie.get("/b"), Some(&3));
+    }
+}
@@ -0,0 +1,214 @@
+use alloc::borrow::Cow;
+#[cfg(feature = "unicode-case")]
+use alloc::{string::String, vec::Vec};
+
+use crate::{Lookup, Trie};
+
+/// Lowercases a path for case-insensitive matching, borrowing it when nothing changes
+///
+/// Only ASCII letters are folded, unless the `unicode-case` feature is enabled. Every
+/// character is then replaced by its Unicode lowercase mapping, which may be longer than the
+/// character itself, so the folded path is a separate buffer rather than a view of `path`.
+pub(crate) fn fold_case(path: Cow<'_, str>) -> Cow<'_, str> {
+    #[cfg(not(feature = "unicode-case"))]
+    let needs_folding = path.bytes().any(|b| b.is_ascii_uppercase());
+    #[cfg(feature = "unicode-case")]
+    let needs_folding = path.chars().any(changes_case);
+    if !needs_folding {
+        return path;
+    }
+
+    #[cfg(not(feature = "unicode-case"))]
+    return Cow::Owned(path.to_ascii_lowercase());
+    #[cfg(feature = "unicode-case")]
+    return Cow::Owned(path.chars().flat_map(char::to_lowercase).collect());
+}
+
+/// Lowercases a raw byte path like [`fold_case`], leaving bytes that aren't valid UTF-8 as is
+pub(crate) fn fold_case_bytes(path: Cow<'_, [u8]>) -> Cow<'_, [u8]> {
+    #[cfg(not(feature = "unicode-case"))]
+    let needs_folding = path.iter().any(|b| b.is_ascii_uppercase());
+    #[cfg(feature = "unicode-case")]
+    let needs_folding = path
+        .utf8_chunks()
+        .any(|chunk| chunk.valid().chars().any(changes_case));
+    if !needs_folding {
+        return path;
+    }
+
+    #[cfg(not(feature = "unicode-case"))]
+    return Cow::Owned(path.to_ascii_lowercase());
+    #[cfg(feature = "unicode-case")]
+    {
+        let mut folded = Vec::with_capacity(path.len());
+        for chunk in path.utf8_chunks() {
+            let valid: String = chunk.valid().chars().flat_map(char::to_lowercase).collect();
+            folded.extend_from_slice(valid.as_bytes());
+            folded.extend_from_slice(chunk.invalid());
+        }
+        Cow::Owned(folded)
+    }
+}
+
+/// Maps a byte offset into the folded form of `path` back to the offset of the same character
+/// in `path`
+///
+/// ASCII folding keeps every character's length, but Unicode lowercase mappings may be longer
+/// than the character, so the folded lengths are summed up to `folded_offset`.
+pub(crate) fn unfolded_offset(path: &str, folded_offset: usize) -> usize {
+    #[cfg(not(feature = "unicode-case"))]
+    return folded_offset.min(path.len());
+    #[cfg(feature = "unicode-case")]
+    {
+        let mut folded = 0;
+        for (index, c) in path.char_indices() {
+            if folded >= folded_offset {
+                return index;
+            }
+            folded += c.to_lowercase().map(char::len_utf8).sum::<usize>();
+        }
+        path.len()
+    }
+}
+
+/// Checks whether lowercasing changes a character
+#[cfg(feature = "unicode-case")]
+fn changes_case(c: char) -> bool {
+    !c.to_lowercase().eq([c])
+}
+
+impl<T> Trie<T> {
+    /// Retrieves a value like [`Trie::get`], comparing the path to routes regardless of ASCII
+    /// case
+    ///
+    /// Unlike [`Trie::with_case_insensitive`], this leaves the stored routes as they are and
+    /// only affects this lookup. When routes differ only in case, the one following the
+    /// path's own case at the first place they branch is preferred.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get("/API/Users"), None);
+    /// assert_eq!(trie.get_ignore_case("/API/Users"), Some(&"users_handler"));
+    /// ```
+    pub fn get_ignore_case<'a>(&'a self, path: &str) -> Option<&'a T> {
+        let mut lookup = Lookup {
+            ignore_case: true,
+            ..self.lookup()
+        };
+        self.root.get(self.normalize(path).as_bytes(), &mut lookup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_get_ignore_case() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/files/?.TXT", "text_file");
+        trie.insert("/users/:id/Posts", "user_posts");
+
+        assert_eq!(trie.get("/API/Users"), None);
+        assert_eq!(trie.get_ignore_case("/API/Users"), Some(&"users_handler"));
+        assert_eq!(trie.get_ignore_case("/Api/POSTS"), Some(&"api_fallback"));
+        assert_eq!(trie.get_ignore_case("/FILES/a.txt"), Some(&"text_file"));
+        assert_eq!(
+            trie.get_ignore_case("/users/Bob/posts"),
+            Some(&"user_posts")
+        );
+        assert_eq!(trie.get_ignore_case("/static"), None);
+
+        // Routes differing only in case prefer the path's own case where they branch
+        trie.insert("/Docs", "upper");
+        trie.insert("/docs", "lower");
+        assert_eq!(trie.get_ignore_case("/DOCS"), Some(&"upper"));
+        assert_eq!(trie.get_ignore_case("/dOCS"), Some(&"lower"));
+    }
+
+    #[test]
+    fn test_case_insensitive_ascii() {
+        let mut trie = Trie::with_case_insensitive(true);
+        trie.named_params = true;
+        trie.insert("/API/Users", "users_handler");
+        trie.insert("/Static/*", "static_files");
+
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("/API/USERS"), Some(&"users_handler"));
+        assert_eq!(trie.get("/static/App.CSS"), Some(&"static_files"));
+        assert_eq!(trie.get_bytes(b"/STATIC/\xff"), Some(&"static_files"));
+
+        // Keys are stored folded
+        assert_eq!(trie.remove("/api/USERS"), Some("users_handler"));
+        assert_eq!(
+            trie.iter().next(),
+            Some(("/static/*".to_string(), &"static_files"))
+        );
+
+        // Matching is case-insensitive, but captures keep the case of the path
+        trie.insert("/Users/:id", "user");
+        assert_eq!(
+            trie.get_with_rest("/STATIC/Css/App.CSS"),
+            Some((&"static_files", Some("Css/App.CSS".to_string())))
+        );
+        assert_eq!(trie.get_segments("/static/A/b").unwrap().1, ["A", "b"]);
+        assert_eq!(trie.explain("/static/Img").wildcards()[0].rest(), "Img");
+        assert_eq!(
+            trie.match_route("/users/BoB").unwrap().param("id"),
+            Some("BoB")
+        );
+        let mut frozen = Trie::with_case_insensitive(true);
+        frozen.insert("/Static/*", "static_files");
+        assert_eq!(
+            frozen.freeze().get_with_rest("/static/Logo.PNG"),
+            Some((&"static_files", Some("Logo.PNG".to_string())))
+        );
+
+        let mut sensitive = Trie::with_case_insensitive(false);
+        sensitive.insert("/API", 1);
+        assert_eq!(sensitive.get("/api"), None);
+    }
+
+    #[cfg(feature = "unicode-case")]
+    #[test]
+    fn test_unicode_case_folding() {
+        let mut trie = Trie::with_case_insensitive(true);
+        trie.named_params = true;
+        trie.insert("/ÉTÉ", "summer");
+        trie.insert("/straße", "street");
+        trie.insert("/İstanbul", "dotted");
+        trie.insert("/ısparta", "dotless");
+
+        assert_eq!(trie.get("/été"), Some(&"summer"));
+        assert_eq!(trie.get("/Été"), Some(&"summer"));
+
+        // `ẞ` lowercases to `ß`, but `ß` isn't expanded to `ss`
+        assert_eq!(trie.get("/STRAẞE"), Some(&"street"));
+        assert_eq!(trie.get("/strasse"), None);
+
+        // Lowercasing is locale-independent: `İ` becomes `i` followed by a combining dot,
+        // which is longer than `İ`, while dotless `ı` has no uppercase counterpart to match
+        assert_eq!(trie.get("/i\u{307}stanbul"), Some(&"dotted"));
+        assert_eq!(trie.get("/istanbul"), None);
+        assert_eq!(trie.get("/ISPARTA"), None);
+        assert_eq!(trie.get("/ıSPARTA"), Some(&"dotless"));
+
+        assert_eq!(trie.get_bytes("/ÉTÉ".as_bytes()), Some(&"summer"));
+
+        // Captures are sliced from the path as given, past folds that change its length
+        trie.insert("/İİ/*", "files");
+        trie.insert("/İ/:name/ẞ", "param");
+        assert_eq!(
+            trie.get_with_rest("/İİ/Docs/ÉTÉ"),
+            Some((&"files", Some("Docs/ÉTÉ".to_string())))
+        );
+        let matched = trie.match_route("/İ/İzmir/ẞ").unwrap();
+        assert_eq!(matched.param("name"), Some("İzmir"));
+    }
+}
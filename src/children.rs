@@ -0,0 +1,360 @@
+use alloc::vec::Vec;
+use core::ops::Index;
+use core::slice;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::btree_map as map;
+#[cfg(feature = "std")]
+use std::collections::hash_map as map;
+
+use crate::snapshot::{Child, Owned, Storage};
+
+/// Map holding the children of a node with a wide fan-out
+#[cfg(feature = "std")]
+type Map<T, S> = map::HashMap<char, Child<T, S>>;
+/// Map holding the children of a node with a wide fan-out
+#[cfg(not(feature = "std"))]
+type Map<T, S> = map::BTreeMap<char, Child<T, S>>;
+
+/// Number of children above which a node moves them from a sorted vector into a map
+const WIDE_THRESHOLD: usize = 16;
+
+/// Number of children below which a node moves them back into a sorted vector, lower than
+/// [`WIDE_THRESHOLD`] so a node hovering around it doesn't switch on every change
+const NARROW_THRESHOLD: usize = WIDE_THRESHOLD / 2;
+
+/// Child nodes, indexed by the first character of their prefix
+///
+/// Most nodes of a route table have a handful of children, which a vector sorted by first
+/// character holds more compactly than a map and searches just as fast. A node with more than
+/// [`WIDE_THRESHOLD`] children stores them in a `HashMap` (a `BTreeMap` without `std`).
+#[derive(Debug, Clone)]
+pub(crate) enum Children<T, S: Storage = Owned> {
+    Narrow(Vec<(char, Child<T, S>)>),
+    Wide(Map<T, S>),
+}
+
+impl<T, S: Storage> Default for Children<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: Storage> Children<T, S> {
+    pub(crate) const fn new() -> Self {
+        Self::Narrow(Vec::new())
+    }
+
+    /// Creates an empty set of children with room for `capacity` of them
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let mut children = Self::new();
+        children.reserve(capacity);
+        children
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Narrow(entries) => entries.len(),
+            Self::Wide(map) => map.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of children that fit without reallocating
+    pub(crate) fn capacity(&self) -> usize {
+        match self {
+            Self::Narrow(entries) => entries.capacity(),
+            #[cfg(feature = "std")]
+            Self::Wide(map) => map.capacity(),
+            #[cfg(not(feature = "std"))]
+            Self::Wide(map) => map.len(),
+        }
+    }
+
+    /// Makes room for at least `additional` more children, switching to a map if they
+    /// wouldn't fit in a narrow node
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        if let Self::Narrow(entries) = self
+            && entries.len() + additional > WIDE_THRESHOLD
+        {
+            *self = Self::Wide(core::mem::take(entries).into_iter().collect());
+        }
+        match self {
+            Self::Narrow(entries) => entries.reserve(additional),
+            #[cfg(feature = "std")]
+            Self::Wide(map) => map.reserve(additional),
+            #[cfg(not(feature = "std"))]
+            Self::Wide(_) => {}
+        }
+    }
+
+    /// Estimates the heap memory allocated for the entries, excluding the nodes' own
+    pub(crate) fn heap_size(&self) -> usize {
+        let entry_size = core::mem::size_of::<(char, Child<T, S>)>();
+        // Hash maps keep a control byte per bucket next to each entry
+        let control_size = usize::from(cfg!(feature = "std") && matches!(self, Self::Wide(_)));
+        self.capacity() * (entry_size + control_size)
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, first_char: &char) -> Option<&Child<T, S>> {
+        match self {
+            Self::Narrow(entries) => {
+                let index = search(entries, *first_char).ok()?;
+                Some(&entries[index].1)
+            }
+            Self::Wide(map) => map.get(first_char),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self, first_char: &char) -> Option<&mut Child<T, S>> {
+        match self {
+            Self::Narrow(entries) => {
+                let index = search(entries, *first_char).ok()?;
+                Some(&mut entries[index].1)
+            }
+            Self::Wide(map) => map.get_mut(first_char),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, first_char: &char) -> bool {
+        self.get(first_char).is_some()
+    }
+
+    /// Stores a child, returning the one it replaced
+    pub(crate) fn insert(&mut self, first_char: char, child: Child<T, S>) -> Option<Child<T, S>> {
+        match self {
+            Self::Narrow(entries) => match search(entries, first_char) {
+                Ok(index) => Some(core::mem::replace(&mut entries[index].1, child)),
+                Err(index) => {
+                    entries.insert(index, (first_char, child));
+                    if entries.len() > WIDE_THRESHOLD {
+                        *self = Self::Wide(core::mem::take(entries).into_iter().collect());
+                    }
+                    None
+                }
+            },
+            Self::Wide(map) => map.insert(first_char, child),
+        }
+    }
+
+    /// Returns the child starting with `first_char`, storing the one `f` creates if there's none
+    pub(crate) fn get_or_insert_with(
+        &mut self,
+        first_char: char,
+        f: impl FnOnce() -> Child<T, S>,
+    ) -> &mut Child<T, S> {
+        if !self.contains_key(&first_char) {
+            self.insert(first_char, f());
+        }
+        self.get_mut(&first_char).unwrap()
+    }
+
+    pub(crate) fn remove(&mut self, first_char: &char) -> Option<Child<T, S>> {
+        match self {
+            Self::Narrow(entries) => {
+                let index = search(entries, *first_char).ok()?;
+                Some(entries.remove(index).1)
+            }
+            Self::Wide(map) => {
+                let child = map.remove(first_char)?;
+                if map.len() < NARROW_THRESHOLD {
+                    *self = core::mem::take(map).into_iter().collect();
+                }
+                Some(child)
+            }
+        }
+    }
+
+    /// Returns an iterator over the children along with their first characters, in no
+    /// particular order
+    pub(crate) fn iter(&self) -> Iter<'_, T, S> {
+        match self {
+            Self::Narrow(entries) => Iter::Narrow(entries.iter()),
+            Self::Wide(map) => Iter::Wide(map.iter()),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &char> {
+        self.iter().map(|(first_char, _)| first_char)
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &Child<T, S>> {
+        self.iter().map(|(_, child)| child)
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut Child<T, S>> {
+        let (narrow, wide) = match self {
+            Self::Narrow(entries) => (Some(entries.iter_mut().map(|(_, child)| child)), None),
+            Self::Wide(map) => (None, Some(map.values_mut())),
+        };
+        narrow
+            .into_iter()
+            .flatten()
+            .chain(wide.into_iter().flatten())
+    }
+
+    pub(crate) fn into_values(self) -> impl Iterator<Item = Child<T, S>> {
+        self.into_iter().map(|(_, child)| child)
+    }
+}
+
+/// Finds the index of the child starting with `first_char` in a narrow node, or where it
+/// would be inserted
+#[inline]
+fn search<T, S: Storage>(
+    entries: &[(char, Child<T, S>)],
+    first_char: char,
+) -> Result<usize, usize> {
+    entries.binary_search_by_key(&first_char, |(c, _)| *c)
+}
+
+impl<T, S: Storage> Index<&char> for Children<T, S> {
+    type Output = Child<T, S>;
+
+    fn index(&self, first_char: &char) -> &Child<T, S> {
+        self.get(first_char)
+            .expect("no child for the given character")
+    }
+}
+
+impl<T, S: Storage> FromIterator<(char, Child<T, S>)> for Children<T, S> {
+    fn from_iter<I: IntoIterator<Item = (char, Child<T, S>)>>(iter: I) -> Self {
+        let mut entries: Vec<_> = iter.into_iter().collect();
+        if entries.len() > WIDE_THRESHOLD {
+            return Self::Wide(entries.into_iter().collect());
+        }
+        entries.sort_unstable_by_key(|(first_char, _)| *first_char);
+        Self::Narrow(entries)
+    }
+}
+
+/// Iterator over the children of a node, created by [`Children::iter`]
+pub(crate) enum Iter<'a, T, S: Storage = Owned> {
+    Narrow(slice::Iter<'a, (char, Child<T, S>)>),
+    Wide(map::Iter<'a, char, Child<T, S>>),
+}
+
+impl<'a, T, S: Storage> Iterator for Iter<'a, T, S> {
+    type Item = (&'a char, &'a Child<T, S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Narrow(entries) => entries
+                .next()
+                .map(|(first_char, child)| (first_char, child)),
+            Self::Wide(map) => map.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Narrow(entries) => entries.size_hint(),
+            Self::Wide(map) => map.size_hint(),
+        }
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &'a Children<T, S> {
+    type Item = (&'a char, &'a Child<T, S>);
+    type IntoIter = Iter<'a, T, S>;
+
+    fn into_iter(self) -> Iter<'a, T, S> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the children of a node
+pub(crate) enum IntoIter<T, S: Storage = Owned> {
+    Narrow(alloc::vec::IntoIter<(char, Child<T, S>)>),
+    Wide(map::IntoIter<char, Child<T, S>>),
+}
+
+impl<T, S: Storage> Iterator for IntoIter<T, S> {
+    type Item = (char, Child<T, S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Narrow(entries) => entries.next(),
+            Self::Wide(map) => map.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Narrow(entries) => entries.size_hint(),
+            Self::Wide(map) => map.size_hint(),
+        }
+    }
+}
+
+impl<T, S: Storage> IntoIterator for Children<T, S> {
+    type Item = (char, Child<T, S>);
+    type IntoIter = IntoIter<T, S>;
+
+    fn into_iter(self) -> IntoIter<T, S> {
+        match self {
+            Self::Narrow(entries) => IntoIter::Narrow(entries.into_iter()),
+            Self::Wide(map) => IntoIter::Wide(map.into_iter()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::{Children, NARROW_THRESHOLD, WIDE_THRESHOLD};
+    use crate::Trie;
+
+    #[test]
+    fn test_children_switch_between_vec_and_map() {
+        let is_wide = |trie: &Trie<usize>| matches!(trie.root.children, Children::Wide(_));
+        let mut trie = Trie::new();
+        let mut expected = BTreeMap::new();
+        let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+
+        // The root gains one child per letter, crossing the threshold on the way up
+        for (i, letter) in letters.iter().enumerate() {
+            for route in [format!("{letter}x"), format!("{letter}y/*")] {
+                trie.insert(&route, i);
+                expected.insert(route, i);
+            }
+            assert_eq!(is_wide(&trie), i + 1 > WIDE_THRESHOLD, "{letter}");
+        }
+
+        let check = |trie: &Trie<usize>, expected: &BTreeMap<String, usize>| {
+            let entries: BTreeMap<_, _> = trie.iter().map(|(key, v)| (key, *v)).collect();
+            assert_eq!(&entries, expected);
+            for letter in &letters {
+                for path in [
+                    format!("{letter}x"),
+                    format!("{letter}y/z"),
+                    format!("{letter}w"),
+                ] {
+                    let route = path.replace("y/z", "y/*");
+                    assert_eq!(trie.get(&path), expected.get(&route), "{path}");
+                }
+            }
+        };
+        check(&trie, &expected);
+
+        // Removing children switches back only well below the threshold
+        for (i, letter) in letters.iter().enumerate().rev() {
+            trie.remove(&format!("{letter}x"));
+            trie.remove(&format!("{letter}y/*"));
+            expected.remove(&format!("{letter}x"));
+            expected.remove(&format!("{letter}y/*"));
+            assert_eq!(is_wide(&trie), i >= NARROW_THRESHOLD, "{letter}");
+            check(&trie, &expected);
+        }
+        assert!(trie.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Collapses every run of `separator` in a path into one, borrowing it when it has none
+pub(crate) fn collapse_separators(path: Cow<'_, str>, separator: u8) -> Cow<'_, str> {
+    match collapse_separators_bytes(Cow::Borrowed(path.as_bytes()), separator) {
+        Cow::Borrowed(_) => path,
+        Cow::Owned(collapsed) => Cow::Owned(
+            String::from_utf8(collapsed).expect("dropping ASCII separators keeps UTF-8 valid"),
+        ),
+    }
+}
+
+/// Collapses every run of `separator` in a raw byte path like [`collapse_separators`]
+pub(crate) fn collapse_separators_bytes(path: Cow<'_, [u8]>, separator: u8) -> Cow<'_, [u8]> {
+    if !path.windows(2).any(|pair| pair == [separator; 2]) {
+        return path;
+    }
+
+    let mut collapsed = Vec::with_capacity(path.len());
+    for (index, &b) in path.iter().enumerate() {
+        if !(b == separator && index > 0 && path[index - 1] == separator) {
+            collapsed.push(b);
+        }
+    }
+    Cow::Owned(collapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_collapse_slashes() {
+        let mut trie = Trie::with_collapse_slashes(true);
+        trie.insert("/", "home");
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/static//*", "static_files");
+        trie.insert("/docs/", "docs_index");
+
+        for path in ["/api/users", "/api//users", "//api///users", "/api/users"] {
+            assert_eq!(trie.get(path), Some(&"users_handler"), "{path}");
+        }
+        assert_eq!(trie.get("//"), Some(&"home"));
+        assert_eq!(trie.get("/docs//"), Some(&"docs_index"));
+        assert_eq!(trie.get("/static///app.css"), Some(&"static_files"));
+        assert_eq!(trie.get_bytes(b"/api//users"), Some(&"users_handler"));
+        assert_eq!(trie.iter().count(), 4);
+
+        // Off by default, so an empty segment stays distinct
+        let mut plain = Trie::new();
+        plain.insert("/api/users", "users_handler");
+        assert_eq!(plain.get("/api//users"), None);
+        plain.insert("/api//users", "doubled");
+        assert_eq!(plain.get("/api//users"), Some(&"doubled"));
+        assert_eq!(plain.get("/api/users"), Some(&"users_handler"));
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::string::{String, ToString};
+
+use crate::Trie;
+
+/// Path normalization options of a [`Trie`], which a [`CompiledPath`] was normalized with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Normalization {
+    require_leading_slash: bool,
+    case_insensitive: bool,
+    percent_decoding: bool,
+    collapse_slashes: bool,
+    separator: char,
+}
+
+/// A lookup path normalized once, for repeated lookups with [`Trie::get_compiled`]
+///
+/// Created by [`Trie::compile`]. The key owns its path, so it can outlive the trie that
+/// compiled it and be used with any other trie. Tries with different normalization options
+/// normalize the original path again on every lookup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompiledPath {
+    path: String,
+    normalized: String,
+    normalization: Normalization,
+}
+
+impl CompiledPath {
+    /// Returns the path the key was compiled from
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+}
+
+impl<T> Trie<T> {
+    /// Returns the normalization options applied to paths
+    fn normalization(&self) -> Normalization {
+        Normalization {
+            require_leading_slash: self.require_leading_slash,
+            case_insensitive: self.case_insensitive,
+            percent_decoding: self.percent_decoding,
+            collapse_slashes: self.collapse_slashes,
+            separator: self.separator,
+        }
+    }
+
+    /// Normalizes a path once, for repeated lookups with [`Trie::get_compiled`]
+    ///
+    /// Plain lookups normalize their path every time, which allocates with
+    /// [`Trie::with_require_leading_slash`], [`Trie::with_case_insensitive`],
+    /// [`Trie::with_percent_decoding`] or [`Trie::with_collapse_slashes`]. Compiling a hot path
+    /// up front skips that work.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_case_insensitive(true);
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let key = trie.compile("/API/Users");
+    /// assert_eq!(trie.get_compiled(&key), Some(&"users_handler"));
+    /// assert_eq!(trie.get_compiled(&key), trie.get("/API/Users"));
+    /// ```
+    pub fn compile(&self, path: &str) -> CompiledPath {
+        CompiledPath {
+            path: path.to_string(),
+            normalized: self.normalize(path).into_owned(),
+            normalization: self.normalization(),
+        }
+    }
+
+    /// Retrieves a value for a compiled path, matching like [`Trie::get`]
+    pub fn get_compiled<'a>(&'a self, key: &CompiledPath) -> Option<&'a T> {
+        if key.normalization != self.normalization() {
+            return self.get(&key.path);
+        }
+        let mut lookup = self.lookup();
+        self.root.get(key.normalized.as_bytes(), &mut lookup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_get_compiled_matches_get() {
+        let build = |mut trie: Trie<&'static str>| {
+            trie.insert("/api/*", "api_fallback");
+            trie.insert("/api/users", "users_handler");
+            trie.insert("/users/*/posts", "user_posts");
+            trie
+        };
+        let tries = [
+            build(Trie::new()),
+            build(Trie::with_require_leading_slash(true)),
+            build(Trie::with_case_insensitive(true)),
+            build(Trie::with_percent_decoding(true)),
+            build(Trie::with_collapse_slashes(true)),
+        ];
+        let paths = [
+            "/api/users",
+            "/api/posts",
+            "api/users",
+            "/API/Users",
+            "/api/%75sers",
+            "/api//users",
+            "/users/7/posts",
+            "/static",
+            "",
+        ];
+
+        for trie in &tries {
+            for path in paths {
+                let key = trie.compile(path);
+                assert_eq!(key.as_str(), path);
+                assert_eq!(trie.get_compiled(&key), trie.get(path), "{path}");
+
+                // A key compiled by another trie is normalized again
+                for other in &tries {
+                    assert_eq!(other.get_compiled(&key), other.get(path), "{path}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::Trie;
+
+/// A thread-safe trie for route tables that are read concurrently and updated occasionally
+///
+/// Reads take a shared lock and hand out owned values, so no lock is held once a lookup
+/// returns. Updates take an exclusive lock, and [`ConcurrentTrie::replace`] swaps in a whole
+/// new table atomically, so readers see either the old or the new table but never a mix.
+///
+/// # Examples
+/// ```rust
+/// # use wildcard_trie::{ConcurrentTrie, Trie};
+/// let routes = ConcurrentTrie::new();
+/// routes.insert("/api/*", "api_handler");
+/// assert_eq!(routes.get_cloned("/api/users"), Some("api_handler"));
+///
+/// let mut next = Trie::new();
+/// next.insert("/api/*", "api_handler_v2");
+/// routes.replace(next);
+/// assert_eq!(routes.get_cloned("/api/users"), Some("api_handler_v2"));
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentTrie<T>(RwLock<Trie<T>>);
+
+impl<T> Default for ConcurrentTrie<T> {
+    fn default() -> Self {
+        Self(RwLock::new(Trie::new()))
+    }
+}
+
+impl<T> From<Trie<T>> for ConcurrentTrie<T> {
+    fn from(trie: Trie<T>) -> Self {
+        Self(RwLock::new(trie))
+    }
+}
+
+impl<T> ConcurrentTrie<T> {
+    /// Creates a new empty trie
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retrieves a clone of the value for the given path, with exact > wildcard precedence.
+    pub fn get_cloned(&self, path: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.read().get(path).cloned()
+    }
+
+    /// Inserts a value at the given path
+    ///
+    /// Paths ending with `/*` are treated as wildcard routes that match any sub-path.
+    pub fn insert(&self, path: &str, value: T) {
+        self.write().insert(path, value);
+    }
+
+    /// Removes a value at the given path, returning it if it existed
+    pub fn remove(&self, path: &str) -> Option<T> {
+        self.write().remove(path)
+    }
+
+    /// Atomically swaps in a new route table, returning the previous one
+    pub fn replace(&self, new: Trie<T>) -> Trie<T> {
+        core::mem::replace(&mut *self.write(), new)
+    }
+
+    /// Locks the trie for reading, for several lookups against the same table
+    ///
+    /// Writers are blocked until the returned guard is dropped.
+    pub fn read(&self) -> RwLockReadGuard<'_, Trie<T>> {
+        // A panicking reader or writer cannot leave the trie half-updated in a way that
+        // breaks lookups, so recover the guard instead of propagating the poison
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the trie for writing
+    fn write(&self) -> RwLockWriteGuard<'_, Trie<T>> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Consumes the wrapper, returning the underlying trie
+    pub fn into_inner(self) -> Trie<T> {
+        self.0.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let routes = ConcurrentTrie::new();
+        routes.insert("/api/*", "api_handler");
+        routes.insert("/api/users", "users_handler");
+
+        assert_eq!(routes.get_cloned("/api/users"), Some("users_handler"));
+        assert_eq!(routes.remove("/api/users"), Some("users_handler"));
+        assert_eq!(routes.get_cloned("/api/users"), Some("api_handler"));
+    }
+
+    #[test]
+    fn test_reads_while_swapping_tables() {
+        let build = |version: usize| {
+            let mut trie = Trie::new();
+            trie.insert("/api/*", version);
+            trie.insert("/api/users", version);
+            trie
+        };
+        let routes = ConcurrentTrie::from(build(0));
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        // Both routes always come from the same table
+                        let table = routes.read();
+                        let users = table.get("/api/users").copied();
+                        assert!(users.is_some());
+                        assert_eq!(table.get("/api/posts").copied(), users);
+                        drop(table);
+
+                        assert!(routes.get_cloned("/api/users").is_some());
+                    }
+                });
+            }
+
+            scope.spawn(|| {
+                for version in 1..=100 {
+                    let previous = routes.replace(build(version));
+                    assert_eq!(previous.get("/api/users"), Some(&(version - 1)));
+                }
+            });
+        });
+
+        assert_eq!(routes.into_inner().get("/api/posts"), Some(&100));
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Trie;
+
+impl<T> Trie<T> {
+    /// Returns the keys of the routes in this trie that `other` doesn't have, in lexicographic
+    /// order
+    ///
+    /// Keys are compared as yielded by [`Trie::iter`], so wildcard routes carry their `/*`
+    /// suffix. Comparing an edited route table against the original lists the added routes,
+    /// and the other way around the removed ones.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut before = Trie::new();
+    /// before.insert("/api/users", 1);
+    /// before.insert("/legacy/*", 2);
+    ///
+    /// let mut after = before.clone();
+    /// after.remove("/legacy/*");
+    /// after.insert("/api/posts", 3);
+    ///
+    /// assert_eq!(after.difference(&before), ["/api/posts"]);
+    /// assert_eq!(before.difference(&after), ["/legacy/*"]);
+    /// ```
+    pub fn difference(&self, other: &Trie<T>) -> Vec<String> {
+        let theirs = other.entry_map();
+        self.iter_sorted()
+            .filter(|(key, _)| !theirs.contains_key(key))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Returns the keys of the routes both tries have with different values, in lexicographic
+    /// order
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut before = Trie::new();
+    /// before.insert("/api/users", "users_v1");
+    /// before.insert("/api/*", "api_fallback");
+    ///
+    /// let mut after = before.clone();
+    /// after.insert("/api/users", "users_v2");
+    ///
+    /// assert_eq!(after.changed(&before), ["/api/users"]);
+    /// ```
+    pub fn changed(&self, other: &Trie<T>) -> Vec<String>
+    where
+        T: PartialEq,
+    {
+        let theirs = other.entry_map();
+        self.iter_sorted()
+            .filter(|(key, value)| theirs.get(key).is_some_and(|theirs| theirs != value))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Collects the entries of the trie by key
+    fn entry_map(&self) -> BTreeMap<String, &T> {
+        self.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_config_diff() {
+        let mut before = Trie::new();
+        before.insert("/", "home");
+        before.insert("/api/*", "api_v1");
+        before.insert("/api/users", "users");
+        before.insert("/legacy/*", "legacy");
+        before.insert("/static/*", "static_files");
+
+        let mut after = Trie::new();
+        after.insert("/", "home");
+        after.insert("/api/*", "api_v2");
+        after.insert("/api/users", "users");
+        after.insert("/api/posts", "posts");
+        after.insert("/static/*", "cdn");
+        after.insert("/static", "static_index");
+
+        assert_eq!(after.difference(&before), ["/api/posts", "/static"]);
+        assert_eq!(before.difference(&after), ["/legacy/*"]);
+        assert_eq!(after.changed(&before), ["/api/*", "/static/*"]);
+        assert_eq!(before.changed(&after), after.changed(&before));
+
+        assert!(after.difference(&after).is_empty());
+        assert!(after.changed(&after).is_empty());
+        assert_eq!(after.difference(&Trie::new()).len(), 6);
+    }
+}
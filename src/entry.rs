@@ -0,0 +1,108 @@
+use alloc::string::{String, ToString};
+
+use crate::Trie;
+
+/// A view into the slot of a [`Trie`] targeted by a path, returned by [`Trie::entry`]
+///
+/// The slot is the exact slot, or the wildcard slot for paths ending with `/*`. Nodes are only
+/// created once a value is actually inserted, so an unused entry leaves the trie untouched.
+///
+/// # Examples
+/// ```rust
+/// # use wildcard_trie::Trie;
+/// let mut trie = Trie::new();
+/// for path in ["/api/users", "/api/posts", "/api/users"] {
+///     *trie.entry(path).or_default() += 1;
+/// }
+///
+/// assert_eq!(trie.get("/api/users"), Some(&2));
+/// assert_eq!(trie.get("/api/posts"), Some(&1));
+/// ```
+#[derive(Debug)]
+pub struct Entry<'a, T> {
+    trie: &'a mut Trie<T>,
+    path: String,
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// Creates an entry for the slot targeted by `path`
+    pub(crate) fn new(trie: &'a mut Trie<T>, path: &str) -> Self {
+        Self {
+            trie,
+            path: path.to_string(),
+        }
+    }
+
+    /// Returns the path this entry was created for
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns a mutable reference to the value, inserting `value` if the slot is empty
+    pub fn or_insert(self, value: T) -> &'a mut T {
+        self.or_insert_with(|| value)
+    }
+
+    /// Returns a mutable reference to the value, inserting `f()` if the slot is empty
+    pub fn or_insert_with(self, f: impl FnOnce() -> T) -> &'a mut T {
+        self.trie.get_or_insert_with(&self.path, f)
+    }
+
+    /// Returns a mutable reference to the value, inserting `T::default()` if the slot is empty
+    pub fn or_default(self) -> &'a mut T
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+}
+
+impl<T> Trie<T> {
+    /// Returns the entry for the slot targeted by the given path, for in-place insertion
+    ///
+    /// Targets the same slot as [`Trie::get_or_insert_with`], without any wildcard fallback.
+    pub fn entry(&mut self, path: &str) -> Entry<'_, T> {
+        Entry::new(self, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_or_default_counters() {
+        let mut trie: Trie<u32> = Trie::new();
+        trie.insert("/hits/*", 100);
+
+        for path in [
+            "/hits/api",
+            "/hits/api",
+            "/hits/web",
+            "/hits/api",
+            "/hits/*",
+        ] {
+            *trie.entry(path).or_default() += 1;
+        }
+        assert_eq!(trie.get("/hits/api"), Some(&3));
+        assert_eq!(trie.get("/hits/web"), Some(&1));
+        assert_eq!(trie.get("/hits/other"), Some(&101));
+    }
+
+    #[test]
+    fn test_unused_entry_leaves_trie_untouched() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "users");
+        let node_count = trie.node_count();
+
+        assert_eq!(trie.entry("/api/posts").path(), "/api/posts");
+        assert_eq!(trie.node_count(), node_count);
+
+        assert_eq!(*trie.entry("/api/users").or_insert("other"), "users");
+        assert_eq!(
+            *trie.entry("/api/posts").or_insert_with(|| "posts"),
+            "posts"
+        );
+        assert_eq!(trie.get("/api/posts"), Some(&"posts"));
+    }
+}
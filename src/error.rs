@@ -0,0 +1,124 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// An error for a route path that is malformed for this crate's semantics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathError {
+    /// The path doesn't start with `/`
+    MissingLeadingSlash,
+    /// The path isn't valid UTF-8
+    InvalidUtf8,
+    /// The path contains an empty segment (`//`)
+    EmptySegment,
+    /// A `*` isn't preceded by a `/`, as in `/api*`
+    WildcardNotAfterSeparator,
+    /// A `*` is followed by more of its segment, as in `/api/*.json`
+    MisplacedWildcard,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLeadingSlash => f.write_str("path must start with `/`"),
+            Self::InvalidUtf8 => f.write_str("path must be valid UTF-8"),
+            Self::EmptySegment => f.write_str("path must not contain empty segments (`//`)"),
+            Self::WildcardNotAfterSeparator => f.write_str("`*` must follow a `/`"),
+            Self::MisplacedWildcard => {
+                f.write_str("`*` must be a whole segment, followed by `/` or the end of the path")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PathError {}
+
+/// An error for a route registered more than once with a [`TrieBuilder`](crate::TrieBuilder)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DuplicateRoute {
+    path: String,
+}
+
+impl DuplicateRoute {
+    /// Creates an error for the given path
+    pub(crate) fn new(path: String) -> Self {
+        Self { path }
+    }
+
+    /// Returns the path that was registered twice
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for DuplicateRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "route `{}` is already registered", self.path)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DuplicateRoute {}
+
+/// An error for a path with more segments than [`Trie::get_limited`](crate::Trie::get_limited)
+/// allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TooManySegments {
+    max_segments: usize,
+}
+
+impl TooManySegments {
+    /// Creates an error for the given limit
+    pub(crate) fn new(max_segments: usize) -> Self {
+        Self { max_segments }
+    }
+
+    /// Returns the number of segments that was exceeded
+    pub fn max_segments(&self) -> usize {
+        self.max_segments
+    }
+}
+
+impl fmt::Display for TooManySegments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "path has more than {} segments", self.max_segments)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TooManySegments {}
+
+/// An error for a path matched by several equally specific routes, returned by
+/// [`Trie::get_strict`](crate::Trie::get_strict)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ambiguous {
+    keys: Vec<String>,
+}
+
+impl Ambiguous {
+    /// Creates an error for the given competing routes
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+
+    /// Returns the keys of the competing routes, as yielded by
+    /// [`Trie::iter`](crate::Trie::iter), in the order [`Trie::get`](crate::Trie::get) tries
+    /// them
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+}
+
+impl fmt::Display for Ambiguous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "path matches equally specific routes {}",
+            self.keys.join(", ")
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Ambiguous {}
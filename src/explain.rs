@@ -0,0 +1,279 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Lookup, Match, Trie};
+
+/// A diagnostic report of every route that applies to a path, returned by [`Trie::explain`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchReport<'a, T> {
+    winner: Option<Match<'a, T>>,
+    exact: Option<&'a T>,
+    wildcards: Vec<WildcardMatch<'a, T>>,
+}
+
+impl<'a, T> MatchReport<'a, T> {
+    /// Returns the route that [`Trie::get`] resolves the path to, if any
+    ///
+    /// Its [`Match::is_wildcard`] tells which slot the value was found in.
+    pub fn winner(&self) -> Option<&Match<'a, T>> {
+        self.winner.as_ref()
+    }
+
+    /// Returns the value of the preferred route matching the path without a trailing wildcard,
+    /// if any
+    ///
+    /// This is the exact route stored at the path, or else a route whose `*`, `:name` or `?`
+    /// wildcards match it, such as `/users/:id` for `/users/42`.
+    pub fn exact(&self) -> Option<&'a T> {
+        self.exact
+    }
+
+    /// Returns the trailing wildcards matching the path, in the order [`Trie::get`] prefers
+    /// them, which is deepest first under the default policy
+    pub fn wildcards(&self) -> &[WildcardMatch<'a, T>] {
+        &self.wildcards
+    }
+}
+
+/// A trailing wildcard route covering a path, as listed by [`MatchReport::wildcards`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WildcardMatch<'a, T> {
+    value: &'a T,
+    matched_path: String,
+    rest: String,
+}
+
+impl<'a, T> WildcardMatch<'a, T> {
+    /// Returns the value of the wildcard route
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+
+    /// Returns the key of the wildcard route, with its `/*` suffix
+    pub fn matched_path(&self) -> &str {
+        &self.matched_path
+    }
+
+    /// Returns the part of the path the wildcard captures, as [`Trie::get_with_rest`] does
+    pub fn rest(&self) -> &str {
+        &self.rest
+    }
+}
+
+impl<T> Trie<T> {
+    /// Reports every route that applies to the given path, along with the one that wins
+    ///
+    /// Meant for tooling and for debugging tricky precedence, this is heavier than
+    /// [`Trie::get`]. Routes are matched like [`Trie::get_all_matches`], so their patterns can
+    /// use `*`, `:name` and `?` wildcards, and each trailing wildcard comes with the part of
+    /// the path it captures.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let report = trie.explain("/api/users");
+    /// assert_eq!(report.winner().unwrap().value(), &"users_handler");
+    /// assert_eq!(report.exact(), Some(&"users_handler"));
+    /// assert_eq!(report.wildcards()[0].matched_path(), "/api/*");
+    /// assert_eq!(report.wildcards()[0].rest(), "users");
+    /// ```
+    pub fn explain(&self, path: &str) -> MatchReport<'_, T> {
+        let mut exact = None;
+        let mut wildcards = Vec::new();
+        for (value, clean_path, is_wildcard) in self.matching_slots(path) {
+            if !is_wildcard {
+                exact = exact.or(Some(value));
+                continue;
+            }
+            wildcards.push(WildcardMatch {
+                value,
+                matched_path: Self::format_key(&clean_path, true, self.separator),
+                rest: self.wildcard_rest(&clean_path, path),
+            });
+        }
+
+        MatchReport {
+            winner: self.resolve(path),
+            exact,
+            wildcards,
+        }
+    }
+
+    /// Retrieves a value like [`Trie::get`], describing each step of the descent to `trace`
+    ///
+    /// Every message is a human-readable line such as `matched prefix '/api/', descending into
+    /// 'users'`, naming the node prefixes the path is compared against. A node holding a
+    /// pattern route or competing wildcard children is reported as a single step, followed by
+    /// the steps of each branch tried below it, so backtracking shows up as several descents.
+    /// The messages are meant for people and may change between releases.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let mut steps = Vec::new();
+    /// let value = trie.get_traced("/api/posts", |step| steps.push(step.to_string()));
+    /// assert_eq!(value, Some(&"api_fallback"));
+    /// assert_eq!(
+    ///     steps,
+    ///     [
+    ///         "matched prefix '', descending into '/api'",
+    ///         "wildcard fallback set at '/api'",
+    ///         "matched prefix '/api', descending into '/users'",
+    ///         "prefix '/users' doesn't match, returning fallback",
+    ///     ]
+    /// );
+    /// ```
+    pub fn get_traced(&self, path: &str, mut trace: impl FnMut(&str)) -> Option<&T> {
+        let normalized = self.normalize(path);
+        if normalized != path {
+            trace(&format!("normalized path to '{normalized}'"));
+        }
+        let mut lookup = Lookup {
+            trace: Some(&mut trace),
+            ..self.lookup()
+        };
+        self.root.get(normalized.as_bytes(), &mut lookup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_explain() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/*", "root_fallback");
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users/*", "users_fallback");
+        trie.insert("/api/users/:id", "user_by_id");
+        trie.insert("/api/users/me", "me");
+
+        let report = trie.explain("/api/users/me");
+        let winner = report.winner().unwrap();
+        assert_eq!(winner.value(), &"me");
+        assert_eq!(winner.matched_path(), "/api/users/me");
+        assert!(!winner.is_wildcard());
+        assert_eq!(report.exact(), Some(&"me"));
+        let wildcards: Vec<_> = report
+            .wildcards()
+            .iter()
+            .map(|wildcard| (wildcard.matched_path(), wildcard.rest(), *wildcard.value()))
+            .collect();
+        assert_eq!(
+            wildcards,
+            [
+                ("/api/users/*", "me", "users_fallback"),
+                ("/api/*", "users/me", "api_fallback"),
+                ("/*", "api/users/me", "root_fallback"),
+            ]
+        );
+        assert_eq!(
+            trie.get_all_matches("/api/users/me"),
+            [
+                &"me",
+                &"user_by_id",
+                &"users_fallback",
+                &"api_fallback",
+                &"root_fallback"
+            ]
+        );
+
+        // A single-segment wildcard can win without being a literal prefix
+        let report = trie.explain("/api/users/42");
+        assert_eq!(report.winner().unwrap().matched_path(), "/api/users/:id");
+        assert_eq!(report.exact(), Some(&"user_by_id"));
+        assert_eq!(report.wildcards().len(), 3);
+
+        // Trailing wildcards after pattern segments report the part of the path they capture
+        trie.insert("/api/*/files/*", "files");
+        trie.insert("/api/v?/docs/*", "docs");
+        let report = trie.explain("/api/v2/docs/intro/setup");
+        let wildcards: Vec<_> = report
+            .wildcards()
+            .iter()
+            .map(|wildcard| (wildcard.matched_path(), wildcard.rest()))
+            .collect();
+        assert_eq!(
+            wildcards,
+            [
+                ("/api/v?/docs/*", "intro/setup"),
+                ("/api/*", "v2/docs/intro/setup"),
+                ("/*", "api/v2/docs/intro/setup"),
+            ]
+        );
+        let report = trie.explain("/api/42/files/a.txt");
+        assert_eq!(report.wildcards()[0].matched_path(), "/api/*/files/*");
+        assert_eq!(report.wildcards()[0].rest(), "a.txt");
+
+        let empty = Trie::<u8>::new();
+        let report = empty.explain("/anything");
+        assert!(report.winner().is_none() && report.wildcards().is_empty());
+    }
+
+    #[test]
+    fn test_get_traced() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/v1/users", "users");
+        trie.insert("/api/v1/posts", "posts");
+        trie.insert("/files/*/raw", "raw");
+
+        let traced = |trie: &Trie<&'static str>, path| {
+            let mut steps = Vec::new();
+            let value = trie.get_traced(path, |step| steps.push(step.to_string()));
+            assert_eq!(value, trie.get(path), "{path}");
+            steps
+        };
+        assert_eq!(
+            traced(&trie, "/api/v1/zzz"),
+            [
+                "matched prefix '', descending into '/'",
+                "matched prefix '/', descending into 'api'",
+                "wildcard fallback set at 'api'",
+                "matched prefix 'api', descending into '/v1/'",
+                "no child for 'z', returning fallback",
+            ]
+        );
+        assert_eq!(
+            traced(&trie, "/files/a/raw")[2..],
+            [
+                "matching pattern 'files/*/raw' segment by segment",
+                "done matching pattern 'files/*/raw', returning its match",
+            ]
+        );
+
+        // The literal branch is tried and abandoned before the parameter
+        trie.insert("/api/:version/users", "any_users");
+        assert_eq!(
+            traced(&trie, "/api/v2/users")[4..],
+            [
+                "matched prefix '/', trying every child for 'v'",
+                "prefix 'v1/' doesn't match, returning fallback",
+                "matching pattern ':version/users' segment by segment",
+                "done matching pattern ':version/users', returning its match",
+                "done trying children of '/', returning the child's match",
+            ]
+        );
+
+        let mut trie = Trie::with_require_leading_slash(true);
+        trie.insert("/api/users", "users");
+        assert_eq!(
+            traced(&trie, "api"),
+            [
+                "normalized path to '/api'",
+                "matched prefix '', descending into '/api/users'",
+                "prefix '/api/users' doesn't match, no match",
+            ]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::snapshot::Child;
+use crate::{CHAR_WILDCARD, MatchPolicy, RadixNode, Trie, first_char};
+
+/// An immutable [`Trie`] laid out for fast lookups, created by [`Trie::freeze`]
+///
+/// Nodes are flattened into one contiguous array in breadth-first order, so the children of
+/// a node are adjacent and found by a binary search over their first characters instead of
+/// a hash lookup. Their prefixes share a single string buffer.
+///
+/// Only literal routes and trailing `/*` wildcards can be flattened. A trie using `?`,
+/// single-segment `*` or `:name` wildcards needs backtracking, so it keeps its dynamic form
+/// and answers exactly as before.
+#[derive(Debug)]
+pub struct FrozenTrie<T> {
+    /// The source trie: emptied and kept for its settings once flattened, or holding every
+    /// route when they can't be
+    trie: Trie<T>,
+    table: Option<Table<T>>,
+}
+
+/// The flattened nodes of a [`FrozenTrie`], the root first
+#[derive(Debug)]
+struct Table<T> {
+    nodes: Vec<Node>,
+    /// First character of each node's prefix, indexed like `nodes`
+    first_chars: Vec<char>,
+    /// Prefixes of all nodes, concatenated
+    prefixes: String,
+    values: Vec<T>,
+}
+
+/// A node of a [`Table`], referring to its prefix, children and values by position
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    prefix_start: usize,
+    prefix_end: usize,
+    /// Index of the first child in `nodes`, the others following it sorted by first char
+    first_child: usize,
+    child_count: usize,
+    exact: Option<usize>,
+    wildcard: Option<usize>,
+}
+
+impl<T> Table<T> {
+    /// Flattens the tree below `root` breadth first
+    fn new(root: RadixNode<T>) -> Self {
+        let mut table = Table {
+            nodes: Vec::new(),
+            first_chars: Vec::new(),
+            prefixes: String::new(),
+            values: Vec::new(),
+        };
+        let mut queue = VecDeque::from([root]);
+        // Nodes are numbered in the order they're queued, so a node's children get the next
+        // free indices when it's flattened
+        let mut queued = 1;
+        while let Some(node) = queue.pop_front() {
+            let prefix_start = table.prefixes.len();
+            table.prefixes.push_str(&node.prefix);
+            let mut slot = |value: Option<T>| {
+                value.map(|value| {
+                    table.values.push(value);
+                    table.values.len() - 1
+                })
+            };
+            let exact = slot(node.exact_value);
+            let wildcard = slot(node.wildcard_value);
+
+            let mut children: Vec<_> = node.children.into_iter().collect();
+            children.sort_unstable_by_key(|(first_char, _)| *first_char);
+            table.nodes.push(Node {
+                prefix_start,
+                prefix_end: table.prefixes.len(),
+                first_child: queued,
+                child_count: children.len(),
+                exact,
+                wildcard,
+            });
+            table
+                .first_chars
+                .push(node.prefix.chars().next().unwrap_or_default());
+            queued += children.len();
+            queue.extend(children.into_iter().map(|(_, child)| child.into_inner()));
+        }
+        table
+    }
+
+    fn prefix(&self, node: &Node) -> &str {
+        &self.prefixes[node.prefix_start..node.prefix_end]
+    }
+
+    fn value(&self, index: Option<usize>) -> Option<&T> {
+        index.map(|index| &self.values[index])
+    }
+
+    /// Returns the wildcard value of `node` if it covers the rest of the path, which takes
+    /// that rest to start a new segment unless `node` is the root
+    fn wildcard(&self, node: &Node, remaining_path: &[u8], separator: u8) -> Option<&T> {
+        let at_boundary = node.prefix_start == node.prefix_end
+            || remaining_path.first().is_none_or(|&b| b == separator);
+        at_boundary.then(|| self.value(node.wildcard)).flatten()
+    }
+
+    /// Returns the child of `node` whose prefix starts with `first_char`
+    fn child(&self, node: &Node, first_char: char) -> Option<&Node> {
+        let range = node.first_child..node.first_child + node.child_count;
+        let index = self.first_chars[range].binary_search(&first_char).ok()?;
+        Some(&self.nodes[node.first_child + index])
+    }
+
+    /// Retrieves a value like `RadixNode::get` for a trie without pattern routes
+    fn get(&self, path: &[u8], policy: MatchPolicy, separator: u8) -> Option<&T> {
+        let mut node = &self.nodes[0];
+        let mut path = path;
+        let mut fallback = None;
+        loop {
+            let Some(remaining_path) = path.strip_prefix(self.prefix(node).as_bytes()) else {
+                return fallback;
+            };
+            let current_fallback = self.wildcard(node, remaining_path, separator).or(fallback);
+            if remaining_path.is_empty() {
+                let exact = self.value(node.exact);
+                return match policy {
+                    MatchPolicy::ExactFirst => exact.or(current_fallback),
+                    MatchPolicy::WildcardFirst => current_fallback.or(exact),
+                };
+            }
+            let Some(child) = first_char(remaining_path).and_then(|c| self.child(node, c)) else {
+                return current_fallback;
+            };
+            node = child;
+            path = remaining_path;
+            fallback = current_fallback;
+        }
+    }
+
+    /// Retrieves a value like `Table::get`, along with where a wildcard match's rest
+    /// begins
+    fn get_with_rest(
+        &self,
+        path: &str,
+        policy: MatchPolicy,
+        separator: u8,
+    ) -> Option<(&T, Option<usize>)> {
+        let mut node = &self.nodes[0];
+        let mut rest = path;
+        let mut fallback = None;
+        while let Some(remaining_path) = rest.strip_prefix(self.prefix(node)) {
+            let consumed = path.len() - remaining_path.len();
+            fallback = self
+                .wildcard(node, remaining_path.as_bytes(), separator)
+                .map(|value| (value, consumed))
+                .or(fallback);
+            if remaining_path.is_empty() {
+                if let Some(value) = self.value(node.exact)
+                    && (policy == MatchPolicy::ExactFirst || fallback.is_none())
+                {
+                    return Some((value, None));
+                }
+                break;
+            }
+            let first_char = remaining_path.chars().next().unwrap();
+            let Some(child) = self.child(node, first_char) else {
+                break;
+            };
+            node = child;
+            rest = remaining_path;
+        }
+        fallback.map(|(value, end)| (value, Some(end)))
+    }
+}
+
+impl<T> RadixNode<T> {
+    /// Checks whether every route in this subtree is literal, apart from trailing wildcards
+    fn is_flat(&self, after_separator: bool, separator: u8, named_params: bool) -> bool {
+        let ends_with_separator = self.prefix.as_bytes().ends_with(&[separator]);
+        !self.prefix.contains(CHAR_WILDCARD)
+            && self
+                .find_segment_wildcard(0, after_separator, separator, named_params)
+                .is_none()
+            && self
+                .children
+                .values()
+                .all(|child| child.is_flat(ends_with_separator, separator, named_params))
+    }
+}
+
+impl<T> Trie<T> {
+    /// Converts the trie into an immutable form optimized for lookups
+    ///
+    /// Suits a route set that is fully known up front: the frozen trie answers [`Trie::get`]
+    /// and [`Trie::get_with_rest`] queries exactly like the trie did, with the same path
+    /// normalization, but can't be changed anymore. See [`FrozenTrie`] for the layout.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let frozen = trie.freeze();
+    /// assert_eq!(frozen.get("/api/users"), Some(&"users_handler"));
+    /// assert_eq!(
+    ///     frozen.get_with_rest("/api/posts/1"),
+    ///     Some((&"api_fallback", Some("posts/1".to_string())))
+    /// );
+    /// ```
+    pub fn freeze(mut self) -> FrozenTrie<T> {
+        if !self
+            .root
+            .is_flat(false, self.separator as u8, self.named_params)
+        {
+            return FrozenTrie {
+                trie: self,
+                table: None,
+            };
+        }
+        let root = core::mem::replace(&mut self.root, Child::new(RadixNode::new(String::new())));
+        FrozenTrie {
+            table: Some(Table::new(root.into_inner())),
+            trie: self,
+        }
+    }
+}
+
+impl<T> FrozenTrie<T> {
+    /// Retrieves a value for the given path, like [`Trie::get`]
+    pub fn get(&self, path: &str) -> Option<&T> {
+        match &self.table {
+            Some(table) => table.get(
+                &self.trie.normalize_bytes(path.as_bytes()),
+                self.trie.policy,
+                self.trie.separator as u8,
+            ),
+            None => self.trie.get(path),
+        }
+    }
+
+    /// Retrieves a value for the given path along with the part matched by a wildcard, like
+    /// [`Trie::get_with_rest`]
+    pub fn get_with_rest(&self, path: &str) -> Option<(&T, Option<String>)> {
+        let Some(table) = &self.table else {
+            return self.trie.get_with_rest(path);
+        };
+        let normalized = self.trie.normalize(path);
+        let (value, rest_start) =
+            table.get_with_rest(&normalized, self.trie.policy, self.trie.separator as u8)?;
+        Some((
+            value,
+            rest_start.map(|start| self.trie.rest_from(path, start)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MatchPolicy, Trie};
+
+    #[test]
+    fn test_frozen_matches_source() {
+        let literal_routes = [
+            ("/", 0),
+            ("/*", 1),
+            ("/api/*", 2),
+            ("/api/users", 3),
+            ("/api/users/*", 4),
+            ("/api/v1/posts", 5),
+            ("/static/*", 6),
+            ("/café/menu", 7),
+            ("/a*b", 8),
+            ("/x:y", 9),
+        ];
+        let pattern_routes = [("/users/*/posts", 10), ("/files/?.txt", 11), ("/u/:id", 12)];
+        let paths = [
+            "",
+            "/",
+            "/api",
+            "/api/",
+            "/api/users",
+            "/api/users/42",
+            "/apix",
+            "/api/usersx/1",
+            "/api/v1/posts",
+            "/api/v1/post",
+            "/static/css/app.css",
+            "/café/menu",
+            "/caf",
+            "/a*b",
+            "/ab",
+            "/x:y",
+            "/users/7/posts",
+            "/files/a.txt",
+            "/u/alice",
+            "/unknown",
+        ];
+
+        let build = |mut trie: Trie<i32>, with_patterns: bool| {
+            for (path, value) in literal_routes {
+                trie.insert(path, value);
+            }
+            if with_patterns {
+                for (path, value) in pattern_routes {
+                    trie.insert(path, value);
+                }
+            }
+            trie
+        };
+        for with_patterns in [false, true] {
+            let tries = [
+                build(Trie::new(), with_patterns),
+                build(Trie::with_policy(MatchPolicy::WildcardFirst), with_patterns),
+                build(Trie::with_case_insensitive(true), with_patterns),
+            ];
+            for trie in tries {
+                let expected: Vec<_> = paths
+                    .iter()
+                    .map(|path| (trie.get(path).copied(), trie.get_with_rest(path)))
+                    .map(|(value, rest)| (value, rest.map(|(v, rest)| (*v, rest))))
+                    .collect();
+                let frozen = trie.freeze();
+                assert_eq!(frozen.table.is_none(), with_patterns);
+                for (path, expected) in paths.iter().zip(expected) {
+                    let actual = (
+                        frozen.get(path).copied(),
+                        frozen.get_with_rest(path).map(|(v, rest)| (*v, rest)),
+                    );
+                    assert_eq!(actual, expected, "{path}");
+                }
+                assert_eq!(
+                    frozen.get("/API/USERS") == Some(&3),
+                    frozen.trie.case_insensitive
+                );
+            }
+        }
+
+        assert_eq!(Trie::<u8>::new().freeze().get("/anything"), None);
+    }
+}
@@ -0,0 +1,153 @@
+use alloc::sync::Arc;
+
+use crate::Trie;
+
+/// Strings shared by an [`Interner`]
+#[cfg(feature = "std")]
+type Strings = std::collections::HashSet<Arc<str>>;
+/// Strings shared by an [`Interner`]
+#[cfg(not(feature = "std"))]
+type Strings = alloc::collections::BTreeSet<Arc<str>>;
+
+/// A set of shared strings, handing out one allocation for every equal value
+///
+/// Used with [`Trie::insert_interned`]. The interner lives apart from the trie, so it costs
+/// nothing to tries that don't intern, can be shared between several tries, and can be
+/// dropped once the routes are built: the values keep their strings alive on their own.
+///
+/// # Examples
+/// ```rust
+/// # use std::sync::Arc;
+/// # use wildcard_trie::Interner;
+/// let mut interner = Interner::new();
+/// let first = interner.intern("proxy");
+/// assert!(Arc::ptr_eq(&first, &interner.intern("proxy")));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    strings: Strings,
+}
+
+impl Interner {
+    /// Creates an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared string equal to `value`, allocating it on first use
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(shared) = self.strings.get(value) {
+            return Arc::clone(shared);
+        }
+        let shared = Arc::<str>::from(value);
+        self.strings.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// Forgets the strings that no value outside the interner uses anymore, freeing them
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::{Interner, Trie};
+    /// let mut interner = Interner::new();
+    /// let mut trie = Trie::new();
+    /// trie.insert_interned("/api/*", "proxy", &mut interner);
+    /// trie.insert_interned("/auth/login", "login", &mut interner);
+    ///
+    /// trie.remove("/auth/login");
+    /// interner.prune();
+    /// assert_eq!(interner.len(), 1);
+    /// ```
+    pub fn prune(&mut self) {
+        self.strings.retain(|shared| Arc::strong_count(shared) > 1);
+    }
+
+    /// Returns the number of distinct strings held
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Checks whether no string is held
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl Trie<Arc<str>> {
+    /// Inserts a string value at the given path, sharing one allocation between equal values
+    ///
+    /// Suits tables where many routes map to the same handler name: the value is looked up
+    /// in `interner`, and reused if an equal one was interned before. Values inserted with
+    /// [`Trie::insert`] aren't shared. The interner keeps its strings alive until it is
+    /// dropped or [`Interner::prune`] finds them unused.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use wildcard_trie::{Interner, Trie};
+    /// let mut interner = Interner::new();
+    /// let mut trie = Trie::new();
+    /// trie.insert_interned("/api/*", "proxy", &mut interner);
+    /// trie.insert_interned("/static/*", "proxy", &mut interner);
+    ///
+    /// let api = trie.get("/api/users").unwrap();
+    /// let assets = trie.get("/static/app.css").unwrap();
+    /// assert!(Arc::ptr_eq(api, assets));
+    /// ```
+    pub fn insert_interned(&mut self, path: &str, value: &str, interner: &mut Interner) {
+        self.insert(path, interner.intern(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use super::Interner;
+    use crate::Trie;
+
+    #[test]
+    fn test_insert_interned() {
+        let mut interner = Interner::new();
+        let mut trie = Trie::new();
+        trie.insert_interned("/api/*", "proxy", &mut interner);
+        trie.insert_interned("/static/*", "proxy", &mut interner);
+        trie.insert_interned("/auth/login", "login", &mut interner);
+        trie.insert("/legacy", Arc::from("proxy"));
+
+        let api = trie.get("/api/users").unwrap();
+        let assets = trie.get("/static/app.css").unwrap();
+        assert_eq!(&**api, "proxy");
+        assert!(Arc::ptr_eq(api, assets));
+        assert!(!Arc::ptr_eq(api, trie.get("/legacy").unwrap()));
+        assert_eq!(trie.get("/auth/login").map(|value| &**value), Some("login"));
+
+        // Replacing a route reuses the shared value
+        trie.insert_interned("/api/*", "login", &mut interner);
+        assert!(Arc::ptr_eq(
+            trie.get("/api/users").unwrap(),
+            trie.get("/auth/login").unwrap()
+        ));
+
+        // Other tries can share the same interner
+        let mut other = Trie::new();
+        other.insert_interned("/other", "proxy", &mut interner);
+        assert!(Arc::ptr_eq(
+            other.get("/other").unwrap(),
+            trie.get("/static/x").unwrap()
+        ));
+
+        // Pruning only frees the strings no route uses anymore
+        trie.remove("/static/*");
+        other.remove("/other");
+        interner.prune();
+        assert_eq!(interner.len(), 1);
+        assert!(Arc::ptr_eq(
+            &interner.intern("login"),
+            trie.get("/auth/login").unwrap()
+        ));
+        drop(interner);
+        assert_eq!(trie.get("/api/users").map(|value| &**value), Some("login"));
+    }
+}
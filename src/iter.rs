@@ -0,0 +1,307 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{RadixNode, Trie};
+
+/// An iterator over the `(path, &value)` entries of a [`Trie`]
+///
+/// Wildcard routes are yielded with their `/*` suffix restored, and literal `/*` routes with
+/// it escaped as `/\*`. Entries come out in no particular order; use [`Trie::iter_sorted`] for
+/// lexicographic order.
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    /// Nodes still to visit, with the full path of each
+    stack: Vec<(String, &'a RadixNode<T>)>,
+    /// Wildcard entry of the last visited node, yielded after its exact entry
+    pending: Option<(String, &'a T)>,
+    /// Separator used to restore wildcard suffixes
+    separator: char,
+}
+
+impl<'a, T> Iter<'a, T> {
+    /// Creates an iterator over the tree below `root`
+    pub(crate) fn new(root: &'a RadixNode<T>, separator: char) -> Self {
+        Self {
+            stack: Vec::from([(root.prefix.clone(), root)]),
+            pending: None,
+            separator,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.pending.take() {
+            return Some(entry);
+        }
+
+        loop {
+            let (path, node) = self.stack.pop()?;
+            for child in node.children.values() {
+                self.stack
+                    .push(([path.as_str(), &child.prefix].concat(), child));
+            }
+
+            let wildcard = node
+                .wildcard_value
+                .as_ref()
+                .map(|value| (Trie::<T>::format_key(&path, true, self.separator), value));
+            match &node.exact_value {
+                Some(value) => {
+                    self.pending = wildcard;
+                    return Some((Trie::<T>::into_key(path, false, self.separator), value));
+                }
+                None if wildcard.is_some() => return wildcard,
+                None => {}
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Trie<T> {
+    type Item = (String, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An owning iterator over the `(path, value)` entries of a [`Trie`]
+///
+/// Keys are formatted as by [`Iter`]. Entries come out in no particular order.
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    /// Detached nodes still to visit, with the full path of each
+    stack: Vec<(String, RadixNode<T>)>,
+    /// Separator used to restore wildcard suffixes
+    separator: char,
+}
+
+impl<T> IntoIter<T> {
+    /// Creates an iterator that walks the detached tree below `root`
+    pub(crate) fn new(root: RadixNode<T>, separator: char) -> Self {
+        let path = root.prefix.clone();
+        Self {
+            stack: Vec::from([(path, root)]),
+            separator,
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (String, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, node) = self.stack.last_mut()?;
+            if let Some(value) = node.exact_value.take() {
+                return Some((Trie::<T>::format_key(path, false, self.separator), value));
+            }
+            if let Some(value) = node.wildcard_value.take() {
+                return Some((Trie::<T>::format_key(path, true, self.separator), value));
+            }
+
+            // Both slots are drained, so replace this node with its children
+            let (path, node) = self.stack.pop()?;
+            for child in node.children.into_values() {
+                let child_path = [path.as_str(), &child.prefix].concat();
+                self.stack.push((child_path, child.into_inner()));
+            }
+        }
+    }
+}
+
+impl<T> IntoIterator for Trie<T> {
+    type Item = (String, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.root.into_inner(), self.separator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_iter() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 1);
+        trie.insert("/api", 2);
+        trie.insert("/api/users", 3);
+
+        let mut entries: Vec<_> = trie.iter().collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                ("/api".to_string(), &2),
+                ("/api/*".to_string(), &1),
+                ("/api/users".to_string(), &3)
+            ]
+        );
+        assert_eq!((&trie).into_iter().count(), 3);
+        assert_eq!(Trie::<()>::new().iter().count(), 0);
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        let mut trie = Trie::new();
+        for path in [
+            "/static/*",
+            "/api/v1/users",
+            "/api-v2",
+            "/api/*",
+            "/api",
+            "/",
+            "/api/!",
+        ] {
+            trie.insert(path, path.len());
+        }
+
+        let keys: Vec<_> = trie.iter_sorted().map(|(key, _)| key).collect();
+        assert_eq!(
+            keys,
+            [
+                "/",
+                "/api",
+                "/api-v2",
+                "/api/!",
+                "/api/*",
+                "/api/v1/users",
+                "/static/*"
+            ]
+        );
+        assert!(trie.iter_sorted().all(|(key, value)| key.len() == *value));
+    }
+
+    #[test]
+    fn test_to_sorted_entries_is_deterministic() {
+        let routes = [
+            ("/static/*", "wildcard"),
+            ("/api/users", "users"),
+            ("/api/*", "api"),
+            ("/api", "api_root"),
+            ("/api/users/*", "users_fallback"),
+        ];
+        let build = |order: &mut dyn Iterator<Item = &(&'static str, &'static str)>| {
+            let mut trie = Trie::new();
+            for (path, value) in order {
+                trie.insert(path, *value);
+            }
+            trie.insert_exact("/static/*", "literal");
+            trie.insert_exact("/static/\\*", "escaped");
+            trie
+        };
+        let forward = build(&mut routes.iter());
+        let backward = build(&mut routes.iter().rev());
+
+        let entries = forward.to_sorted_entries();
+        assert_eq!(entries, backward.to_sorted_entries());
+        let expected = [
+            ("/api", "api_root"),
+            ("/api/*", "api"),
+            ("/api/users", "users"),
+            ("/api/users/*", "users_fallback"),
+            ("/static/*", "wildcard"),
+            ("/static/\\*", "literal"),
+            ("/static/\\\\*", "escaped"),
+        ];
+        let entries: Vec<_> = entries.iter().map(|(key, v)| (key.as_str(), **v)).collect();
+        assert_eq!(entries, expected);
+        assert!(Trie::<u8>::new().to_sorted_entries().is_empty());
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 1);
+        trie.insert("/api/users", 2);
+        trie.insert("/api/users/*", 3);
+        trie.insert("/static/app.css", 4);
+        trie.insert("/*", 5);
+
+        let mut wildcards: Vec<_> = trie.wildcards().collect();
+        wildcards.sort();
+        assert_eq!(
+            wildcards,
+            vec![
+                ("/*".to_string(), &5),
+                ("/api/*".to_string(), &1),
+                ("/api/users/*".to_string(), &3)
+            ]
+        );
+        let wildcard_entries = trie.iter().filter(|(key, _)| key.ends_with("/*"));
+        assert_eq!(trie.wildcards().count(), wildcard_entries.count());
+        assert_eq!(Trie::<()>::new().wildcards().count(), 0);
+    }
+
+    #[test]
+    fn test_values_mut() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 1);
+        trie.insert("/api/users", 2);
+        trie.insert("/api/users/*", 3);
+        trie.insert("", 4);
+
+        for value in trie.values_mut() {
+            *value *= 2;
+        }
+        assert_eq!(trie.get("/api/posts"), Some(&2));
+        assert_eq!(trie.get("/api/users"), Some(&4));
+        assert_eq!(trie.get("/api/users/1"), Some(&6));
+        assert_eq!(trie.get(""), Some(&8));
+        assert_eq!(trie.values_mut().count(), 4);
+        assert_eq!(Trie::<()>::new().values_mut().count(), 0);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 1);
+        trie.insert("/api/users", 2);
+        trie.insert("", 3);
+
+        let mut entries: Vec<_> = trie.into_iter().collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (String::new(), 3),
+                ("/api/*".to_string(), 1),
+                ("/api/users".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drain_leaves_trie_empty() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/users", "users");
+        trie.insert("/api/v1/posts", "posts");
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/*", "root_fallback");
+
+        let mut drained: Vec<_> = trie.drain().collect();
+        drained.sort();
+        assert_eq!(
+            drained,
+            vec![
+                ("/*".to_string(), "root_fallback"),
+                ("/api/*".to_string(), "api_fallback"),
+                ("/api/v1/posts".to_string(), "posts"),
+                ("/api/v1/users".to_string(), "users"),
+            ]
+        );
+        assert!(trie.is_empty());
+        assert_eq!(trie.get("/api/v1/users"), None);
+
+        // The trie remains usable afterwards
+        trie.insert("/api/users", "users");
+        assert_eq!(trie.get("/api/users"), Some(&"users"));
+    }
+}
@@ -5,6 +5,11 @@
 //!
 //! This crate supports:
 //! - Wildcard Support: Routes ending in `/*` match any sub-path  
+//! - Segment Wildcards: A `*` segment mid-path (e.g. `/users/*/posts`) matches exactly one segment
+//! - Named Parameters: A `:name` segment (e.g. `/users/:id`) matches one segment and binds it
+//!   to the name, in tries built with [`Trie::with_named_params`]
+//! - Character Wildcards: A `?` (e.g. `/file-?.txt`) matches exactly one character within a segment
+//! - Custom Separators: Segments can be split by another character, e.g. `.` for `com.example.*`
 //! - Fast Lookups: `O(path_length)`` instead of `O(number_of_routes)`
 //! - DoS Resistant: Long paths don't create excessive nodes
 //! - Compressed representation: `/api/v1/users` and `/api/v1/posts` share the `/api/v1/` prefix
@@ -20,367 +25,6009 @@
 //! assert_eq!(trie.get("/api/users"), Some(&"users_handler"));  // Exact match
 //! assert_eq!(trie.get("/api/posts"), Some(&"api_handler"));    // Wildcard match
 //! ```
+//!
+//! ## `no_std`
+//! The crate only requires `alloc` when the default `std` feature is disabled. Nodes with many
+//! children then store them in a `BTreeMap` instead of a `HashMap`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
+mod case;
+mod children;
+mod collapse;
+mod compiled;
+#[cfg(feature = "concurrent")]
+mod concurrent;
+mod diff;
+mod entry;
+mod error;
+mod explain;
+mod frozen;
+mod intern;
+mod iter;
+#[cfg(feature = "std")]
+mod lines;
+mod multi;
+mod params;
+mod percent;
 #[cfg(feature = "debug")]
 mod prettyprint;
+mod resolve;
+mod snapshot;
+mod stats;
+mod subtrie;
+mod visit;
+
+#[cfg(feature = "binary")]
+pub use binary::BinaryValue;
+pub use builder::TrieBuilder;
+pub use compiled::CompiledPath;
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentTrie;
+pub use entry::Entry;
+pub use error::{Ambiguous, DuplicateRoute, PathError, TooManySegments};
+pub use explain::{MatchReport, WildcardMatch};
+pub use frozen::FrozenTrie;
+pub use intern::Interner;
+pub use iter::{IntoIter, Iter};
+pub use multi::MultiTrie;
+pub use params::RouteMatch;
+pub use resolve::{Match, MatchedPath};
+#[cfg(feature = "snapshot")]
+pub use snapshot::SnapshotTrie;
+pub use stats::TrieStats;
+pub use subtrie::SubTrie;
+pub use visit::VisitNode;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use children::Children;
+use snapshot::{Child, Owned, Storage};
+
+/// Separator between path segments, unless configured with [`Trie::with_separator`]
+const DEFAULT_SEPARATOR: char = '/';
+
+/// Characters starting a single-segment wildcard (`*` or `:name`), in order of precedence
+const SEGMENT_WILDCARDS: [char; 2] = ['*', ':'];
 
-use std::collections::HashMap;
+/// Returns the characters starting a single-segment wildcard, leaving out `:` unless named
+/// parameters are enabled
+fn segment_wildcards(named_params: bool) -> impl Iterator<Item = char> {
+    SEGMENT_WILDCARDS
+        .into_iter()
+        .filter(move |&wildcard| named_params || wildcard != ':')
+}
 
-/// Suffix that indicates a wildcard route (matches any sub-path)
-const WILDCARD_SUFFIX: &str = "/*";
+/// Matches exactly one character within a segment, taking precedence over segment wildcards
+const CHAR_WILDCARD: char = '?';
 
 /// A node in the radix trie that stores a compressed path prefix
 #[derive(Debug, Clone)]
-struct RadixNode<T> {
+struct RadixNode<T, S: Storage = Owned> {
     /// The path prefix stored at this node (e.g., "/api/v1")
     prefix: String,
     /// Child nodes, indexed by the first character of their prefix
-    children: HashMap<char, RadixNode<T>>,
+    children: Children<T, S>,
     /// Value for exact path matches at this node
     exact_value: Option<T>,
     /// Value for wildcard matches (/*) at this node
     wildcard_value: Option<T>,
+    /// Priority of the exact value among equally specific wildcard matches
+    exact_priority: i32,
+    /// Priority of the wildcard value among equally specific wildcard matches
+    wildcard_priority: i32,
 }
 
-impl<T> RadixNode<T> {
+impl<T, S: Storage> RadixNode<T, S> {
     /// Creates a new node with the given prefix
     fn new(prefix: String) -> Self {
         Self {
             prefix,
-            children: HashMap::new(),
+            children: Children::new(),
             exact_value: None,
             wildcard_value: None,
+            exact_priority: 0,
+            wildcard_priority: 0,
         }
     }
 
-    /// Inserts a value at the given path
-    fn insert(&mut self, path: &str, value: T, is_wildcard: bool) {
+    /// Returns the node whose full path is `path`, creating or splitting nodes as needed
+    fn node_mut(&mut self, path: &str) -> &mut RadixNode<T, S> {
         if path.is_empty() {
-            self.store_value(value, is_wildcard);
-            return;
+            return self;
         }
 
-        let common_length = self.count_common_prefix_chars(path);
+        let common_length = self.common_prefix_len(path);
 
         // Split this node if the path diverges from our prefix
         if common_length < self.prefix.len() {
             self.split_at(common_length);
         }
 
-        // Continue to child or store at current node
+        // Continue to child or stop at current node
         if common_length < path.len() {
-            self.insert_in_child(&path[common_length..], value, is_wildcard);
+            self.node_in_child(&path[common_length..])
+        } else {
+            self
+        }
+    }
+
+    /// Returns the existing node whose full path is exactly `path`, without matching patterns
+    fn find_node(&self, path: &str) -> Option<&RadixNode<T, S>> {
+        let mut node = self;
+        let mut path = path;
+
+        loop {
+            path = path.strip_prefix(node.prefix.as_str())?;
+            let Some(first_char) = path.chars().next() else {
+                return Some(node);
+            };
+            node = node.children.get(&first_char)?;
+        }
+    }
+
+    /// Returns the existing node whose full path is exactly `path`
+    fn find_node_mut(&mut self, path: &str) -> Option<&mut RadixNode<T, S>> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        let common_length = self.common_prefix_len(path);
+        if common_length != self.prefix.len() {
+            return None; // Path doesn't exist
+        }
+
+        let remaining_path = &path[common_length..];
+        if remaining_path.is_empty() {
+            Some(self)
         } else {
-            self.store_value(value, is_wildcard);
+            let first_char = remaining_path.chars().next().unwrap();
+            self.children
+                .get_mut(&first_char)?
+                .find_node_mut(remaining_path)
         }
     }
 
     /// Retrieves a value for the given path, considering wildcards
-    fn get(&self, path: &str) -> Option<&T> {
-        self.get_with_fallback(path, None)
+    ///
+    /// Matching is byte-oriented, so `path` doesn't need to be valid UTF-8.
+    fn get<'a>(&'a self, path: &[u8], lookup: &mut Lookup<'a, '_, T, S>) -> Option<&'a T> {
+        self.get_with_fallback(path, false, None, lookup)
     }
 
-    /// Removes a value at the given path
+    /// Removes a value at the given path, pruning nodes that no longer need to exist
     fn remove(&mut self, path: &str, is_wildcard: bool) -> Option<T> {
         if path.is_empty() {
             return self.take_value(is_wildcard);
         }
 
-        let common_length = self.count_common_prefix_chars(path);
+        let common_length = self.common_prefix_len(path);
         if common_length != self.prefix.len() {
             return None; // Path doesn't exist
         }
 
         let remaining_path = &path[common_length..];
         if remaining_path.is_empty() {
-            self.take_value(is_wildcard)
-        } else {
-            self.remove_from_child(remaining_path, is_wildcard)
+            return self.take_value(is_wildcard);
         }
+
+        let first_char = remaining_path.chars().next().unwrap();
+        let removed = self
+            .children
+            .get_mut(&first_char)?
+            .remove(remaining_path, is_wildcard)?;
+        self.prune_child(first_char);
+        Some(removed)
     }
 
-    /// Stores a value in the appropriate slot (exact or wildcard)
-    fn store_value(&mut self, value: T, is_wildcard: bool) {
-        if is_wildcard {
-            self.wildcard_value = Some(value);
-        } else {
-            self.exact_value = Some(value);
-        }
+    /// Removes the value at the given path without changing the node structure
+    fn unset(&mut self, path: &str, is_wildcard: bool) -> Option<T> {
+        self.find_node_mut(path)?.take_value(is_wildcard)
     }
 
-    /// Takes a value from the appropriate slot (exact or wildcard)
-    fn take_value(&mut self, is_wildcard: bool) -> Option<T> {
-        if is_wildcard {
-            self.wildcard_value.take()
-        } else {
-            self.exact_value.take()
+    /// Drops or merges the given child if it no longer holds values of its own
+    ///
+    /// A valueless leaf is removed, and a valueless node with a single child is merged with
+    /// that child, restoring the compression a fresh insert would have produced.
+    fn prune_child(&mut self, first_char: char) {
+        let Some(child) = self.children.get_mut(&first_char) else {
+            return;
+        };
+        if child.exact_value.is_some() || child.wildcard_value.is_some() {
+            return;
+        }
+
+        match child.children.len() {
+            0 => {
+                self.children.remove(&first_char);
+            }
+            1 => child.merge_only_child(),
+            _ => {}
         }
     }
 
-    /// Counts how many characters this node's prefix shares with the given path
-    fn count_common_prefix_chars(&self, path: &str) -> usize {
-        self.prefix
-            .chars()
-            .zip(path.chars())
-            .take_while(|(a, b)| a == b)
-            .count()
+    /// Prunes every child in this subtree that no longer holds values of its own, deepest first
+    fn compact(&mut self) {
+        let first_chars: Vec<char> = self.children.keys().copied().collect();
+        for first_char in first_chars {
+            if let Some(child) = self.children.get_mut(&first_char) {
+                child.compact();
+            }
+            self.prune_child(first_char);
+        }
     }
 
-    /// Retrieves value with wildcard fallback support
-    fn get_with_fallback<'a>(&'a self, path: &str, fallback: Option<&'a T>) -> Option<&'a T> {
-        // Update fallback if we have a wildcard at this level
-        let current_fallback = self.wildcard_value.as_ref().or(fallback);
+    /// Absorbs this node's single child, concatenating their prefixes
+    fn merge_only_child(&mut self) {
+        let children = core::mem::take(&mut self.children);
+        if let Some(child) = children.into_values().next() {
+            let child = child.into_inner();
+            self.prefix.push_str(&child.prefix);
+            self.children = child.children;
+            self.exact_value = child.exact_value;
+            self.wildcard_value = child.wildcard_value;
+            self.exact_priority = child.exact_priority;
+            self.wildcard_priority = child.wildcard_priority;
+        }
+    }
 
-        if path.is_empty() {
-            return self
-                .exact_value
-                .as_ref()
-                .or(self.wildcard_value.as_ref())
-                .or(fallback);
+    /// Splits this node's prefix into a chain of nodes holding at most `max` bytes each
+    ///
+    /// A chunk is cut back to a character boundary, but always holds at least one character.
+    /// The chain is built from the end, so every byte of the prefix is copied once.
+    fn chunk_prefix(&mut self, max: usize) {
+        if self.prefix.len() <= max {
+            return;
         }
 
-        let common_length = self.count_common_prefix_chars(path);
+        let mut cuts = Vec::new();
+        let mut start = 0;
+        while self.prefix.len() - start > max {
+            let mut end = start + max;
+            while !self.prefix.is_char_boundary(end) {
+                end -= 1;
+            }
+            if end == start {
+                end += self.prefix[start..].chars().next().unwrap().len_utf8();
+            }
+            if end == self.prefix.len() {
+                break; // A single character longer than `max`
+            }
+            cuts.push(end);
+            start = end;
+        }
 
-        if common_length == self.prefix.len() {
-            let remaining_path = &path[common_length..];
+        // Each split moves only the last chunk, since the ones after it are already split off
+        for &cut in cuts.iter().rev() {
+            self.split_at(cut);
+        }
+        self.prefix.shrink_to_fit();
+    }
 
-            if remaining_path.is_empty() {
-                // Exact match at this node
-                self.exact_value
-                    .as_ref()
-                    .or(self.wildcard_value.as_ref())
-                    .or(current_fallback)
-            } else {
-                // Continue searching in children
-                self.search_in_child(remaining_path, current_fallback)
-            }
-        } else {
-            // Partial match - return original fallback, not our wildcard
-            fallback
+    /// Chunks the prefix of every node along `path` with [`RadixNode::chunk_prefix`],
+    /// returning the node whose full path is `path` if it exists
+    fn chunk_path(&mut self, path: &str, max: usize) -> Option<&mut RadixNode<T, S>> {
+        let mut node = self;
+        let mut path = path;
+        loop {
+            node.chunk_prefix(max);
+            path = path.strip_prefix(node.prefix.as_str())?;
+            let Some(first_char) = path.chars().next() else {
+                return Some(node);
+            };
+            node = node.children.get_mut(&first_char)?;
         }
     }
 
-    /// Inserts value in the appropriate child node
-    fn insert_in_child(&mut self, remaining_path: &str, value: T, is_wildcard: bool) {
-        let first_char = remaining_path.chars().next().unwrap();
-        self.children
-            .entry(first_char)
-            .or_insert_with(|| RadixNode::new(remaining_path.to_string()))
-            .insert(remaining_path, value, is_wildcard);
+    /// Returns the node whose full path is `path` like [`RadixNode::node_mut`], chunking the
+    /// prefixes along it longer than `max`
+    fn node_mut_chunked(&mut self, path: &str, max: Option<usize>) -> &mut RadixNode<T, S> {
+        let Some(max) = max else {
+            return self.node_mut(path);
+        };
+        self.node_mut(path);
+        self.chunk_path(path, max).expect("node was just created")
     }
 
-    /// Searches for a value in child nodes
-    fn search_in_child<'a>(
-        &'a self,
-        remaining_path: &str,
-        fallback: Option<&'a T>,
-    ) -> Option<&'a T> {
-        let first_char = remaining_path.chars().next().unwrap();
-        if let Some(child) = self.children.get(&first_char) {
-            child.get_with_fallback(remaining_path, fallback)
-        } else {
-            fallback
+    /// Removes a value like [`RadixNode::remove`], chunking again the prefixes the pruning
+    /// merged past `max_prefix_len`
+    fn remove_chunked(
+        &mut self,
+        path: &str,
+        is_wildcard: bool,
+        max_prefix_len: Option<usize>,
+    ) -> Option<T> {
+        let removed = self.remove(path, is_wildcard)?;
+        if let Some(max) = max_prefix_len {
+            self.chunk_path(path, max);
         }
+        Some(removed)
     }
 
-    /// Removes value from the appropriate child node
-    fn remove_from_child(&mut self, remaining_path: &str, is_wildcard: bool) -> Option<T> {
-        let first_char = remaining_path.chars().next().unwrap();
-        if let Some(child) = self.children.get_mut(&first_char) {
-            child.remove(remaining_path, is_wildcard)
-        } else {
-            None
+    /// Chunks the prefix of every node in this subtree with [`RadixNode::chunk_prefix`]
+    fn chunk_subtree(&mut self, max: usize) {
+        let mut stack = Vec::from([self]);
+        while let Some(node) = stack.pop() {
+            node.chunk_prefix(max);
+            stack.extend(node.children.values_mut().map(|child| &mut **child));
         }
     }
 
-    /// Splits this node at the given position to accommodate path divergence
-    fn split_at(&mut self, split_position: usize) {
-        if split_position >= self.prefix.len() {
-            return;
+    /// Detaches the child subtree covering the non-empty `prefix`
+    ///
+    /// The detached subtree is returned as a root of its own, with paths relative to `prefix`.
+    fn split_off(&mut self, prefix: &str) -> Option<RadixNode<T, S>> {
+        let first_char = prefix.chars().next().unwrap();
+        let child = self.children.get_mut(&first_char)?;
+
+        let common_length = child.common_prefix_len(prefix);
+        if common_length == prefix.len() {
+            // Every path below this child starts with the prefix
+            let mut child = self.children.remove(&first_char)?;
+            child.prefix = child.prefix.split_off(common_length);
+            let Some(first_char) = child.prefix.chars().next() else {
+                return Some(child.into_inner());
+            };
+            let mut root = RadixNode::new(String::new());
+            root.children.insert(first_char, child);
+            Some(root)
+        } else if common_length == child.prefix.len() {
+            let detached = child.split_off(&prefix[common_length..]);
+            self.prune_child(first_char);
+            detached
+        } else {
+            None // Prefix diverges from the stored paths
         }
+    }
 
-        // Create new child with the suffix
-        let suffix = self.prefix.split_off(split_position);
-        let mut new_child = RadixNode::new(suffix.clone());
+    /// Returns the topmost node whose full path starts with `prefix`
+    fn find_prefix(&self, prefix: &str) -> Option<&RadixNode<T, S>> {
+        let mut node = self;
+        let mut prefix = prefix;
 
-        // Move our data to the new child
-        new_child.children = std::mem::take(&mut self.children);
-        new_child.exact_value = self.exact_value.take();
-        new_child.wildcard_value = self.wildcard_value.take();
+        loop {
+            let common_length = node.common_prefix_len(prefix);
+            if common_length == prefix.len() {
+                return Some(node); // Every path below this node starts with the prefix
+            }
+            if common_length != node.prefix.len() {
+                return None; // Prefix diverges from the stored paths
+            }
 
-        // Add the new child
-        let first_char = suffix.chars().next().unwrap();
-        self.children.insert(first_char, new_child);
+            prefix = &prefix[common_length..];
+            node = node.children.get(&prefix.chars().next()?)?;
+        }
     }
-}
 
-/// A radix trie for efficient path-based routing with wildcard support
-#[derive(Debug)]
-pub struct Trie<T>(RadixNode<T>);
+    /// Returns the number of values stored in this subtree
+    fn value_count(&self) -> usize {
+        let own =
+            usize::from(self.exact_value.is_some()) + usize::from(self.wildcard_value.is_some());
+        own + self
+            .children
+            .values()
+            .map(|child| child.value_count())
+            .sum::<usize>()
+    }
 
-impl<T> Default for Trie<T> {
-    fn default() -> Self {
-        Self(RadixNode::new(String::new()))
+    /// Checks whether any node in this subtree stores a value, stopping at the first one
+    fn has_values(&self) -> bool {
+        self.exact_value.is_some()
+            || self.wildcard_value.is_some()
+            || self.children.values().any(|child| child.has_values())
     }
-}
 
-impl<T> Trie<T> {
-    /// Creates a new empty trie
-    pub fn new() -> Self {
-        Self::default()
+    /// Returns the number of nodes on the longest path from this node to a leaf
+    fn depth(&self) -> usize {
+        1 + self
+            .children
+            .values()
+            .map(|child| child.depth())
+            .max()
+            .unwrap_or(0)
     }
 
-    /// Inserts a value at the given path
-    ///
-    /// Paths ending with `/*` are treated as wildcard routes that match any sub-path.
-    ///
-    /// # Examples
-    /// ```rust
-    /// # use wildcard_trie::Trie;
-    /// let mut trie = Trie::new();
-    /// trie.insert("/api/users", "users_handler");
-    /// trie.insert("/api/*", "api_fallback");
-    /// ```
-    pub fn insert(&mut self, path: &str, value: T) {
-        let (clean_path, is_wildcard) = Self::parse_path(path);
-        self.0.insert(clean_path, value, is_wildcard);
+    /// Returns the number of nodes in this subtree, including this node
+    fn node_count(&self) -> usize {
+        1 + self
+            .children
+            .values()
+            .map(|child| child.node_count())
+            .sum::<usize>()
     }
 
-    /// Retrieves a value for the given path, with exact > wildcard precedence.
-    ///
-    /// # Examples
-    /// ```rust
-    /// # use wildcard_trie::Trie;
-    /// # let mut trie = Trie::new();
-    /// # trie.insert("/api/users", "users_handler");
-    /// # trie.insert("/api/*", "api_fallback");
-    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler"));  // Exact
-    /// assert_eq!(trie.get("/api/posts"), Some(&"api_fallback"));   // Wildcard
-    /// ```
-    pub fn get<'a>(&'a self, path: &str) -> Option<&'a T> {
-        self.0.get(path)
+    /// Estimates the heap memory owned by this subtree, excluding this node itself
+    fn heap_usage(&self) -> usize {
+        self.prefix.capacity()
+            + self.children.heap_size()
+            + self.children.len() * S::allocation_size::<T>()
+            + self
+                .children
+                .values()
+                .map(|child| child.heap_usage())
+                .sum::<usize>()
     }
 
-    /// Removes a value at the given path, returning it if it existed
-    pub fn remove(&mut self, path: &str) -> Option<T> {
-        let (clean_path, is_wildcard) = Self::parse_path(path);
-        self.0.remove(clean_path, is_wildcard)
+    /// Stores a value in the appropriate slot (exact or wildcard), returning the old value
+    fn store_value(&mut self, value: T, is_wildcard: bool) -> Option<T> {
+        self.store_prioritized(value, is_wildcard, 0)
     }
 
-    /// Parses a path to determine if it's a wildcard and extract the clean path
-    fn parse_path(path: &str) -> (&str, bool) {
-        if let Some(prefix) = path.strip_suffix(WILDCARD_SUFFIX) {
-            (prefix, true)
+    /// Stores a value with the given priority, returning the old value
+    fn store_prioritized(&mut self, value: T, is_wildcard: bool, priority: i32) -> Option<T> {
+        *self.priority_mut(is_wildcard) = priority;
+        self.slot_mut(is_wildcard).replace(value)
+    }
+
+    /// Takes a value from the appropriate slot (exact or wildcard)
+    fn take_value(&mut self, is_wildcard: bool) -> Option<T> {
+        *self.priority_mut(is_wildcard) = 0;
+        if is_wildcard {
+            self.wildcard_value.take()
         } else {
-            (path, false)
+            self.exact_value.take()
         }
     }
 
-    /// Checks if the trie is empty
-    fn is_empty(&self) -> bool {
-        self.0.children.is_empty()
-            && self.0.exact_value.is_none()
-            && self.0.wildcard_value.is_none()
+    /// Returns the priority of the appropriate slot (exact or wildcard)
+    fn priority_mut(&mut self, is_wildcard: bool) -> &mut i32 {
+        if is_wildcard {
+            &mut self.wildcard_priority
+        } else {
+            &mut self.exact_priority
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_exact_path_matching() {
-        let mut trie = Trie::new();
-        trie.insert("/api/users", "users_handler");
-        trie.insert("/api/posts", "posts_handler");
+    /// Returns the priority of the slot holding `value`, if it's stored in this node
+    fn priority_of(&self, value: &T) -> Option<i32> {
+        if same_value(self.exact_value.as_ref(), Some(value)) {
+            Some(self.exact_priority)
+        } else if same_value(self.wildcard_value.as_ref(), Some(value)) {
+            Some(self.wildcard_priority)
+        } else {
+            None
+        }
+    }
 
-        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
-        assert_eq!(trie.get("/api/posts"), Some(&"posts_handler"));
-        assert_eq!(trie.get("/api/other"), None);
+    /// Returns the value in the appropriate slot (exact or wildcard)
+    fn slot(&self, is_wildcard: bool) -> Option<&T> {
+        if is_wildcard {
+            self.wildcard_value.as_ref()
+        } else {
+            self.exact_value.as_ref()
+        }
     }
 
-    #[test]
-    fn test_wildcard_matching() {
-        let mut trie = Trie::new();
-        trie.insert("/api/*", "api_handler");
+    /// Returns the appropriate slot (exact or wildcard)
+    fn slot_mut(&mut self, is_wildcard: bool) -> &mut Option<T> {
+        if is_wildcard {
+            &mut self.wildcard_value
+        } else {
+            &mut self.exact_value
+        }
+    }
 
-        assert_eq!(trie.get("/api/users"), Some(&"api_handler"));
-        assert_eq!(trie.get("/api/posts/123"), Some(&"api_handler"));
-        assert_eq!(trie.get("/auth/login"), None);
+    /// Returns the length in bytes of the prefix this node shares with the given path
+    fn common_prefix_len(&self, path: &str) -> usize {
+        common_prefix_len(&self.prefix, path)
     }
 
-    #[test]
-    fn test_exact_takes_precedence_over_wildcard() {
-        let mut trie = Trie::new();
-        trie.insert("/api/*", "wildcard_handler");
-        trie.insert("/api/users", "exact_handler");
+    /// Retrieves value with wildcard fallback support
+    ///
+    /// `after_separator` tells whether the parent's prefix ends with the segment separator,
+    /// which decides whether a `*` at the start of this node's prefix is a single-segment
+    /// wildcard.
+    fn get_with_fallback<'a>(
+        &'a self,
+        path: &[u8],
+        after_separator: bool,
+        fallback: Option<&'a T>,
+        lookup: &mut Lookup<'a, '_, T, S>,
+    ) -> Option<&'a T> {
+        // Descend iteratively along the literal chain, so deep tries and long adversarial
+        // paths don't grow the stack. Only single-segment wildcards branch, and recursion
+        // there is bounded by the registered patterns rather than by the query.
+        let mut node = self;
+        let mut path = path;
+        let mut after_separator = after_separator;
+        let mut fallback = fallback;
 
-        assert_eq!(trie.get("/api/users"), Some(&"exact_handler"));
-        assert_eq!(trie.get("/api/posts"), Some(&"wildcard_handler"));
-    }
+        let outcome = |found: Option<&T>| match found {
+            Some(_) => "returning fallback",
+            None => "no match",
+        };
+        loop {
+            lookup.visit(node);
+            if node
+                .find_segment_wildcard(0, after_separator, lookup.separator, lookup.named_params)
+                .is_some()
+            {
+                lookup.trace(|| format!("matching pattern '{}' segment by segment", node.prefix));
+                let found = node.match_from(0, path, after_separator, fallback, lookup);
+                lookup.trace(|| {
+                    let outcome = match found {
+                        found if same_value(found, fallback) => outcome(found),
+                        _ => "returning its match",
+                    };
+                    format!("done matching pattern '{}', {outcome}", node.prefix)
+                });
+                return found;
+            }
 
-    #[test]
-    fn test_path_compression() {
-        let mut trie = Trie::new();
-        trie.insert("/api/v1/users", "v1_users");
-        trie.insert("/api/v1/posts", "v1_posts");
-        trie.insert("/api/v2/users", "v2_users");
+            let Some(remaining_path) = lookup.strip_glob(&node.prefix, path) else {
+                // Partial match - return original fallback, not our wildcard
+                lookup.trace(|| {
+                    let outcome = outcome(fallback);
+                    format!("prefix '{}' doesn't match, {outcome}", node.prefix)
+                });
+                return fallback;
+            };
 
-        assert_eq!(trie.get("/api/v1/users"), Some(&"v1_users"));
-        assert_eq!(trie.get("/api/v1/posts"), Some(&"v1_posts"));
-        assert_eq!(trie.get("/api/v2/users"), Some(&"v2_users"));
-    }
+            // Update fallback if we have a wildcard at this level
+            let wildcard = lookup.wildcard_slot(node, remaining_path);
+            if wildcard.is_some() {
+                lookup.trace(|| format!("wildcard fallback set at '{}'", node.prefix));
+            }
+            let current_fallback = wildcard.or(fallback);
+            if remaining_path.is_empty() {
+                // Exact match at this node
+                let found = node.resolve(current_fallback, lookup);
+                lookup.trace(|| {
+                    let outcome = match found {
+                        Some(value) if same_value(Some(value), node.exact_value.as_ref()) => {
+                            "returning exact value"
+                        }
+                        found => outcome(found),
+                    };
+                    format!("path ends at '{}', {outcome}", node.prefix)
+                });
+                return found;
+            }
 
-    #[test]
-    fn test_removal() {
-        let mut trie = Trie::new();
-        trie.insert("/api/users", "handler");
+            // Children all start with a whole character, so invalid UTF-8 matches none of them
+            let Some(first_char) = first_char(remaining_path) else {
+                lookup.trace(|| format!("invalid UTF-8, {}", outcome(current_fallback)));
+                return current_fallback;
+            };
 
-        assert_eq!(trie.get("/api/users"), Some(&"handler"));
-        assert_eq!(trie.remove("/api/users"), Some("handler"));
-        assert_eq!(trie.get("/api/users"), None);
+            // A wildcard sibling of the literal child, or a literal child in the other case when
+            // ignoring case, needs a branching search
+            let child_after_separator = node.prefix.as_bytes().ends_with(&[lookup.separator]);
+            let mut literal_children = case_variants(first_char, lookup.ignore_case)
+                .filter_map(|first_char| node.children.get(&first_char));
+            let literal_child = literal_children.next();
+            if literal_children.next().is_some()
+                || node.has_wildcard_sibling(first_char, child_after_separator, lookup.named_params)
+            {
+                lookup.trace(|| {
+                    let prefix = &node.prefix;
+                    format!("matched prefix '{prefix}', trying every child for '{first_char}'")
+                });
+                let found = node.search_in_child(remaining_path, current_fallback, lookup);
+                lookup.trace(|| {
+                    let outcome = match found {
+                        found if same_value(found, current_fallback) => outcome(found),
+                        _ => "returning the child's match",
+                    };
+                    format!("done trying children of '{}', {outcome}", node.prefix)
+                });
+                return found;
+            }
+
+            let Some(child) = literal_child else {
+                lookup.trace(|| {
+                    format!("no child for '{first_char}', {}", outcome(current_fallback))
+                });
+                return current_fallback;
+            };
+            lookup.trace(|| {
+                let (prefix, child_prefix) = (&node.prefix, &child.prefix);
+                format!("matched prefix '{prefix}', descending into '{child_prefix}'")
+            });
+            node = child;
+            path = remaining_path;
+            after_separator = child_after_separator;
+            fallback = current_fallback;
+        }
     }
 
-    #[test]
-    fn test_wildcard_removal() {
-        let mut trie = Trie::new();
-        trie.insert("/api/*", "handler");
+    /// Checks whether `get` would find a value, stopping at the first applicable wildcard
+    fn matches_any<'a>(&'a self, path: &[u8], lookup: &mut Lookup<'a, '_, T, S>) -> bool {
+        let mut node = self;
+        let mut path = path;
+        let mut after_separator = false;
 
-        assert_eq!(trie.get("/api/users"), Some(&"handler"));
-        assert_eq!(trie.remove("/api/*"), Some("handler"));
-        assert_eq!(trie.get("/api/users"), None);
-    }
+        loop {
+            if node
+                .find_segment_wildcard(0, after_separator, lookup.separator, lookup.named_params)
+                .is_some()
+            {
+                return node
+                    .match_from(0, path, after_separator, None, lookup)
+                    .is_some();
+            }
 
-    #[test]
-    fn test_root_path() {
-        let mut trie = Trie::new();
-        trie.insert("/", "root_handler");
-        assert_eq!(trie.get("/"), Some(&"root_handler"));
-    }
+            let Some(remaining_path) = lookup.strip_glob(&node.prefix, path) else {
+                return false;
+            };
 
-    #[test]
-    fn test_root_wildcard() {
-        let mut trie = Trie::new();
-        trie.insert("/*", "root_handler");
-        assert_eq!(trie.get("/"), Some(&"root_handler"));
-    }
+            // A lookup always falls back to the innermost wildcard it passed
+            if lookup.wildcard_slot(node, remaining_path).is_some() {
+                return true;
+            }
+            if remaining_path.is_empty() {
+                return node.exact_value.is_some();
+            }
 
-    #[test]
-    fn test_empty_path() {
-        let mut trie = Trie::new();
-        trie.insert("", "empty_handler");
-        assert_eq!(trie.get(""), Some(&"empty_handler"));
+            let Some(first_char) = first_char(remaining_path) else {
+                return false;
+            };
+            let child_after_separator = node.prefix.as_bytes().ends_with(&[lookup.separator]);
+            if node.has_wildcard_sibling(first_char, child_after_separator, lookup.named_params) {
+                return node.search_in_child(remaining_path, None, lookup).is_some();
+            }
+
+            let Some(child) = node.children.get(&first_char) else {
+                return false;
+            };
+            node = child;
+            path = remaining_path;
+            after_separator = child_after_separator;
+        }
     }
 
-    #[test]
-    fn test_common_prefix() {
-        let mut trie = Trie::new();
-        trie.insert("long_prefix_one", "one");
-        trie.insert("long_prefix_two", "two");
-        trie.insert("long_prefix_three", "three");
+    /// Matches this node's prefix from byte `offset` onward against `path`
+    fn match_from<'a>(
+        &'a self,
+        offset: usize,
+        path: &[u8],
+        after_separator: bool,
+        fallback: Option<&'a T>,
+        lookup: &mut Lookup<'a, '_, T, S>,
+    ) -> Option<&'a T> {
+        // Compare literally up to the next single-segment wildcard, if any
+        let wildcard = self.find_segment_wildcard(
+            offset,
+            after_separator,
+            lookup.separator,
+            lookup.named_params,
+        );
+        let literal_end = wildcard.as_ref().map_or(self.prefix.len(), |w| w.start);
+        let literal = &self.prefix[offset..literal_end];
+        let Some(remaining_path) = lookup.strip_glob(literal, path) else {
+            // Partial match - return original fallback, not our wildcard
+            return fallback;
+        };
+
+        let Some(wildcard) = wildcard else {
+            // Update fallback if we have a wildcard at this level
+            let current_fallback = lookup.wildcard_slot(self, remaining_path).or(fallback);
+
+            return if remaining_path.is_empty() {
+                // Exact match at this node
+                self.resolve(current_fallback, lookup)
+            } else {
+                // Continue searching in children
+                self.search_in_child(remaining_path, current_fallback, lookup)
+            };
+        };
+
+        // A literal `*` in the path takes precedence over the wildcard
+        let is_parameter = self.prefix.as_bytes()[wildcard.start] == b':';
+        if let Some(rest) = remaining_path.strip_prefix(b"*").filter(|_| !is_parameter) {
+            let found = self.match_from(wildcard.end, rest, false, fallback, lookup);
+            if !same_value(found, fallback) {
+                return found;
+            }
+        }
+
+        // Otherwise the wildcard consumes exactly one non-empty segment
+        let segment_length = remaining_path
+            .iter()
+            .position(|&b| b == lookup.separator)
+            .unwrap_or(remaining_path.len());
+        if segment_length == 0 {
+            return fallback;
+        }
+        let remaining_path = &remaining_path[segment_length..];
+
+        if wildcard.end < self.prefix.len() {
+            self.match_from(wildcard.end, remaining_path, false, fallback, lookup)
+        } else if is_parameter {
+            self.match_after_parameter(remaining_path, fallback, lookup)
+        } else {
+            // The wildcard ends this node's prefix, so it must be followed by a separator:
+            // only the wildcard slot (`/*/*`) and children (`/*/...`) can apply
+            let current_fallback = lookup.slot(self, true).or(fallback);
+            if remaining_path.is_empty() {
+                current_fallback
+            } else {
+                self.search_in_child(remaining_path, current_fallback, lookup)
+            }
+        }
+    }
+
+    /// Finds the first single-segment wildcard at or after `offset`, returning its byte range
+    ///
+    /// Such a wildcard is preceded by the separator, and is either a `*` that forms a whole
+    /// segment or a `:name` parameter running to the end of the segment. Either can end this
+    /// node's prefix, in which case the continuation decides whether it can match (and a
+    /// parameter's name may continue in a child). A `:` is literal unless `named_params` is set.
+    fn find_segment_wildcard(
+        &self,
+        offset: usize,
+        after_separator: bool,
+        separator: u8,
+        named_params: bool,
+    ) -> Option<Range<usize>> {
+        let bytes = self.prefix.as_bytes();
+        (offset..bytes.len()).find_map(|index| {
+            let preceded = if index == 0 {
+                after_separator
+            } else {
+                bytes[index - 1] == separator
+            };
+            match bytes[index] {
+                _ if !preceded => None,
+                b'*' if bytes.get(index + 1).is_none_or(|&b| b == separator) => {
+                    Some(index..index + 1)
+                }
+                b':' if named_params => {
+                    let name_length = bytes[index..].iter().position(|&b| b == separator);
+                    Some(index..name_length.map_or(bytes.len(), |length| index + length))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Matches `path` after a parameter that ends this node's prefix
+    ///
+    /// Children starting with the separator continue the route after the parameter. Any other
+    /// child holds the rest of a longer name sharing this one's start, as `:idx` does next to
+    /// `:id`, and is only tried if this node's own routes don't match.
+    fn match_after_parameter<'a>(
+        &'a self,
+        path: &[u8],
+        fallback: Option<&'a T>,
+        lookup: &mut Lookup<'a, '_, T, S>,
+    ) -> Option<&'a T> {
+        let current_fallback = lookup.slot(self, true).or(fallback);
+        let found = if path.is_empty() {
+            self.resolve(current_fallback, lookup)
+        } else {
+            self.search_in_child(path, current_fallback, lookup)
+        };
+        if !same_value(found, current_fallback) {
+            return found;
+        }
+
+        for (&first_char, child) in &self.children {
+            if first_char == char::from(lookup.separator) {
+                continue;
+            }
+            lookup.backtrack_to(self);
+            lookup.visit(child);
+            let name_length = child.prefix.bytes().position(|b| b == lookup.separator);
+            let continued = match name_length {
+                Some(name_length) => child.match_from(name_length, path, false, fallback, lookup),
+                None => child.match_after_parameter(path, fallback, lookup),
+            };
+            if !same_value(continued, fallback) {
+                return continued;
+            }
+        }
+        found
+    }
+
+    /// Returns whether a child other than the one for `first_char` starts with a wildcard,
+    /// which a lookup must try as well
+    ///
+    /// Single-segment wildcards only apply when this node's prefix ends with the separator,
+    /// as `after_separator` tells, and `:` only starts one if named parameters are enabled.
+    fn has_wildcard_sibling(
+        &self,
+        first_char: char,
+        after_separator: bool,
+        named_params: bool,
+    ) -> bool {
+        let has_child = |wildcard| wildcard != first_char && self.children.contains_key(&wildcard);
+        has_child(CHAR_WILDCARD)
+            || after_separator && segment_wildcards(named_params).any(has_child)
+    }
+
+    /// Picks between the exact value and the applicable wildcard for a path ending here
+    fn resolve<'a>(
+        &'a self,
+        wildcard: Option<&'a T>,
+        lookup: &Lookup<'a, '_, T, S>,
+    ) -> Option<&'a T> {
+        let exact = lookup.slot(self, false);
+        match lookup.policy {
+            MatchPolicy::ExactFirst => exact.or(wildcard),
+            MatchPolicy::WildcardFirst => wildcard.or(exact),
+        }
+    }
+
+    /// Returns whether this node's wildcard route covers `remaining_path`, the rest of a path
+    /// after the node's prefix
+    ///
+    /// A wildcard route only covers whole segments below its path, so `/files/*` applies to
+    /// `/files` and `/files/a` but not `/filesx`. The root catch-all covers every path.
+    fn wildcard_covers(&self, remaining_path: &[u8], separator: u8) -> bool {
+        self.prefix.is_empty() || remaining_path.first().is_none_or(|&b| b == separator)
+    }
+
+    /// Returns the nodes whose full path is a prefix of `path`, root first, each with the rest
+    /// of the path after it, along with the exact value for `path` if a node matches it entirely
+    fn match_chain<'p>(&self, path: &'p str) -> (Vec<(&Self, &'p str)>, Option<&T>) {
+        let mut chain = Vec::new();
+        let mut node = self;
+        let mut path = path;
+
+        loop {
+            let Some(remaining_path) = path.strip_prefix(node.prefix.as_str()) else {
+                return (chain, None); // Partial match - this node's values don't apply
+            };
+            chain.push((node, remaining_path));
+
+            path = remaining_path;
+            let Some(first_char) = path.chars().next() else {
+                return (chain, node.exact_value.as_ref());
+            };
+            match node.children.get(&first_char) {
+                Some(child) => node = child,
+                None => return (chain, None),
+            }
+        }
+    }
+
+    /// Collects the exact values of every node whose full path is a prefix of `path`
+    ///
+    /// Each value comes with the length of its node's full path, so `path[..length]` is its key.
+    fn prefix_values(&self, path: &str) -> Vec<(usize, &T)> {
+        let mut values = Vec::new();
+        let mut node = self;
+        let mut path = path;
+        let mut length = 0;
+
+        loop {
+            let common_length = node.common_prefix_len(path);
+            if common_length != node.prefix.len() {
+                break; // Node diverges from the path
+            }
+            length += common_length;
+            values.extend(node.exact_value.as_ref().map(|value| (length, value)));
+
+            path = &path[common_length..];
+            let Some(first_char) = path.chars().next() else {
+                break;
+            };
+            match node.children.get(&first_char) {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+        values
+    }
+
+    /// Returns the exact value of the deepest node whose full path is a prefix of `path`
+    fn closest_value(&self, path: &str) -> Option<&T> {
+        let mut closest = None;
+        let mut node = self;
+        let mut path = path;
+
+        loop {
+            let Some(remaining_path) = path.strip_prefix(node.prefix.as_str()) else {
+                return closest; // Node diverges from the path
+            };
+            closest = node.exact_value.as_ref().or(closest);
+
+            path = remaining_path;
+            let Some(first_char) = path.chars().next() else {
+                return closest;
+            };
+            match node.children.get(&first_char) {
+                Some(child) => node = child,
+                None => return closest,
+            }
+        }
+    }
+
+    /// Returns the length in bytes of the longest prefix of `path` shared with a stored path
+    fn shared_prefix_len(&self, path: &str) -> usize {
+        let mut shared = 0;
+        let mut node = self;
+        let mut path = path;
+
+        loop {
+            let common_length = node.common_prefix_len(path);
+            shared += common_length;
+            if common_length != node.prefix.len() {
+                return shared; // Diverged inside this node
+            }
+
+            path = &path[common_length..];
+            let Some(first_char) = path.chars().next() else {
+                return shared;
+            };
+            match node.children.get(&first_char) {
+                Some(child) => node = child,
+                None => return shared,
+            }
+        }
+    }
+
+    /// Returns the child node for the remaining path, creating it if needed
+    fn node_in_child(&mut self, remaining_path: &str) -> &mut RadixNode<T, S> {
+        let first_char = remaining_path.chars().next().unwrap();
+        self.children
+            .get_or_insert_with(first_char, || {
+                Child::new(RadixNode::new(remaining_path.to_string()))
+            })
+            .node_mut(remaining_path)
+    }
+
+    /// Searches for a value in child nodes
+    ///
+    /// The literal child is tried first, followed by the one in the other ASCII case when
+    /// ignoring case. If neither has a value of its own for the path, the child starting with
+    /// `?` is tried next, then those starting with a single-segment wildcard (`*`, then
+    /// `:name`). When several of these wildcard children match, the one whose
+    /// matched route has the highest priority wins, and the order above breaks ties.
+    ///
+    /// `fallback` must already include this node's own wildcard, since it is returned as is
+    /// when no child matches.
+    fn search_in_child<'a>(
+        &'a self,
+        remaining_path: &[u8],
+        fallback: Option<&'a T>,
+        lookup: &mut Lookup<'a, '_, T, S>,
+    ) -> Option<&'a T> {
+        let Some(first_char) = first_char(remaining_path) else {
+            return fallback;
+        };
+        let after_separator = self.prefix.as_bytes().ends_with(&[lookup.separator]);
+
+        for first_char in case_variants(first_char, lookup.ignore_case) {
+            let Some(child) = self.children.get(&first_char) else {
+                continue;
+            };
+            lookup.backtrack_to(self);
+            let found = child.get_with_fallback(remaining_path, after_separator, fallback, lookup);
+            if !same_value(found, fallback) {
+                return found;
+            }
+        }
+        // The literal child for a path starting with `?`, `*` or `:` was already tried
+        let named_params = lookup.named_params;
+        let wildcard_children = || {
+            let segment_wildcards =
+                segment_wildcards(named_params).filter(move |_| after_separator);
+            core::iter::once(CHAR_WILDCARD)
+                .chain(segment_wildcards)
+                .filter(move |&wildcard| wildcard != first_char)
+                .filter_map(|wildcard| self.children.get(&wildcard))
+        };
+
+        // Equally specific wildcard children compete by priority, which takes probing each
+        // with a trail to find the slot its match came from. Without any priorities set, the
+        // fixed order decides, unless the competing routes are wanted for `get_strict`.
+        let mut best = None;
+        let compete = lookup.prioritized || lookup.conflicts.is_some();
+        if compete && wildcard_children().nth(1).is_some() {
+            let mut best_priority = i32::MIN;
+            let mut candidates = Vec::new();
+            let mut trail = Vec::new();
+            for child in wildcard_children() {
+                trail.clear();
+                let mut probe = Lookup {
+                    policy: lookup.policy,
+                    separator: lookup.separator,
+                    trail: Some(&mut trail),
+                    ignore_case: lookup.ignore_case,
+                    trace: None,
+                    conflicts: None,
+                    accept: lookup.accept,
+                    named_params: lookup.named_params,
+                    prioritized: lookup.prioritized,
+                };
+                let found =
+                    child.get_with_fallback(remaining_path, after_separator, fallback, &mut probe);
+                let Some(value) = found.filter(|_| !same_value(found, fallback)) else {
+                    continue;
+                };
+                let priority = trail
+                    .iter()
+                    .find_map(|node| node.priority_of(value))
+                    .expect("matched node is on the lookup trail");
+                if lookup.conflicts.is_some() {
+                    candidates.push((priority, lookup.probe_key(self, &trail, value)));
+                }
+                if best.is_none() || priority > best_priority {
+                    best = Some(&**child);
+                    best_priority = priority;
+                }
+            }
+            if best.is_none() {
+                return fallback;
+            }
+            if let Some(conflicts) = lookup.conflicts.as_deref_mut().filter(|c| c.is_empty()) {
+                let tied = candidates
+                    .into_iter()
+                    .filter(|(priority, _)| *priority == best_priority)
+                    .map(|(_, key)| key);
+                conflicts.extend(tied);
+                if conflicts.len() == 1 {
+                    conflicts.clear();
+                }
+            }
+        }
+
+        for child in wildcard_children() {
+            if best.is_some_and(|best| !core::ptr::eq(best, &**child)) {
+                continue;
+            }
+            // Matched again with the lookup itself, so its trail leads to the chosen route
+            lookup.backtrack_to(self);
+            let found = child.get_with_fallback(remaining_path, after_separator, fallback, lookup);
+            if !same_value(found, fallback) {
+                return found;
+            }
+        }
+        fallback
+    }
+
+    /// Transforms every value in this subtree, keeping the node structure as is
+    ///
+    /// The nodes can move to another storage, e.g. behind `Arc` for a `SnapshotTrie`.
+    fn map<U, S2: Storage>(self, f: &mut impl FnMut(T) -> U) -> RadixNode<U, S2> {
+        RadixNode {
+            prefix: self.prefix,
+            children: self
+                .children
+                .into_iter()
+                .map(|(first_char, child)| (first_char, Child::new(child.into_inner().map(f))))
+                .collect(),
+            exact_value: self.exact_value.map(&mut *f),
+            wildcard_value: self.wildcard_value.map(f),
+            exact_priority: self.exact_priority,
+            wildcard_priority: self.wildcard_priority,
+        }
+    }
+
+    /// Splits this node at the given byte position to accommodate path divergence
+    ///
+    /// The position must fall on a character boundary, as returned by `common_prefix_len`.
+    fn split_at(&mut self, split_position: usize) {
+        if split_position >= self.prefix.len() {
+            return;
+        }
+        debug_assert!(self.prefix.is_char_boundary(split_position));
+
+        // Create new child with the suffix
+        let suffix = self.prefix.split_off(split_position);
+        let first_char = suffix.chars().next().unwrap();
+        let mut new_child = RadixNode::new(suffix);
+
+        // Move our data to the new child
+        new_child.children = core::mem::take(&mut self.children);
+        new_child.exact_value = self.exact_value.take();
+        new_child.wildcard_value = self.wildcard_value.take();
+        new_child.exact_priority = core::mem::take(&mut self.exact_priority);
+        new_child.wildcard_priority = core::mem::take(&mut self.wildcard_priority);
+
+        // Add the new child
+        self.children.insert(first_char, Child::new(new_child));
+    }
+
+    /// Builds a node with the given prefix and the subtree holding `routes` below it
+    ///
+    /// `routes` are `(path, is_wildcard)` pairs, sorted and without duplicates, whose first
+    /// `offset` bytes are the full path of this node. `values` yields their values in the same
+    /// order. Every prefix and child map is allocated once at its final size, as no node is
+    /// ever split.
+    fn from_sorted(
+        prefix: String,
+        routes: &[(String, bool)],
+        offset: usize,
+        values: &mut impl Iterator<Item = T>,
+    ) -> Self {
+        let mut node = RadixNode::new(prefix);
+        let mut rest = routes;
+
+        // Count the children first, so they're allocated once at their final size
+        {
+            let mut child_count = 0;
+            let mut previous = None;
+            for first_char in routes
+                .iter()
+                .filter_map(|(path, _)| path[offset..].chars().next())
+            {
+                if previous != Some(first_char) {
+                    child_count += 1;
+                    previous = Some(first_char);
+                }
+            }
+            node.children.reserve(child_count);
+        }
+
+        while let Some(((path, is_wildcard), _)) = rest.split_first() {
+            let remaining_path = &path[offset..];
+            let Some(first_char) = remaining_path.chars().next() else {
+                // Routes ending at this node sort before the ones below it
+                node.store_value(values.next().unwrap(), *is_wildcard);
+                rest = &rest[1..];
+                continue;
+            };
+
+            // Routes sharing the first character form one child, whose prefix is the part
+            // they all have in common (that of the first and last, since they're sorted)
+            let group_length = rest
+                .iter()
+                .position(|(path, _)| !path[offset..].starts_with(first_char))
+                .unwrap_or(rest.len());
+            let (group, others) = rest.split_at(group_length);
+            let last_path = &group[group_length - 1].0[offset..];
+            let common_length = common_prefix_len(remaining_path, last_path);
+
+            let child = RadixNode::from_sorted(
+                remaining_path[..common_length].to_string(),
+                group,
+                offset + common_length,
+                values,
+            );
+            node.children.insert(first_char, Child::new(child));
+            rest = others;
+        }
+        node
+    }
+}
+
+/// Precedence between exact and wildcard routes that both match a path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchPolicy {
+    /// Exact routes take precedence over wildcards (the default)
+    #[default]
+    ExactFirst,
+    /// Wildcards take precedence over exact routes, e.g. for a maintenance-mode catch-all
+    WildcardFirst,
+}
+
+/// Settings and state for a single lookup through the trie
+struct Lookup<'a, 't, T, S: Storage = Owned> {
+    /// Precedence between exact and wildcard matches
+    policy: MatchPolicy,
+    /// Separator between path segments
+    separator: u8,
+    /// Nodes from the root to the node being visited, only recorded when the key of the
+    /// matched route is needed
+    trail: Option<&'t mut Vec<&'a RadixNode<T, S>>>,
+    /// Whether literal parts of routes match the path regardless of ASCII case
+    ignore_case: bool,
+    /// Callback describing each step of the descent, for [`Trie::get_traced`]
+    trace: Option<&'t mut dyn FnMut(&str)>,
+    /// Keys of the first equally specific wildcard routes found competing for the path, only
+    /// recorded along with the trail, for [`Trie::get_strict`]
+    conflicts: Option<&'t mut Vec<String>>,
+    /// Filter on the stored values, which treats the slots of rejected ones as empty
+    accept: Option<&'t dyn Fn(&T) -> bool>,
+    /// Whether `:name` segments of routes are parameters rather than literal text
+    named_params: bool,
+    /// Whether competing wildcard routes are probed for their priorities
+    prioritized: bool,
+}
+
+impl<'a, 't, T, S: Storage> Lookup<'a, 't, T, S> {
+    /// Creates a lookup that doesn't record its trail
+    fn new(policy: MatchPolicy, separator: char) -> Self {
+        Self {
+            policy,
+            separator: separator as u8,
+            trail: None,
+            ignore_case: false,
+            trace: None,
+            conflicts: None,
+            accept: None,
+            named_params: false,
+            prioritized: false,
+        }
+    }
+
+    /// Returns the value in `node`'s wildcard slot if it covers the rest of the path
+    fn wildcard_slot(&self, node: &'a RadixNode<T, S>, remaining_path: &[u8]) -> Option<&'a T> {
+        node.wildcard_covers(remaining_path, self.separator)
+            .then(|| self.slot(node, true))
+            .flatten()
+    }
+
+    /// Returns the value in one of `node`'s slots, unless the lookup's filter rejects it
+    fn slot(&self, node: &'a RadixNode<T, S>, is_wildcard: bool) -> Option<&'a T> {
+        let value = node.slot(is_wildcard)?;
+        self.accept
+            .is_none_or(|accept| accept(value))
+            .then_some(value)
+    }
+
+    /// Describes a step of the lookup to its trace callback, formatting it only if there is one
+    fn trace(&mut self, message: impl FnOnce() -> String) {
+        if let Some(trace) = &mut self.trace {
+            trace(&message());
+        }
+    }
+
+    /// Strips `pattern` from the start of a byte path, if it matches there
+    ///
+    /// Each `?` in the pattern matches exactly one character other than the separator.
+    fn strip_glob<'p>(&self, pattern: &str, path: &'p [u8]) -> Option<&'p [u8]> {
+        let strip_literal = |path: &'p [u8], literal: &[u8]| {
+            let (head, rest) = path.split_at_checked(literal.len())?;
+            let matches = if self.ignore_case {
+                head.eq_ignore_ascii_case(literal)
+            } else {
+                head == literal
+            };
+            matches.then_some(rest)
+        };
+        let mut pieces = pattern.as_bytes().split(|&b| b == CHAR_WILDCARD as u8);
+        let mut path = strip_literal(path, pieces.next()?)?;
+        for piece in pieces {
+            let c = first_char(path).filter(|&c| c != char::from(self.separator))?;
+            path = strip_literal(&path[c.len_utf8()..], piece)?;
+        }
+        Some(path)
+    }
+
+    /// Records that the lookup descended into `node`
+    fn visit(&mut self, node: &'a RadixNode<T, S>) {
+        if let Some(trail) = &mut self.trail {
+            trail.push(node);
+        }
+    }
+
+    /// Returns the key of the route that a probe of a child of `node` matched with `value`
+    ///
+    /// The probe's trail starts at the child, and the lookup's own trail leads to `node`.
+    fn probe_key(
+        &mut self,
+        node: &RadixNode<T, S>,
+        probe_trail: &[&RadixNode<T, S>],
+        value: &T,
+    ) -> String {
+        self.backtrack_to(node);
+        let (depth, is_wildcard) = matched_slot(probe_trail, value);
+        let ancestors = self.trail.as_deref().map_or(&[][..], Vec::as_slice);
+        let path: String = ancestors
+            .iter()
+            .chain(&probe_trail[..=depth])
+            .map(|node| node.prefix.as_str())
+            .collect();
+        Trie::<T>::format_key(&path, is_wildcard, char::from(self.separator))
+    }
+
+    /// Drops the trail below `node`, before the lookup tries another of its children
+    fn backtrack_to(&mut self, node: &RadixNode<T, S>) {
+        if let Some(trail) = &mut self.trail {
+            let depth = trail
+                .iter()
+                .rposition(|visited| core::ptr::eq(*visited, node));
+            trail.truncate(depth.map_or(0, |depth| depth + 1));
+        }
+    }
+}
+
+/// Returns the length in bytes of the common prefix of two strings
+///
+/// The result always falls on a character boundary of both strings, so it can be used to
+/// slice and split them even when they contain multi-byte characters.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((index, _), _)| index)
+}
+
+/// Decodes the character at the start of a byte path, if it starts with valid UTF-8
+fn first_char(path: &[u8]) -> Option<char> {
+    path.utf8_chunks().next()?.valid().chars().next()
+}
+
+/// Returns `first_char`, followed by its other ASCII case when `ignore_case` is set
+///
+/// These are the keys of the literal children a path starting with `first_char` can descend
+/// into, in order of preference.
+fn case_variants(first_char: char, ignore_case: bool) -> impl Iterator<Item = char> {
+    let swapped = if first_char.is_ascii_lowercase() {
+        first_char.to_ascii_uppercase()
+    } else {
+        first_char.to_ascii_lowercase()
+    };
+    core::iter::once(first_char).chain(Some(swapped).filter(|&c| ignore_case && c != first_char))
+}
+
+/// Returns the position on a lookup trail of the node holding `value`, and whether it's in the
+/// wildcard slot
+///
+/// The matched node and its ancestors are always left at the start of the trail.
+fn matched_slot<T, S: Storage>(trail: &[&RadixNode<T, S>], value: &T) -> (usize, bool) {
+    trail
+        .iter()
+        .enumerate()
+        .find_map(|(depth, node)| {
+            if same_value(node.exact_value.as_ref(), Some(value)) {
+                Some((depth, false))
+            } else if same_value(node.wildcard_value.as_ref(), Some(value)) {
+                Some((depth, true))
+            } else {
+                None
+            }
+        })
+        .expect("matched node is on the lookup trail")
+}
+
+/// Checks whether two lookup results refer to the same stored value
+fn same_value<T>(a: Option<&T>, b: Option<&T>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => core::ptr::eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// A change to the routes of a [`Trie`], reported to its [`Trie::on_change`] callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeEvent<'a> {
+    /// A value was inserted at `path`, replacing an existing value if `replaced` is set
+    Inserted { path: &'a str, replaced: bool },
+    /// The value at `path` was removed
+    Removed { path: &'a str },
+}
+
+/// Boxed change callback registered with [`Trie::on_change`]
+struct ChangeHook(Box<dyn FnMut(ChangeEvent<'_>) + Send + Sync>);
+
+/// A radix trie for efficient path-based routing with wildcard support
+pub struct Trie<T> {
+    /// The root node, which always has an empty prefix
+    root: Child<T>,
+    /// Precedence between exact and wildcard matches in `get`
+    policy: MatchPolicy,
+    /// Whether a missing leading `/` is prepended to paths
+    require_leading_slash: bool,
+    /// Whether paths are lowercased, so they match regardless of case
+    case_insensitive: bool,
+    /// Whether `%XX` escapes in paths are decoded
+    percent_decoding: bool,
+    /// Whether runs of the separator in paths are collapsed into one
+    collapse_slashes: bool,
+    /// Whether `:name` segments of routes are parameters rather than literal text
+    named_params: bool,
+    /// Separator between path segments, which also forms the wildcard suffix
+    separator: char,
+    /// Longest prefix in bytes a single node may hold, if limited
+    max_prefix_len: Option<usize>,
+    /// Whether a route was ever inserted with a nonzero priority, which makes lookups
+    /// compare the priorities of competing wildcard routes
+    prioritized: bool,
+    /// Callback notified after routes are inserted or removed
+    on_change: Option<ChangeHook>,
+}
+
+impl<T> Default for Trie<T> {
+    fn default() -> Self {
+        Self {
+            root: Child::new(RadixNode::new(String::new())),
+            policy: MatchPolicy::default(),
+            require_leading_slash: false,
+            case_insensitive: false,
+            percent_decoding: false,
+            collapse_slashes: false,
+            named_params: false,
+            separator: DEFAULT_SEPARATOR,
+            max_prefix_len: None,
+            prioritized: false,
+            on_change: None,
+        }
+    }
+}
+
+/// Formats the trie as a map of its routes in lexicographic order
+///
+/// Use `pretty_print` (with the `debug` feature) to see the node structure instead.
+impl<T: core::fmt::Debug> core::fmt::Debug for Trie<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter_sorted()).finish()
+    }
+}
+
+/// Compares the routes of two tries along with their priorities, regardless of insertion
+/// order, node shape or settings
+impl<T: PartialEq> PartialEq for Trie<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.root.value_count() == other.root.value_count()
+            && self.prioritized_entries() == other.prioritized_entries()
+    }
+}
+
+impl<T: Eq> Eq for Trie<T> {}
+
+/// Hashes the routes and their priorities in lexicographic path order, consistently with
+/// `PartialEq`
+///
+/// Children aren't stored in any order, so the entries are sorted first, as in
+/// [`Trie::to_sorted_entries`].
+impl<T: core::hash::Hash> core::hash::Hash for Trie<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.prioritized_entries().hash(state);
+    }
+}
+
+/// Absorbs every route of the given tries, later routes replacing earlier ones in the same slot
+///
+/// Routes keep their slot and priority, so a literal `/*` route stored with
+/// [`Trie::insert_exact`] stays exact. Their paths were normalized when they were first
+/// inserted and are stored as they are, and each insertion reports to the change callback.
+impl<T> Extend<Trie<T>> for Trie<T> {
+    fn extend<I: IntoIterator<Item = Trie<T>>>(&mut self, tries: I) {
+        for other in tries {
+            self.prioritized |= other.prioritized;
+            let root = other.root.into_inner();
+            let mut stack = Vec::from([(root.prefix.clone(), root)]);
+            while let Some((path, node)) = stack.pop() {
+                let slots = [
+                    (false, node.exact_value, node.exact_priority),
+                    (true, node.wildcard_value, node.wildcard_priority),
+                ];
+                for (is_wildcard, slot, priority) in slots {
+                    if let Some(value) = slot {
+                        let replaced =
+                            self.node_mut(&path)
+                                .store_prioritized(value, is_wildcard, priority);
+                        let key = Self::format_key(&path, is_wildcard, self.separator);
+                        self.notify(ChangeEvent::Inserted {
+                            path: &key,
+                            replaced: replaced.is_some(),
+                        });
+                    }
+                }
+                for child in node.children.into_values() {
+                    let child_path = [path.as_str(), &child.prefix].concat();
+                    stack.push((child_path, child.into_inner()));
+                }
+            }
+        }
+    }
+}
+
+impl<T> Trie<T> {
+    /// Creates a new empty trie
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new empty trie with the given match precedence
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::{MatchPolicy, Trie};
+    /// let mut trie = Trie::with_policy(MatchPolicy::WildcardFirst);
+    /// trie.insert("/api/*", "maintenance");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get("/api/users"), Some(&"maintenance"));
+    /// ```
+    pub fn with_policy(policy: MatchPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie that prepends a missing leading `/` to paths
+    ///
+    /// When enabled, every method taking a path treats `"api/users"` as `"/api/users"`, so
+    /// routes registered without the slash still match requests. Use [`Trie::insert_strict`]
+    /// to reject such paths instead.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_require_leading_slash(true);
+    /// trie.insert("api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+    /// assert_eq!(trie.get("api/users"), Some(&"users_handler"));
+    /// ```
+    pub fn with_require_leading_slash(require: bool) -> Self {
+        Self {
+            require_leading_slash: require,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie that matches paths regardless of case
+    ///
+    /// When enabled, every method taking a path lowercases it first, so keys are stored and
+    /// yielded lowercased. Only ASCII letters are folded, unless the `unicode-case` feature is
+    /// enabled: every character is then lowercased with its locale-independent Unicode
+    /// mapping, so `É` matches `é`, but `ß` doesn't match `ss`. The parts of a path handed
+    /// back, like the rest from [`Trie::get_with_rest`] or the parameters bound by
+    /// [`Trie::match_route`], keep the case they were given in.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_case_insensitive(true);
+    /// trie.insert("/API/Users", "users_handler");
+    ///
+    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+    /// assert_eq!(trie.get("/Api/USERS"), Some(&"users_handler"));
+    /// ```
+    pub fn with_case_insensitive(insensitive: bool) -> Self {
+        Self {
+            case_insensitive: insensitive,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie that decodes `%XX` escapes in paths
+    ///
+    /// When enabled, every method taking a path decodes it first, so routes registered in
+    /// decoded form match percent-encoded requests, and encoded routes are stored decoded.
+    /// Escapes of the separator (`%2F` for `/`) are kept encoded, so they stay part of their
+    /// segment: `/users/a%2Fb` doesn't match `/users/a/b`, but a single-segment wildcard matches
+    /// `a%2Fb` as a whole. Malformed escapes are kept as is, as are those of a path that
+    /// wouldn't be valid UTF-8 once decoded, except in [`Trie::get`] and [`Trie::get_bytes`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_percent_decoding(true);
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get("/api/%75sers"), Some(&"users_handler"));
+    /// ```
+    pub fn with_percent_decoding(decode: bool) -> Self {
+        Self {
+            percent_decoding: decode,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie that collapses runs of the separator in paths into one
+    ///
+    /// When enabled, every method taking a path collapses it first, so requests from sloppy
+    /// clients like `/api//users` match `/api/users`, and routes are stored collapsed. A path
+    /// of separators only, like `//`, becomes the root `/`, and a trailing run becomes a single
+    /// trailing separator. Encoded separators (`%2F`) are left alone.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_collapse_slashes(true);
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get("/api//users"), Some(&"users_handler"));
+    /// assert_eq!(trie.get("//api/users"), Some(&"users_handler"));
+    /// ```
+    pub fn with_collapse_slashes(collapse: bool) -> Self {
+        Self {
+            collapse_slashes: collapse,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie that treats `:name` segments as named parameters when
+    /// `enabled` is true
+    ///
+    /// Named parameters are disabled by default, so `:` is literal text and a route like
+    /// `/ns/:meta` only matches itself. Enabling them makes a route segment starting with `:`
+    /// match any single segment, like `*`, and bind it to the name in [`Trie::match_route`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_named_params(true);
+    /// trie.insert("/users/:id", "user_handler");
+    ///
+    /// assert_eq!(trie.get("/users/42"), Some(&"user_handler"));
+    /// assert_eq!(trie.get("/users/:id"), Some(&"user_handler"));
+    /// ```
+    pub fn with_named_params(enabled: bool) -> Self {
+        Self {
+            named_params: enabled,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie whose nodes hold prefixes of at most `max` bytes
+    ///
+    /// A radix trie normally stores a key with no branching in a single node, so one huge
+    /// route makes every split, merge and comparison on that node proportionally expensive.
+    /// With a limit, such keys are chunked across a chain of nodes instead. Lookups and
+    /// iteration behave exactly as without it. A smaller limit makes the trie deeper, so it
+    /// should stay well above typical segment lengths. Use [`Trie::set_max_prefix_len`] to
+    /// combine the limit with other options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_max_prefix_len(64);
+    /// let long_key = format!("/blobs/{}", "a".repeat(1000));
+    /// trie.insert(&long_key, "blob");
+    ///
+    /// assert_eq!(trie.get(&long_key), Some(&"blob"));
+    /// assert!(trie.node_count() > 1000 / 64);
+    /// ```
+    pub fn with_max_prefix_len(max: usize) -> Self {
+        assert!(max > 0, "maximum prefix length must be at least 1");
+        Self {
+            max_prefix_len: Some(max),
+            ..Self::default()
+        }
+    }
+
+    /// Limits node prefixes to `max` bytes, chunking the prefixes of existing nodes that are
+    /// longer
+    ///
+    /// Works like [`Trie::with_max_prefix_len`], on a trie created with any other options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_separator('.');
+    /// trie.set_max_prefix_len(64);
+    /// let long_key = format!("com.{}", "a".repeat(1000));
+    /// trie.insert(&long_key, "blob");
+    ///
+    /// assert_eq!(trie.get(&long_key), Some(&"blob"));
+    /// assert!(trie.node_count() > 1000 / 64);
+    /// ```
+    pub fn set_max_prefix_len(&mut self, max: usize) {
+        assert!(max > 0, "maximum prefix length must be at least 1");
+        self.max_prefix_len = Some(max);
+        self.root.chunk_subtree(max);
+    }
+
+    /// Creates a new empty trie whose paths are split into segments by `separator`
+    ///
+    /// Wildcard routes then end with the separator followed by `*` (e.g. `.*` for dotted
+    /// keys), and single-segment wildcards span the text between two separators.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `separator` isn't an ASCII character, or is `*` itself.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_separator('.');
+    /// trie.insert("com.example.*", "example");
+    /// trie.insert("com.*.app", "any_app");
+    ///
+    /// assert_eq!(trie.get("com.example.app"), Some(&"example"));
+    /// assert_eq!(trie.get("com.other.app"), Some(&"any_app"));
+    /// ```
+    pub fn with_separator(separator: char) -> Self {
+        assert!(
+            separator.is_ascii() && separator != '*',
+            "separator must be an ASCII character other than `*`"
+        );
+        Self {
+            separator,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new empty trie with room for `capacity` children below the root
+    ///
+    /// This is only a hint: the children of the root are the routes' distinct first
+    /// characters, so pre-sizing helps wide tables of keys such as `com.example` or paths
+    /// without a leading slash, rather than tables where every path starts with `/`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_capacity(26);
+    /// for key in ["alpha", "bravo", "charlie"] {
+    ///     trie.insert(key, key.len());
+    /// }
+    /// assert_eq!(trie.get("bravo"), Some(&5));
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let root = RadixNode {
+            children: Children::with_capacity(capacity),
+            ..RadixNode::new(String::new())
+        };
+        Self {
+            root: Child::new(root),
+            ..Self::default()
+        }
+    }
+
+    /// Reserves room for at least `additional` more children below the root
+    ///
+    /// A hint like [`Trie::with_capacity`], to call before a bulk load into an existing trie.
+    pub fn reserve(&mut self, additional: usize) {
+        self.root.children.reserve(additional);
+    }
+
+    /// Returns the separator between path segments
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// Returns the match precedence used by [`Trie::get`]
+    pub fn policy(&self) -> MatchPolicy {
+        self.policy
+    }
+
+    /// Registers a callback that is notified after every route added to or removed from the
+    /// trie
+    ///
+    /// Only one callback is kept; registering another replaces it. Events fire after the change
+    /// is made, and only for slots that actually changed:
+    ///
+    /// - `Inserted` for each route stored by the `insert` family ([`Trie::insert`],
+    ///   [`Trie::insert_exact`], [`Trie::try_insert`], [`Trie::insert_with`], ...),
+    ///   [`Extend`], and [`Trie::get_or_insert_with`] or [`Trie::entry`] when they fill an
+    ///   empty slot
+    /// - `Removed` for the route taken by [`Trie::remove`], [`Trie::remove_if`],
+    ///   [`Trie::remove_entry`], [`Trie::remove_match`] and [`Trie::unset`], reporting the path
+    ///   passed to them
+    /// - `Removed` for each route taken by [`Trie::remove_prefix`], [`Trie::retain_prefix`],
+    ///   [`Trie::split_off`] and [`Trie::drain`], reporting its key as [`Trie::iter`] would
+    ///
+    /// Changing a stored value in place, through [`Trie::get_all_matches_mut`], [`Trie::update`],
+    /// [`Trie::values_mut`] or [`Trie::entry_slot`], isn't reported.
+    ///
+    /// The callback must be `Send` and `Sync`, since the trie holding it may be shared between
+    /// threads, so state it updates goes behind a `Mutex` or an atomic, as below.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::{ChangeEvent, Trie};
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let route_count = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&route_count);
+    ///
+    /// let mut trie = Trie::new();
+    /// trie.on_change(move |event| match event {
+    ///     ChangeEvent::Inserted { replaced: false, .. } => {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    ///     ChangeEvent::Removed { .. } => {
+    ///         counter.fetch_sub(1, Ordering::Relaxed);
+    ///     }
+    ///     ChangeEvent::Inserted { replaced: true, .. } => {}
+    /// });
+    ///
+    /// trie.insert("/api/users", "users_handler");
+    /// trie.insert("/api/users", "users_handler_v2");
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.remove("/api/*");
+    /// assert_eq!(route_count.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn on_change(&mut self, f: impl FnMut(ChangeEvent<'_>) + Send + Sync + 'static) {
+        self.on_change = Some(ChangeHook(Box::new(f)));
+    }
+
+    /// Notifies the change callback, if one is registered
+    fn notify(&mut self, event: ChangeEvent<'_>) {
+        if let Some(ChangeHook(f)) = &mut self.on_change {
+            f(event);
+        }
+    }
+
+    /// Reports every route of a detached subtree as removed, `base` being the full path above
+    /// its root
+    fn notify_removed(&mut self, base: &str, subtree: &RadixNode<T>) {
+        if self.on_change.is_none() {
+            return;
+        }
+        let mut stack = Vec::from([([base, &subtree.prefix].concat(), subtree)]);
+        while let Some((path, node)) = stack.pop() {
+            for child in node.children.values() {
+                stack.push(([path.as_str(), &child.prefix].concat(), child));
+            }
+            for (is_wildcard, slot) in [(false, &node.exact_value), (true, &node.wildcard_value)] {
+                if slot.is_some() {
+                    let key = Self::format_key(&path, is_wildcard, self.separator);
+                    self.notify(ChangeEvent::Removed { path: &key });
+                }
+            }
+        }
+    }
+
+    /// Inserts a value at the given path
+    ///
+    /// Paths ending with `/*` are treated as wildcard routes that match any sub-path.
+    /// A `*` segment elsewhere in the path (e.g. `/users/*/posts`) matches exactly one
+    /// segment in [`Trie::get`].
+    ///
+    /// # Root routes
+    ///
+    /// The shortest paths each land in a fixed slot:
+    ///
+    /// | Path   | Slot                        | Matches                                       |
+    /// |--------|-----------------------------|-----------------------------------------------|
+    /// | `""`   | exact value of the root     | only `""`                                     |
+    /// | `"/"`  | exact value of the `/` node | only `"/"`                                    |
+    /// | `"/*"` | wildcard value of the root  | every path without a more specific route      |
+    /// | `"*"`  | same as `"/*"`              | same as `"/*"`                                |
+    ///
+    /// The root catch-all also matches `""`, `"/"` and paths without a leading `/`.
+    ///
+    /// A bare `"*"` is normalized to the root catch-all `"/*"`, and is reported as `"/*"` when
+    /// iterating. A path ending in an escaped `/\\*` is the literal route ending in `/*`, the
+    /// key iteration reports for a route stored with [`Trie::insert_exact`].
+    ///
+    /// # Exact and wildcard routes at the same path
+    ///
+    /// The wildcard route `/files/*` is stored in the wildcard slot of the `/files` node, next
+    /// to the exact route `/files`, so both can be registered independently:
+    ///
+    /// | Path         | Resolves to                                                          |
+    /// |--------------|----------------------------------------------------------------------|
+    /// | `/files`     | the exact route, or the wildcard with [`MatchPolicy::WildcardFirst`] |
+    /// | `/files/`    | the wildcard, capturing an empty rest                                |
+    /// | `/files/a/b` | the wildcard, capturing `a/b`                                        |
+    /// | `/filesx`    | neither, as the wildcard only covers whole segments below `/files`   |
+    ///
+    /// Without the exact route, `/files` itself falls back to the wildcard too.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/*/posts", "posts_handler");
+    /// ```
+    pub fn insert(&mut self, path: &str, value: T) {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        self.insert_parsed(&path, &clean_path, is_wildcard, value);
+    }
+
+    /// Inserts an exact route at the given path, even if it ends with `/*`
+    ///
+    /// The path is stored verbatim, so a literal `/*` suffix is part of the route rather than
+    /// a catch-all.
+    ///
+    /// Iteration reports such a route with the `*` escaped, as `/glob/\\*`, so its key differs
+    /// from the wildcard's. [`Trie::insert`] and [`Trie::remove`] map that key back to the
+    /// literal route.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert_exact("/glob/*", "literal");
+    ///
+    /// assert_eq!(trie.get_exact("/glob/*"), Some(&"literal"));
+    /// assert_eq!(trie.get("/glob/anything"), None);
+    /// ```
+    pub fn insert_exact(&mut self, path: &str, value: T) {
+        let path = self.normalize(path);
+        self.insert_parsed(&path, &path, false, value);
+    }
+
+    /// Inserts a wildcard route for everything below the given prefix
+    ///
+    /// Equivalent to inserting `{prefix}/*`, without any suffix parsing: the prefix is stored
+    /// verbatim, even if it ends with `/*` itself.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert_wildcard("/static", "static_files");
+    /// assert_eq!(trie.get("/static/app.css"), Some(&"static_files"));
+    /// ```
+    pub fn insert_wildcard(&mut self, prefix: &str, value: T) {
+        let prefix = self.normalize(prefix);
+        let path = Self::format_key(&prefix, true, self.separator);
+        self.insert_parsed(&path, &prefix, true, value);
+    }
+
+    /// Stores a value in an explicit slot and notifies the change callback
+    fn insert_parsed(
+        &mut self,
+        path: &str,
+        clean_path: &str,
+        is_wildcard: bool,
+        value: T,
+    ) -> Option<T> {
+        let replaced = self.node_mut(clean_path).store_value(value, is_wildcard);
+        self.notify(ChangeEvent::Inserted {
+            path,
+            replaced: replaced.is_some(),
+        });
+        replaced
+    }
+
+    /// Inserts a value with a priority, which breaks ties between wildcard routes
+    ///
+    /// When the `?`, `*` and `:name` wildcards at the same position all lead to a route for
+    /// a path, [`Trie::get`] returns the one with the highest priority instead of following
+    /// their fixed order. A literal segment still wins over any wildcard, and a deeper route
+    /// over a catch-all above it. Plain [`Trie::insert`] stores priority 0.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_named_params(true);
+    /// trie.insert("/users/*/profile", "any_profile");
+    /// trie.insert_with_priority("/users/:id/profile", "profile_by_id", 10);
+    /// trie.insert("/users/me/profile", "my_profile");
+    ///
+    /// assert_eq!(trie.get("/users/42/profile"), Some(&"profile_by_id"));
+    /// assert_eq!(trie.get("/users/me/profile"), Some(&"my_profile"));
+    /// ```
+    pub fn insert_with_priority(&mut self, path: &str, value: T, priority: i32) {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        self.prioritized |= priority != 0;
+        let replaced = self
+            .node_mut(&clean_path)
+            .store_prioritized(value, is_wildcard, priority);
+        self.notify(ChangeEvent::Inserted {
+            path: &path,
+            replaced: replaced.is_some(),
+        });
+    }
+
+    /// Inserts a value at the given path only if the targeted slot is empty
+    ///
+    /// Targets the same slot as [`Trie::insert`], but never replaces an existing value: the
+    /// rejected value is handed back instead, and the stored one is left in place. Wildcards
+    /// covering the path don't count as occupying it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// assert_eq!(trie.try_insert("/api/users", "users_handler"), Ok(()));
+    /// assert_eq!(trie.try_insert("/api/users", "other"), Err("other"));
+    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+    /// ```
+    pub fn try_insert(&mut self, path: &str, value: T) -> Result<(), T> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let node = self.node_mut(&clean_path);
+        if let Some(previous) = node.store_value(value, is_wildcard) {
+            // Put the original back, handing the rejected value to the caller
+            let rejected = node.store_value(previous, is_wildcard);
+            return Err(rejected.expect("slot was just filled"));
+        }
+        self.notify(ChangeEvent::Inserted {
+            path: &path,
+            replaced: false,
+        });
+        Ok(())
+    }
+
+    /// Inserts every route of a batch, or none of them if any targeted slot is taken
+    ///
+    /// All slots are checked before anything is inserted, so on conflict the trie is left
+    /// exactly as it was. A slot is taken if it already has a value, or if an earlier item of
+    /// the batch targets it. Wildcards covering a path don't count, as with
+    /// [`Trie::try_insert`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the index and value of the first conflicting item.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let batch = vec![
+    ///     ("/api/posts".to_string(), "posts_handler"),
+    ///     ("/api/users".to_string(), "other"),
+    /// ];
+    /// assert_eq!(trie.insert_batch_atomic(batch), Err((1, "other")));
+    /// assert_eq!(trie.get("/api/posts"), None);
+    /// ```
+    pub fn insert_batch_atomic(&mut self, mut items: Vec<(String, T)>) -> Result<(), (usize, T)> {
+        let mut targeted = BTreeSet::new();
+        let conflict = items.iter().position(|(path, _)| {
+            let path = self.normalize(path);
+            let (clean_path, is_wildcard) = self.parse_path(&path);
+            let taken = self
+                .root
+                .find_node(&clean_path)
+                .is_some_and(|node| node.slot(is_wildcard).is_some());
+            taken || !targeted.insert((clean_path.to_string(), is_wildcard))
+        });
+        if let Some(index) = conflict {
+            return Err((index, items.swap_remove(index).1));
+        }
+
+        for (path, value) in items {
+            self.insert(&path, value);
+        }
+        Ok(())
+    }
+
+    /// Inserts a value at the given path, combining it with the value already stored there
+    ///
+    /// Targets the same slot as [`Trie::insert`]. If the slot holds a value, it's replaced by
+    /// `combine(old, new)`; otherwise `new` is stored as is. Wildcards covering the path don't
+    /// count as occupying it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert_with("/api/users", 3, |old, new| old + new);
+    /// trie.insert_with("/api/users", 5, |old, new| old + new);
+    ///
+    /// assert_eq!(trie.get("/api/users"), Some(&8));
+    /// ```
+    pub fn insert_with(&mut self, path: &str, value: T, combine: impl FnOnce(T, T) -> T) {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let slot = self.node_mut(&clean_path).slot_mut(is_wildcard);
+        let replaced = slot.is_some();
+        let combined = match slot.take() {
+            Some(old) => combine(old, value),
+            None => value,
+        };
+        *slot = Some(combined);
+        self.notify(ChangeEvent::Inserted {
+            path: &path,
+            replaced,
+        });
+    }
+
+    /// Inserts the same value at each of the given paths
+    ///
+    /// Every path gets its own clone, except the last which takes `value` itself. Each path is
+    /// inserted like with [`Trie::insert`], so wildcard aliases are supported and the change
+    /// callback is notified once per path. Nothing is inserted if `paths` is empty.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert_aliases(&["/login", "/signin", "/auth/*"], "auth_handler");
+    ///
+    /// assert_eq!(trie.get("/signin"), Some(&"auth_handler"));
+    /// assert_eq!(trie.get("/auth/callback"), Some(&"auth_handler"));
+    /// ```
+    pub fn insert_aliases(&mut self, paths: &[&str], value: T)
+    where
+        T: Clone,
+    {
+        let Some((last, others)) = paths.split_last() else {
+            return;
+        };
+        for path in others {
+            self.insert(path, value.clone());
+        }
+        self.insert(last, value);
+    }
+
+    /// Inserts a value at the given path, rejecting paths that don't start with `/`
+    ///
+    /// Unlike [`Trie::insert`], this never normalizes a missing leading slash, regardless of
+    /// [`Trie::with_require_leading_slash`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::{PathError, Trie};
+    /// let mut trie = Trie::new();
+    /// assert_eq!(trie.insert_strict("/api/users", "users"), Ok(()));
+    /// assert_eq!(trie.insert_strict("api/users", "users"), Err(PathError::MissingLeadingSlash));
+    /// ```
+    pub fn insert_strict(&mut self, path: &str, value: T) -> Result<(), PathError> {
+        if !path.starts_with('/') {
+            return Err(PathError::MissingLeadingSlash);
+        }
+        self.insert(path, value);
+        Ok(())
+    }
+
+    /// Checks a route pattern for mistakes that would make it silently misbehave
+    ///
+    /// A valid path has no empty segments, and each `*` is a whole segment: either a
+    /// single-segment wildcard (`/users/*/posts`) or the trailing catch-all (`/api/*`). A bare
+    /// `*` is the root catch-all. Paths are checked with the default `/` separator, and a
+    /// missing leading `/` is not reported.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::{PathError, Trie};
+    /// assert_eq!(Trie::<()>::validate_path("/users/*/posts/*"), Ok(()));
+    /// assert_eq!(Trie::<()>::validate_path("/api//users"), Err(PathError::EmptySegment));
+    /// assert_eq!(Trie::<()>::validate_path("/api*"), Err(PathError::WildcardNotAfterSeparator));
+    /// assert_eq!(Trie::<()>::validate_path("/api/*.json"), Err(PathError::MisplacedWildcard));
+    /// ```
+    pub fn validate_path(path: &str) -> Result<(), PathError> {
+        if path.contains("//") {
+            return Err(PathError::EmptySegment);
+        }
+
+        for (index, _) in path.match_indices('*') {
+            if !path[..index].is_empty() && !path[..index].ends_with(DEFAULT_SEPARATOR) {
+                return Err(PathError::WildcardNotAfterSeparator);
+            }
+            let rest = &path[index + 1..];
+            if !rest.is_empty() && !rest.starts_with(DEFAULT_SEPARATOR) {
+                return Err(PathError::MisplacedWildcard);
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts a value at the given raw byte path
+    ///
+    /// Stored routes are always UTF-8, so this is the one place a byte path is validated.
+    /// Non-UTF-8 request paths can still be routed through wildcards with [`Trie::get_bytes`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::{PathError, Trie};
+    /// let mut trie = Trie::new();
+    /// assert_eq!(trie.insert_bytes(b"/api/users", "users"), Ok(()));
+    /// assert_eq!(trie.get("/api/users"), Some(&"users"));
+    /// assert_eq!(trie.insert_bytes(b"/api/\xff", "invalid"), Err(PathError::InvalidUtf8));
+    /// ```
+    pub fn insert_bytes(&mut self, path: &[u8], value: T) -> Result<(), PathError> {
+        let path = core::str::from_utf8(path).map_err(|_| PathError::InvalidUtf8)?;
+        self.insert(path, value);
+        Ok(())
+    }
+
+    /// Inserts many values at once, sorting them by path first
+    ///
+    /// Inserting in lexicographic order keeps related paths together, which reduces node
+    /// splits while building large tables. An empty trie without a change callback is built
+    /// directly from the sorted paths instead, allocating each node's prefix only once. The
+    /// result is the same as inserting each item in order with [`Trie::insert`]: if a path
+    /// appears more than once, the last value wins.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert_many([
+    ///     ("/api/v1/users".to_string(), "users"),
+    ///     ("/api/*".to_string(), "api_fallback"),
+    /// ]);
+    ///
+    /// assert_eq!(trie.get("/api/v1/users"), Some(&"users"));
+    /// assert_eq!(trie.get("/api/v1/posts"), Some(&"api_fallback"));
+    /// ```
+    pub fn insert_many<I: IntoIterator<Item = (String, T)>>(&mut self, items: I) {
+        let mut items: Vec<_> = items.into_iter().collect();
+        if self.is_empty() && self.on_change.is_none() {
+            self.build_from(items);
+            return;
+        }
+
+        // Stable sort, so later duplicates still overwrite earlier ones
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (path, value) in items {
+            self.insert(&path, value);
+        }
+    }
+
+    /// Replaces the nodes of the trie with ones built from `items`, without splitting nodes
+    fn build_from(&mut self, items: Vec<(String, T)>) {
+        let mut routes: Vec<_> = items
+            .into_iter()
+            .map(|(path, value)| {
+                let normalized = match self.normalize(&path) {
+                    Cow::Owned(normalized) => Some(normalized),
+                    Cow::Borrowed(_) => None,
+                };
+                let mut path = normalized.unwrap_or(path);
+                let (clean_path, is_wildcard) = self.parse_path(&path);
+                match clean_path {
+                    Cow::Borrowed(clean_path) => path.truncate(clean_path.len()),
+                    Cow::Owned(literal) => path = literal,
+                }
+                (path, is_wildcard, value)
+            })
+            .collect();
+
+        // Stable sort, so the last value of a duplicated route can be kept
+        routes.sort_by(|(a, a_wildcard, _), (b, b_wildcard, _)| {
+            a.cmp(b).then(a_wildcard.cmp(b_wildcard))
+        });
+        routes.dedup_by(|later, earlier| {
+            let duplicate = later.0 == earlier.0 && later.1 == earlier.1;
+            if duplicate {
+                core::mem::swap(&mut later.2, &mut earlier.2);
+            }
+            duplicate
+        });
+
+        let (routes, values): (Vec<_>, Vec<_>) = routes
+            .into_iter()
+            .map(|(path, is_wildcard, value)| ((path, is_wildcard), value))
+            .unzip();
+        let mut root = RadixNode::from_sorted(String::new(), &routes, 0, &mut values.into_iter());
+        if let Some(max) = self.max_prefix_len {
+            root.chunk_subtree(max);
+        }
+        self.root = Child::new(root);
+    }
+
+    /// Returns a mutable reference to the value at the given path, inserting `f()` if absent
+    ///
+    /// Targets the exact slot, or the wildcard slot for paths ending with `/*`. No wildcard
+    /// fallback is considered, and `f` is only called when the slot is empty, in which case
+    /// the insertion is reported to the [`Trie::on_change`] callback.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.get_or_insert_with("/api/users", Vec::new).push("auth");
+    /// trie.get_or_insert_with("/api/users", Vec::new).push("handler");
+    ///
+    /// assert_eq!(trie.get("/api/users"), Some(&vec!["auth", "handler"]));
+    /// ```
+    pub fn get_or_insert_with(&mut self, path: &str, f: impl FnOnce() -> T) -> &mut T {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let vacant = self
+            .root
+            .find_node(&clean_path)
+            .is_none_or(|node| node.slot(is_wildcard).is_none());
+        if vacant {
+            self.insert_parsed(&path, &clean_path, is_wildcard, f());
+        }
+        self.node_mut(&clean_path)
+            .slot_mut(is_wildcard)
+            .as_mut()
+            .expect("slot was just filled")
+    }
+
+    /// Returns the slot targeted by the given path, creating nodes as needed
+    ///
+    /// This is the exact slot, or the wildcard slot for paths ending with `/*`. Filling or
+    /// taking its value directly bypasses the [`Trie::on_change`] callback, and a slot left
+    /// empty keeps the nodes created for it, as [`Trie::unset`] does. A value filled into an
+    /// empty slot has priority 0, like one stored with [`Trie::insert`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// *trie.entry_slot("/api/*") = Some("api_fallback");
+    /// assert_eq!(trie.get("/api/users"), Some(&"api_fallback"));
+    ///
+    /// assert_eq!(trie.entry_slot("/api/*").take(), Some("api_fallback"));
+    /// assert_eq!(trie.get("/api/users"), None);
+    /// ```
+    pub fn entry_slot(&mut self, path: &str) -> &mut Option<T> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let node = self.node_mut(&clean_path);
+        if node.slot(is_wildcard).is_none() {
+            *node.priority_mut(is_wildcard) = 0;
+        }
+        node.slot_mut(is_wildcard)
+    }
+
+    /// Applies `f` to the value stored at the given path, returning whether it was present
+    ///
+    /// Targets the exact slot, or the wildcard slot for paths ending with `/*`, without any
+    /// wildcard fallback.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", 1);
+    ///
+    /// assert!(trie.update("/api/users", |hits| *hits += 1));
+    /// assert!(!trie.update("/api/posts", |hits| *hits += 1));
+    /// assert_eq!(trie.get("/api/users"), Some(&2));
+    /// ```
+    pub fn update(&mut self, path: &str, f: impl FnOnce(&mut T)) -> bool {
+        match self.find_slot_mut(path).and_then(Option::as_mut) {
+            Some(value) => {
+                f(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Retrieves a value for the given path, with exact > wildcard precedence.
+    ///
+    /// Literal segments take precedence over single-segment wildcards, which take precedence
+    /// over trailing `/*` wildcards. The precedence is reversed for tries created with
+    /// [`MatchPolicy::WildcardFirst`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// # let mut trie = Trie::new();
+    /// # trie.insert("/api/users", "users_handler");
+    /// # trie.insert("/api/*", "api_fallback");
+    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler"));  // Exact
+    /// assert_eq!(trie.get("/api/posts"), Some(&"api_fallback"));   // Wildcard
+    /// ```
+    pub fn get<'a>(&'a self, path: &str) -> Option<&'a T> {
+        self.get_bytes(path.as_bytes())
+    }
+
+    /// Retrieves a value for the given raw byte path, with exact > wildcard precedence.
+    ///
+    /// Matches stored routes byte by byte, without validating the path as UTF-8 first, so
+    /// paths straight off the wire can be routed as-is. Bytes that aren't valid UTF-8 can
+    /// only be matched by wildcards.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/files/*", "files");
+    /// trie.insert("/files/readme", "readme");
+    ///
+    /// assert_eq!(trie.get_bytes(b"/files/readme"), Some(&"readme"));
+    /// assert_eq!(trie.get_bytes(b"/files/\xff\xfe"), Some(&"files"));
+    /// ```
+    pub fn get_bytes<'a>(&'a self, path: &[u8]) -> Option<&'a T> {
+        let mut lookup = self.lookup();
+        self.root.get(&self.normalize_bytes(path), &mut lookup)
+    }
+
+    /// Retrieves a value like [`Trie::get`], rejecting paths with more than `max_segments`
+    /// segments before matching them
+    ///
+    /// Every separator starts a segment, so `/api/users` has two. Segments are counted on the
+    /// normalized path, the one actually matched, and counting stops at the first separator
+    /// past the limit, which bounds the work spent on untrusted paths.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// assert_eq!(trie.get_limited("/api/users", 2), Ok(Some(&"api_fallback")));
+    /// assert!(trie.get_limited("/api/users/1", 2).is_err());
+    /// ```
+    pub fn get_limited<'a>(
+        &'a self,
+        path: &str,
+        max_segments: usize,
+    ) -> Result<Option<&'a T>, TooManySegments> {
+        let path = self.normalize(path);
+        let separator = self.separator as u8;
+        let mut separators = path.bytes().filter(|&b| b == separator);
+        if separators.nth(max_segments).is_some() {
+            return Err(TooManySegments::new(max_segments));
+        }
+        let mut lookup = self.lookup();
+        Ok(self.root.get(path.as_bytes(), &mut lookup))
+    }
+
+    /// Retrieves a value for the part of `path` below `mount`, for a trie serving a mounted
+    /// sub-application
+    ///
+    /// `mount` is stripped from the start of `path`, and the remainder is matched like
+    /// [`Trie::get`]. The mount must end at a segment boundary, so `/adminfoo` isn't under
+    /// `/admin`, and a trailing separator on `mount` is ignored. The mount itself leaves an
+    /// empty remainder, which matches the `""` route or a catch-all. `mount` is compared
+    /// verbatim, without this trie's normalization.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut admin = Trie::new();
+    /// admin.insert("/dashboard", "dashboard_handler");
+    ///
+    /// assert_eq!(admin.get_mounted("/admin", "/admin/dashboard"), Some(&"dashboard_handler"));
+    /// assert_eq!(admin.get_mounted("/admin", "/adminfoo/dashboard"), None);
+    /// assert_eq!(admin.get_mounted("/admin", "/dashboard"), None);
+    /// ```
+    pub fn get_mounted<'a>(&'a self, mount: &str, path: &str) -> Option<&'a T> {
+        let mount = mount.strip_suffix(self.separator).unwrap_or(mount);
+        let rest = path.strip_prefix(mount)?;
+        if !rest.is_empty() && !rest.starts_with(self.separator) {
+            return None;
+        }
+        self.get(rest)
+    }
+
+    /// Retrieves the exact route stored at the given path, without any wildcard matching
+    ///
+    /// The path is compared verbatim, so `*` segments and `/*` suffixes only match routes
+    /// inserted with the same literal path, e.g. through [`Trie::insert_exact`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get_exact("/api/users"), Some(&"users_handler"));
+    /// assert_eq!(trie.get_exact("/api/posts"), None);
+    /// ```
+    pub fn get_exact(&self, path: &str) -> Option<&T> {
+        self.root
+            .find_node(&self.normalize(path))?
+            .exact_value
+            .as_ref()
+    }
+
+    /// Retrieves a value for the given path, or `default` if no route matches
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.get_or("/api/users", &"not_found"), &"users_handler");
+    /// assert_eq!(trie.get_or("/api/posts", &"not_found"), &"not_found");
+    /// ```
+    pub fn get_or<'a>(&'a self, path: &str, default: &'a T) -> &'a T {
+        self.get(path).unwrap_or(default)
+    }
+
+    /// Retrieves a value for the given path, or describes how far the lookup got
+    ///
+    /// Matches like [`Trie::get`]. When no route matches, the error holds the longest part of
+    /// `path` shared with the stored routes, cut back to the last separator if they diverge in
+    /// the middle of a segment, as a hint for "did you mean" diagnostics.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/*", "v1_handler");
+    ///
+    /// assert_eq!(trie.get_or_nearest("/api/v1/users"), Ok(&"v1_handler"));
+    /// assert_eq!(trie.get_or_nearest("/api/v2/users"), Err("/api/".to_string()));
+    /// ```
+    pub fn get_or_nearest(&self, path: &str) -> Result<&T, String> {
+        let path = self.normalize(path);
+        let mut lookup = self.lookup();
+        if let Some(value) = self.root.get(path.as_bytes(), &mut lookup) {
+            return Ok(value);
+        }
+
+        let shared = self.root.shared_prefix_len(&path);
+        let mut nearest = path[..shared].to_string();
+        let at_boundary = nearest.ends_with(self.separator)
+            || path[shared..].is_empty()
+            || path[shared..].starts_with(self.separator);
+        if !at_boundary {
+            let segment_start = nearest.rfind(self.separator).map_or(0, |index| index + 1);
+            nearest.truncate(segment_start);
+        }
+        Err(nearest)
+    }
+
+    /// Checks whether any route matches the given path
+    ///
+    /// Equivalent to `self.get(path).is_some()`, but returns as soon as an applicable wildcard
+    /// is found instead of searching for a more specific route.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// assert!(trie.matches_any("/api/users/42"));
+    /// assert!(!trie.matches_any("/static/app.css"));
+    /// ```
+    pub fn matches_any(&self, path: &str) -> bool {
+        let mut lookup = self.lookup();
+        self.root
+            .matches_any(self.normalize(path).as_bytes(), &mut lookup)
+    }
+
+    /// Retrieves a value for the given path, along with the part of the path a wildcard consumed
+    ///
+    /// The rest is `None` for exact matches. For wildcard matches it is the remainder of the
+    /// path after the wildcard's prefix, without the leading separator.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/static/*", "static_files");
+    /// trie.insert("/static/index.html", "index");
+    ///
+    /// assert_eq!(
+    ///     trie.get_with_rest("/static/css/app.css"),
+    ///     Some((&"static_files", Some("css/app.css".to_string())))
+    /// );
+    /// assert_eq!(trie.get_with_rest("/static/index.html"), Some((&"index", None)));
+    /// ```
+    pub fn get_with_rest<'a>(&'a self, path: &str) -> Option<(&'a T, Option<String>)> {
+        let (value, clean_path, is_wildcard) = self.resolve_slot(path)?;
+        let rest = is_wildcard.then(|| self.wildcard_rest(&clean_path, path));
+        Some((value, rest))
+    }
+
+    /// Returns the part of `path` left over after the pattern of a matching wildcard route,
+    /// without the leading separator
+    ///
+    /// Literal characters of the pattern stand for themselves, a `?` for one character, and a
+    /// `*` or `:name` segment for one whole segment, so they are skipped in step along the
+    /// normalized path. The rest keeps the case `path` was given in.
+    fn wildcard_rest(&self, pattern: &str, path: &str) -> String {
+        let separator = self.separator as u8;
+        let normalized = self.normalize(path);
+        let (pattern, bytes) = (pattern.as_bytes(), normalized.as_bytes());
+        let segment_end =
+            |start: usize, bytes: &[u8]| bytes[start..].iter().position(|&b| b == separator);
+        let (mut i, mut j) = (0, 0);
+        while i < pattern.len() && j < bytes.len() {
+            let segment_start = i > 0 && pattern[i - 1] == separator;
+            let next = pattern.get(i + 1);
+            if segment_start && self.named_params && pattern[i] == b':'
+                || segment_start && pattern[i] == b'*' && next.is_none_or(|&b| b == separator)
+            {
+                i += segment_end(i, pattern).unwrap_or(pattern.len() - i);
+                j += segment_end(j, bytes).unwrap_or(bytes.len() - j);
+            } else if pattern[i] == CHAR_WILDCARD as u8 {
+                i += 1;
+                j += first_char(&bytes[j..]).map_or(1, char::len_utf8);
+            } else {
+                i += 1;
+                j += 1;
+            }
+        }
+        self.rest_from(path, j)
+    }
+
+    /// Returns the part of `path` after byte `start` of its normalized form, without the
+    /// leading separator and in the case it was given in
+    fn rest_from(&self, path: &str, start: usize) -> String {
+        let unfolded = self.normalize_unfolded(path);
+        let start = if self.case_insensitive {
+            case::unfolded_offset(&unfolded, start)
+        } else {
+            start
+        };
+        let rest = unfolded.get(start..).unwrap_or_default();
+        rest.strip_prefix(self.separator)
+            .unwrap_or(rest)
+            .to_string()
+    }
+
+    /// Retrieves a value like [`Trie::get_with_rest`], with the part of the path a wildcard
+    /// consumed split into segments
+    ///
+    /// The segments are empty for exact matches, and for a wildcard matching its prefix with
+    /// nothing after it. Empty segments within the rest are kept, so `a//b` gives three.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/files/*", "files");
+    /// trie.insert("/files/readme", "readme");
+    ///
+    /// assert_eq!(
+    ///     trie.get_segments("/files/a/b/c"),
+    ///     Some((&"files", vec!["a".to_string(), "b".to_string(), "c".to_string()]))
+    /// );
+    /// assert_eq!(trie.get_segments("/files/readme"), Some((&"readme", vec![])));
+    /// ```
+    pub fn get_segments<'a>(&'a self, path: &str) -> Option<(&'a T, Vec<String>)> {
+        let (value, rest) = self.get_with_rest(path)?;
+        let segments = match rest {
+            Some(rest) if !rest.is_empty() => {
+                rest.split(self.separator).map(String::from).collect()
+            }
+            _ => Vec::new(),
+        };
+        Some((value, segments))
+    }
+
+    /// Retrieves every value that applies to the given path, most specific first
+    ///
+    /// Routes are matched like [`Trie::get`], including those with `*`, `:name` and `?`
+    /// wildcards, and ordered the way it prefers them under the trie's [`MatchPolicy`]: each is
+    /// the one `get` would return if the routes before it were removed. With the default policy
+    /// the exact match (if any) comes first, followed by the wildcard values from the deepest
+    /// matching node up to the root. The first element is what [`Trie::get`] returns.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/v1/*", "v1_fallback");
+    /// trie.insert("/api/v1/users", "users_handler");
+    ///
+    /// assert_eq!(
+    ///     trie.get_all_matches("/api/v1/users"),
+    ///     vec![&"users_handler", &"v1_fallback", &"api_fallback"]
+    /// );
+    /// ```
+    pub fn get_all_matches<'a>(&'a self, path: &str) -> Vec<&'a T> {
+        self.candidates(path).collect()
+    }
+
+    /// Retrieves mutable references to every value that applies to the given path, most
+    /// specific first
+    ///
+    /// Returns the same values as [`Trie::get_all_matches`], in the same order. The matching
+    /// routes are resolved first, then their slots are collected in a single walk down the
+    /// branches leading to them, splitting each node into its fields on the way.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", 0);
+    /// trie.insert("/api/users", 0);
+    ///
+    /// // Count a hit on every route that applies
+    /// for hits in trie.get_all_matches_mut("/api/users") {
+    ///     *hits += 1;
+    /// }
+    /// assert_eq!(trie.get_all_matches("/api/users"), [&1, &1]);
+    /// ```
+    pub fn get_all_matches_mut<'a>(&'a mut self, path: &str) -> Vec<&'a mut T> {
+        let slots: Vec<_> = self
+            .matching_slots(path)
+            .map(|(_, clean_path, is_wildcard)| (clean_path, is_wildcard))
+            .collect();
+        let mut matches: Vec<Option<&mut T>> = slots.iter().map(|_| None).collect();
+
+        let mut stack = Vec::from([(String::new(), &mut *self.root)]);
+        while let Some((mut path, node)) = stack.pop() {
+            let RadixNode {
+                prefix,
+                children,
+                exact_value,
+                wildcard_value,
+                ..
+            } = node;
+            path.push_str(prefix);
+            for (is_wildcard, value) in [(false, exact_value), (true, wildcard_value)] {
+                let slot = slots
+                    .iter()
+                    .position(|(slot, w)| *slot == path && *w == is_wildcard);
+                if let Some(index) = slot {
+                    matches[index] = value.as_mut();
+                }
+            }
+            for child in children.values_mut() {
+                let child_path = [path.as_str(), &child.prefix].concat();
+                if slots.iter().any(|(slot, _)| slot.starts_with(&child_path)) {
+                    stack.push((path.clone(), &mut **child));
+                }
+            }
+        }
+        matches.into_iter().flatten().collect()
+    }
+
+    /// Returns an iterator over every value that applies to the given path, most specific first
+    ///
+    /// Yields the same values as [`Trie::get_all_matches`] without collecting them: each one
+    /// is only matched as the iterator advances, so `take(1)` or `find` stop early.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/v1/*", "v1_fallback");
+    ///
+    /// let mut candidates = trie.candidates("/api/v1/users");
+    /// assert_eq!(candidates.next(), Some(&"v1_fallback"));
+    /// assert_eq!(candidates.next(), Some(&"api_fallback"));
+    /// assert_eq!(candidates.next(), None);
+    /// ```
+    pub fn candidates<'a>(&'a self, path: &str) -> impl Iterator<Item = &'a T> + use<'a, T> {
+        self.matching_slots(path).map(|(value, ..)| value)
+    }
+
+    /// Retrieves the exact values stored along the path, from the shortest path to the longest
+    ///
+    /// Includes every exact route whose path is a prefix of `path` (including `path` itself),
+    /// which models settings that cascade from general to specific. Wildcard values are not
+    /// included.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/a", 1);
+    /// trie.insert("/a/b", 2);
+    /// trie.insert("/a/b/c", 3);
+    ///
+    /// assert_eq!(trie.prefix_values("/a/b/c"), vec![&1, &2, &3]);
+    /// ```
+    pub fn prefix_values(&self, path: &str) -> Vec<&T> {
+        self.root
+            .prefix_values(&self.normalize(path))
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// Retrieves the exact routes stored along the path with their keys, from shortest to longest
+    ///
+    /// Like [`Trie::prefix_values`], but each value comes with the path it was registered
+    /// under. Only stored routes are included, never the intermediate nodes between them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/a", "read");
+    /// trie.insert("/a/b", "write");
+    /// trie.insert("/x", "admin");
+    ///
+    /// assert_eq!(
+    ///     trie.ancestors("/a/b/c"),
+    ///     vec![("/a".to_string(), &"read"), ("/a/b".to_string(), &"write")]
+    /// );
+    /// ```
+    pub fn ancestors(&self, path: &str) -> Vec<(String, &T)> {
+        let path = self.normalize(path);
+        self.root
+            .prefix_values(&path)
+            .into_iter()
+            .map(|(length, value)| (path[..length].to_string(), value))
+            .collect()
+    }
+
+    /// Returns the sorted keys of the stored routes whose matches interact with `pattern`
+    ///
+    /// For a wildcard pattern, these are the routes it would shadow, i.e. every route under its
+    /// prefix, and the broader wildcards it would partially override. For an exact path, these
+    /// are the wildcards covering it. A route already stored in the pattern's own slot is
+    /// included as well. Routes are compared by their literal keys, the same way `get` descends
+    /// the trie, so segment wildcards and parameters aren't expanded.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/*", "fallback");
+    /// trie.insert("/api/users", "users_handler");
+    /// trie.insert("/auth/login", "login_handler");
+    ///
+    /// assert_eq!(trie.overlapping_routes("/api/*"), ["/*", "/api/users"]);
+    /// assert_eq!(trie.overlapping_routes("/auth/logout"), ["/*"]);
+    /// ```
+    pub fn overlapping_routes(&self, pattern: &str) -> Vec<String> {
+        let pattern = self.normalize(pattern);
+        let (clean_path, is_wildcard) = self.parse_path(&pattern);
+
+        let mut overlaps = Vec::new();
+        let mut node: &RadixNode<T> = &self.root;
+        let mut path = clean_path.as_ref();
+        let mut length = 0;
+        loop {
+            let common_length = node.common_prefix_len(path);
+            if is_wildcard && common_length == path.len() {
+                // Every route from this node down lies under the wildcard's prefix
+                let base = &clean_path[..length];
+                overlaps.extend(
+                    Iter::new(node, self.separator).map(|(key, _)| base.to_string() + &key),
+                );
+                break;
+            }
+            if common_length != node.prefix.len() {
+                break; // No stored route is a prefix of the rest of the pattern
+            }
+
+            length += common_length;
+            path = &path[common_length..];
+            if node.wildcard_value.is_some()
+                && node.wildcard_covers(path.as_bytes(), self.separator as u8)
+            {
+                overlaps.push(Self::format_key(
+                    &clean_path[..length],
+                    true,
+                    self.separator,
+                ));
+            }
+            let Some(first_char) = path.chars().next() else {
+                let key = || Self::format_key(&clean_path, false, self.separator);
+                overlaps.extend(node.exact_value.as_ref().map(|_| key()));
+                break;
+            };
+            match node.children.get(&first_char) {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+        overlaps.sort_unstable();
+        overlaps
+    }
+
+    /// Retrieves the exact value for the given path, or else that of its deepest stored ancestor
+    ///
+    /// An ancestor is any exact route whose path is a prefix of `path`, so this is the last
+    /// element of [`Trie::prefix_values`]. Unlike [`Trie::get`], any exact route acts as a
+    /// fallback for the paths below it, whether or not it was registered as a wildcard, and
+    /// wildcard routes are ignored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/a", 1);
+    /// trie.insert("/a/b/c", 3);
+    ///
+    /// assert_eq!(trie.get_closest("/a/b"), Some(&1));
+    /// assert_eq!(trie.get_closest("/a/b/c"), Some(&3));
+    /// assert_eq!(trie.get("/a/b"), None);
+    /// ```
+    pub fn get_closest(&self, path: &str) -> Option<&T> {
+        self.root.closest_value(&self.normalize(path))
+    }
+
+    /// Retrieves the value of the deepest stored route along the path, exact or wildcard
+    ///
+    /// Walks down the literal prefixes of `path` as far as they go, then returns the first
+    /// value found on the way back up. Depth is all that counts: a wildcard at a deeper node
+    /// beats an exact route at a shallower ancestor, and, as with [`Trie::get_closest`], an
+    /// exact route also applies to the paths below it. Within an ancestor, its wildcard comes
+    /// before its exact route, while the exact route for `path` itself is ranked against the
+    /// wildcard of its node by the trie's [`MatchPolicy`]. [`Trie::get`] instead never uses an
+    /// exact route for another path, and expands single-segment wildcards, which this doesn't.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/config", "defaults");
+    /// trie.insert("/config/db/*", "db_rules");
+    ///
+    /// assert_eq!(trie.deepest_match("/config/db/host"), Some(&"db_rules"));
+    /// assert_eq!(trie.deepest_match("/config/cache/ttl"), Some(&"defaults"));
+    /// assert_eq!(trie.get("/config/cache/ttl"), None);
+    /// ```
+    pub fn deepest_match(&self, path: &str) -> Option<&T> {
+        let path = self.normalize(path);
+        let (chain, exact) = self.root.match_chain(&path);
+        match exact {
+            Some(exact) if self.policy == MatchPolicy::ExactFirst => Some(exact),
+            _ => chain.into_iter().rev().find_map(|(node, rest)| {
+                let covers = node.wildcard_covers(rest.as_bytes(), self.separator as u8);
+                let wildcard = node.wildcard_value.as_ref().filter(|_| covers);
+                wildcard.or(node.exact_value.as_ref())
+            }),
+        }
+    }
+
+    /// Retrieves the most specific value for the given path that satisfies `pred`
+    ///
+    /// Returns the first of [`Trie::get_all_matches`] that `pred` accepts, found by matching
+    /// the path like [`Trie::get`] as if the routes `pred` rejects weren't stored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/admin/*", ("admin", true));
+    /// trie.insert("/admin/logs", ("logs", false));
+    ///
+    /// let allowed = trie.find_match("/admin/logs", |(_, allowed)| *allowed);
+    /// assert_eq!(allowed, Some(&("admin", true)));
+    /// ```
+    pub fn find_match(&self, path: &str, pred: impl Fn(&T) -> bool) -> Option<&T> {
+        let (value, ..) = self.resolve_normalized(&self.normalize(path), Some(&pred))?;
+        Some(value)
+    }
+
+    /// Removes a value at the given path, returning it if it existed
+    ///
+    /// Nodes left without values are pruned, so the trie stays as compact as if the
+    /// remaining routes had been inserted fresh. Use [`Trie::unset`] to keep the structure.
+    pub fn remove(&mut self, path: &str) -> Option<T> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let removed = self.remove_slot(&clean_path, is_wildcard)?;
+        self.notify(ChangeEvent::Removed { path: &path });
+        Some(removed)
+    }
+
+    /// Removes and returns the value at the given path, only if `pred` accepts it
+    ///
+    /// Targets the same slot as [`Trie::remove`]. A rejected value stays in place, and `None`
+    /// is returned as if the slot were empty.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/legacy", ("legacy_handler", true));
+    ///
+    /// assert_eq!(trie.remove_if("/api/legacy", |(_, enabled)| !enabled), None);
+    /// assert_eq!(
+    ///     trie.remove_if("/api/legacy", |(_, enabled)| *enabled),
+    ///     Some(("legacy_handler", true))
+    /// );
+    /// ```
+    pub fn remove_if(&mut self, path: &str, pred: impl FnOnce(&T) -> bool) -> Option<T> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let accepted = self
+            .root
+            .find_node(&clean_path)
+            .and_then(|node| node.slot(is_wildcard))
+            .is_some_and(pred);
+        if !accepted {
+            return None;
+        }
+        let removed = self.remove_slot(&clean_path, is_wildcard)?;
+        self.notify(ChangeEvent::Removed { path: &path });
+        Some(removed)
+    }
+
+    /// Takes the value at the given path out of the trie, without pruning any nodes
+    ///
+    /// Unlike [`Trie::remove`], the node structure (including the emptied node and everything
+    /// below it) is left untouched, which avoids splitting nodes again when a value is
+    /// re-inserted at the same path right away.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// assert_eq!(trie.unset("/api/*"), Some("api_fallback"));
+    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+    /// assert_eq!(trie.get("/api/posts"), None);
+    /// ```
+    pub fn unset(&mut self, path: &str) -> Option<T> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let removed = self.root.unset(&clean_path, is_wildcard)?;
+        self.notify(ChangeEvent::Removed { path: &path });
+        Some(removed)
+    }
+
+    /// Removes a value at the given path, returning its canonical stored key with it
+    ///
+    /// The key includes the `/*` suffix for wildcard routes.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// assert_eq!(trie.remove_entry("/api/*"), Some(("/api/*".to_string(), "api_fallback")));
+    /// assert_eq!(trie.remove_entry("/api/*"), None);
+    /// ```
+    pub fn remove_entry(&mut self, path: &str) -> Option<(String, T)> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        let value = self.remove_slot(&clean_path, is_wildcard)?;
+        self.notify(ChangeEvent::Removed { path: &path });
+        Some((
+            Self::format_key(&clean_path, is_wildcard, self.separator),
+            value,
+        ))
+    }
+
+    /// Returns an iterator over every `(path, &value)` entry, in no particular order
+    ///
+    /// Wildcard routes are yielded with their `/*` suffix restored.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.root, self.separator)
+    }
+
+    /// Returns an iterator over every `(path, &value)` entry, in lexicographic path order
+    ///
+    /// Gives stable output for snapshot tests and route dumps, at the cost of collecting and
+    /// sorting the entries up front. Use [`Trie::iter`] when order doesn't matter.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/static/*", "static_files");
+    /// trie.insert("/api/users", "users_handler");
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// let keys: Vec<_> = trie.iter_sorted().map(|(key, _)| key).collect();
+    /// assert_eq!(keys, ["/api/*", "/api/users", "/static/*"]);
+    /// ```
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (String, &T)> {
+        self.to_sorted_entries().into_iter()
+    }
+
+    /// Returns every `(path, &value)` entry in lexicographic path order
+    ///
+    /// The canonical form of the routes, independent of insertion order and node shape: the
+    /// trie's `Debug` implementation follows it, and `PartialEq` and `Hash` add each route's
+    /// priority to it, which makes it a good basis for golden-file tests. Wildcard routes have
+    /// their `/*` suffix restored, and literal `/*` routes stored with [`Trie::insert_exact`]
+    /// have it escaped as `/\\*`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api", "api_root");
+    ///
+    /// assert_eq!(
+    ///     trie.to_sorted_entries(),
+    ///     [
+    ///         ("/api".to_string(), &"api_root"),
+    ///         ("/api/*".to_string(), &"api_fallback"),
+    ///         ("/api/users".to_string(), &"users_handler"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_sorted_entries(&self) -> Vec<(String, &T)> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+
+    /// Returns every `(path, &value, priority)` entry in lexicographic path order, which the
+    /// `PartialEq` and `Hash` implementations compare
+    fn prioritized_entries(&self) -> Vec<(String, &T, i32)> {
+        let mut entries = Vec::new();
+        let mut stack = Vec::from([(self.root.prefix.clone(), &*self.root)]);
+        while let Some((path, node)) = stack.pop() {
+            let slots = [(false, node.exact_priority), (true, node.wildcard_priority)];
+            for (is_wildcard, priority) in slots {
+                if let Some(value) = node.slot(is_wildcard) {
+                    let key = Self::format_key(&path, is_wildcard, self.separator);
+                    entries.push((key, value, priority));
+                }
+            }
+            for child in node.children.values() {
+                stack.push(([path.as_str(), &child.prefix].concat(), child));
+            }
+        }
+        entries.sort_unstable_by(|(a, ..), (b, ..)| a.cmp(b));
+        entries
+    }
+
+    /// Returns an iterator over the `(path, &value)` entries of wildcard routes only
+    ///
+    /// Keys carry their `/*` suffix, and come out in no particular order. Handy for reviewing
+    /// which catch-all routes are registered.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let wildcards: Vec<_> = trie.wildcards().collect();
+    /// assert_eq!(wildcards, vec![("/api/*".to_string(), &"api_fallback")]);
+    /// ```
+    pub fn wildcards(&self) -> impl Iterator<Item = (String, &T)> {
+        let mut stack = Vec::from([(self.root.prefix.clone(), &self.root)]);
+        core::iter::from_fn(move || {
+            loop {
+                let (path, node) = stack.pop()?;
+                for child in node.children.values() {
+                    stack.push(([path.as_str(), &child.prefix].concat(), child));
+                }
+                if let Some(value) = &node.wildcard_value {
+                    return Some((Self::format_key(&path, true, self.separator), value));
+                }
+            }
+        })
+    }
+
+    /// Returns the `(path, &value)` entries whose value satisfies `pred`, in no particular order
+    ///
+    /// Visits every route, exact and wildcard, leaving the trie untouched.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/*", 1);
+    /// trie.insert("/api/v2/*", 2);
+    ///
+    /// let legacy = trie.find_all(|&version| version < 2);
+    /// assert_eq!(legacy, vec![("/api/v1/*".to_string(), &1)]);
+    /// ```
+    pub fn find_all(&self, pred: impl Fn(&T) -> bool) -> Vec<(String, &T)> {
+        self.iter().filter(|(_, value)| pred(value)).collect()
+    }
+
+    /// Groups every `(path, &value)` entry by the `n`-th segment of its path, counting from 0
+    ///
+    /// A leading separator doesn't start a segment, so `/api/users` has segments `api` and
+    /// `users`, and wildcard routes have a `*` segment in place of their suffix. Entries with
+    /// `n` segments or fewer are skipped. Each group lists its entries in lexicographic path
+    /// order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    /// trie.insert("/auth/login", "login_handler");
+    ///
+    /// let groups = trie.group_by_segment(0);
+    /// assert_eq!(groups["auth"], vec![("/auth/login".to_string(), &"login_handler")]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn group_by_segment(
+        &self,
+        n: usize,
+    ) -> std::collections::HashMap<String, Vec<(String, &T)>> {
+        let mut groups = std::collections::HashMap::<_, Vec<_>>::new();
+        for (path, value) in self.iter_sorted() {
+            let segments = path.strip_prefix(self.separator).unwrap_or(&path);
+            if let Some(segment) = segments.split(self.separator).nth(n) {
+                groups
+                    .entry(segment.to_string())
+                    .or_default()
+                    .push((path, value));
+            }
+        }
+        groups
+    }
+
+    /// Returns an iterator over mutable references to every stored value, in no particular order
+    ///
+    /// Covers both exact and wildcard routes, for updating every value in a single pass.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", 10);
+    /// trie.insert("/api/users", 4);
+    ///
+    /// for hits in trie.values_mut() {
+    ///     *hits /= 2;
+    /// }
+    /// assert_eq!(trie.get("/api/users"), Some(&2));
+    /// assert_eq!(trie.get("/api/posts"), Some(&5));
+    /// ```
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let mut stack = Vec::from([&mut self.root]);
+        core::iter::from_fn(move || {
+            let node: &mut RadixNode<T> = stack.pop()?;
+            stack.extend(node.children.values_mut());
+            Some(node.exact_value.iter_mut().chain(&mut node.wildcard_value))
+        })
+        .flatten()
+    }
+
+    /// Removes every entry from the trie, yielding `(path, value)` pairs
+    ///
+    /// The trie is left empty but usable, and every route is reported to the change callback
+    /// up front. Wildcard routes are yielded with their `/*` suffix restored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// let drained: Vec<_> = trie.drain().collect();
+    /// assert_eq!(drained, vec![("/api/*".to_string(), "api_fallback")]);
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> IntoIter<T> {
+        let root = core::mem::replace(&mut self.root, Child::new(RadixNode::new(String::new())));
+        self.notify_removed("", &root);
+        IntoIter::new(root.into_inner(), self.separator)
+    }
+
+    /// Removes every route at or below `prefix`, returning the number of values removed
+    ///
+    /// The prefix only matches whole segments, so `/api/v1` removes `/api/v1` and
+    /// `/api/v1/users` but leaves `/api/v10` alone. A prefix ending with the separator removes
+    /// only the routes below it. Wildcard routes are matched on their path without the `/*`
+    /// suffix. Returns 0 if no stored route is under `prefix`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "users");
+    /// trie.insert("/api/v1/*", "v1_fallback");
+    /// trie.insert("/api/v10/*", "v10_fallback");
+    ///
+    /// assert_eq!(trie.remove_prefix("/api/v1"), 2);
+    /// assert_eq!(trie.get("/api/v1/users"), None);
+    /// assert_eq!(trie.get("/api/v10/users"), Some(&"v10_fallback"));
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) -> usize {
+        let prefix = self.normalize(prefix);
+        if prefix.is_empty() {
+            let root =
+                core::mem::replace(&mut self.root, Child::new(RadixNode::new(String::new())));
+            self.notify_removed("", &root);
+            return root.value_count();
+        }
+
+        // Take the routes at the prefix itself, then everything below its next separator
+        let mut at_prefix = 0;
+        let mut below = prefix.into_owned();
+        if !below.ends_with(self.separator) {
+            for is_wildcard in [false, true] {
+                if self.remove_slot(&below, is_wildcard).is_some() {
+                    let key = Self::format_key(&below, is_wildcard, self.separator);
+                    self.notify(ChangeEvent::Removed { path: &key });
+                    at_prefix += 1;
+                }
+            }
+            below.push(self.separator);
+        }
+        let Some(removed) = self.root.split_off(&below) else {
+            return at_prefix;
+        };
+        // Pruning the detached branch may have merged the nodes along the prefix
+        if let Some(max) = self.max_prefix_len {
+            self.root.chunk_path(&below, max);
+        }
+        // The detached branch is re-based on the prefix, with paths relative to it
+        self.notify_removed(&below, &removed);
+        at_prefix + removed.value_count()
+    }
+
+    /// Moves every route whose path starts with `prefix` into a new trie, re-based on `prefix`
+    ///
+    /// Paths in the returned trie have `prefix` stripped, so with a prefix of `/api/v1` the
+    /// route `/api/v1/users` becomes `/users`, and `/api/v1/*` becomes the catch-all `/*`.
+    /// Unlike [`Trie::remove_prefix`], routes are selected on a plain string prefix, which may
+    /// end in the middle of a segment. The new trie has the same match policy, separator and
+    /// normalization options, but no change callback; each moved route is reported as removed
+    /// to this trie's.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "v1_users");
+    /// trie.insert("/api/v2/users", "v2_users");
+    ///
+    /// let v1 = trie.split_off("/api/v1");
+    /// assert_eq!(v1.get("/users"), Some(&"v1_users"));
+    /// assert_eq!(trie.get("/api/v1/users"), None);
+    /// assert_eq!(trie.get("/api/v2/users"), Some(&"v2_users"));
+    /// ```
+    pub fn split_off(&mut self, prefix: &str) -> Trie<T> {
+        let prefix = self.normalize(prefix);
+        let root = if prefix.is_empty() {
+            let root =
+                core::mem::replace(&mut self.root, Child::new(RadixNode::new(String::new())));
+            Some(root.into_inner())
+        } else {
+            let detached = self.root.split_off(&prefix);
+            // Pruning the detached branch may have merged the nodes along the prefix
+            if let Some(max) = self.max_prefix_len {
+                self.root.chunk_path(&prefix, max);
+            }
+            detached
+        };
+        if let Some(root) = &root {
+            self.notify_removed(&prefix, root);
+        }
+
+        Trie {
+            root: Child::new(root.unwrap_or_else(|| RadixNode::new(String::new()))),
+            policy: self.policy,
+            require_leading_slash: self.require_leading_slash,
+            case_insensitive: self.case_insensitive,
+            percent_decoding: self.percent_decoding,
+            collapse_slashes: self.collapse_slashes,
+            named_params: self.named_params,
+            separator: self.separator,
+            max_prefix_len: self.max_prefix_len,
+            prioritized: self.prioritized,
+            on_change: None,
+        }
+    }
+
+    /// Consumes the trie, splitting its routes into those whose value satisfies `pred` and
+    /// those whose value doesn't
+    ///
+    /// Both tries keep the original keys and slots, so a literal `/*` route stored with
+    /// [`Trie::insert_exact`] stays exact, along with each route's priority. They have the
+    /// same match policy, separator and normalization options, but no change callback.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", 1);
+    /// trie.insert("/api/*", 2);
+    /// trie.insert("/auth/login", 3);
+    ///
+    /// let (odd, even) = trie.partition(|value| value % 2 == 1);
+    /// assert_eq!(odd.get("/auth/login"), Some(&3));
+    /// assert_eq!(odd.get("/api/posts"), None);
+    /// assert_eq!(even.get("/api/posts"), Some(&2));
+    /// ```
+    pub fn partition(mut self, pred: impl Fn(&T) -> bool) -> (Trie<T>, Trie<T>) {
+        let root = core::mem::replace(&mut self.root, Child::new(RadixNode::new(String::new())));
+        self.on_change = None;
+        let mut rejected = Trie {
+            root: Child::new(RadixNode::new(String::new())),
+            policy: self.policy,
+            require_leading_slash: self.require_leading_slash,
+            case_insensitive: self.case_insensitive,
+            percent_decoding: self.percent_decoding,
+            collapse_slashes: self.collapse_slashes,
+            named_params: self.named_params,
+            separator: self.separator,
+            max_prefix_len: self.max_prefix_len,
+            prioritized: self.prioritized,
+            on_change: None,
+        };
+        let mut accepted = self;
+
+        let root = root.into_inner();
+        let mut stack = Vec::from([(root.prefix.clone(), root)]);
+        while let Some((path, node)) = stack.pop() {
+            let slots = [
+                (false, node.exact_value, node.exact_priority),
+                (true, node.wildcard_value, node.wildcard_priority),
+            ];
+            for (is_wildcard, slot, priority) in slots {
+                if let Some(value) = slot {
+                    let target = if pred(&value) {
+                        &mut accepted
+                    } else {
+                        &mut rejected
+                    };
+                    // Paths were normalized when they were first inserted
+                    target
+                        .node_mut(&path)
+                        .store_prioritized(value, is_wildcard, priority);
+                }
+            }
+            for child in node.children.into_values() {
+                let child_path = [path.as_str(), &child.prefix].concat();
+                stack.push((child_path, child.into_inner()));
+            }
+        }
+        (accepted, rejected)
+    }
+
+    /// Returns the number of routes whose path starts with `prefix`
+    ///
+    /// Routes are selected on a plain string prefix as in [`Trie::split_off`], without visiting
+    /// anything outside the prefix's subtree.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users");
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/auth/login", "login");
+    ///
+    /// assert_eq!(trie.count_prefix("/api"), 2);
+    /// assert_eq!(trie.count_prefix("/nonexistent"), 0);
+    /// ```
+    pub fn count_prefix(&self, prefix: &str) -> usize {
+        self.root
+            .find_prefix(&self.normalize(prefix))
+            .map_or(0, RadixNode::value_count)
+    }
+
+    /// Checks whether any route's path starts with `prefix`
+    ///
+    /// Routes are matched as in [`Trie::count_prefix`], including a prefix that ends in the
+    /// middle of a node's stored prefix, but the search stops at the first value found.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users");
+    ///
+    /// assert!(trie.contains_prefix("/api"));
+    /// assert!(trie.contains_prefix("/api/us"));
+    /// assert!(!trie.contains_prefix("/auth"));
+    /// ```
+    pub fn contains_prefix(&self, prefix: &str) -> bool {
+        self.root
+            .find_prefix(&self.normalize(prefix))
+            .is_some_and(RadixNode::has_values)
+    }
+
+    /// Removes every route whose path does not start with `prefix`, keeping only that namespace
+    ///
+    /// Unlike [`Trie::remove_prefix`], routes are matched on a plain string prefix of their
+    /// full path including the `/*` suffix of wildcard routes, so a literal `/*` route stored
+    /// with [`Trie::insert_exact`] is matched on its path alone. The remaining routes keep their
+    /// full paths, and every removed route is reported to the change callback.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users");
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/auth/login", "login");
+    ///
+    /// trie.retain_prefix("/api");
+    /// assert_eq!(trie.get("/api/users"), Some(&"users"));
+    /// assert_eq!(trie.get("/auth/login"), None);
+    /// ```
+    pub fn retain_prefix(&mut self, prefix: &str) {
+        let prefix = self.normalize(prefix);
+        let mut removed = Vec::new();
+        let mut stack = Vec::from([(self.root.prefix.clone(), &*self.root)]);
+        while let Some((path, node)) = stack.pop() {
+            for child in node.children.values() {
+                stack.push(([path.as_str(), &child.prefix].concat(), child));
+            }
+            if node.exact_value.is_some() && !path.starts_with(prefix.as_ref()) {
+                removed.push((path.clone(), false));
+            }
+            let wildcard = Self::format_key(&path, true, self.separator);
+            if node.wildcard_value.is_some() && !wildcard.starts_with(prefix.as_ref()) {
+                removed.push((path, true));
+            }
+        }
+
+        for (clean_path, is_wildcard) in removed {
+            if self.remove_slot(&clean_path, is_wildcard).is_some() {
+                let key = Self::format_key(&clean_path, is_wildcard, self.separator);
+                self.notify(ChangeEvent::Removed { path: &key });
+            }
+        }
+    }
+
+    /// Consumes the trie, transforming every value with `f`
+    ///
+    /// The node structure and the exact and wildcard slots are kept as they are, so every path
+    /// resolves to the transformed value of the route it resolved to before. The options and
+    /// change callback carry over. Values are visited in no particular order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut names = Trie::new();
+    /// names.insert("/api/*", "api");
+    /// names.insert("/api/users", "users");
+    ///
+    /// let handlers = names.map(|name| format!("{name}_handler"));
+    /// assert_eq!(handlers.get("/api/users"), Some(&"users_handler".to_string()));
+    /// assert_eq!(handlers.get("/api/posts"), Some(&"api_handler".to_string()));
+    /// ```
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Trie<U> {
+        Trie {
+            root: Child::new(self.root.into_inner().map(&mut f)),
+            policy: self.policy,
+            require_leading_slash: self.require_leading_slash,
+            case_insensitive: self.case_insensitive,
+            percent_decoding: self.percent_decoding,
+            collapse_slashes: self.collapse_slashes,
+            named_params: self.named_params,
+            separator: self.separator,
+            max_prefix_len: self.max_prefix_len,
+            prioritized: self.prioritized,
+            on_change: self.on_change,
+        }
+    }
+
+    /// Returns the maximum number of nodes from the root to the deepest leaf, including the root
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "users");
+    /// trie.insert("/api/v1/posts", "posts");
+    ///
+    /// // (root) -> "/api/v1/" -> "users" | "posts"
+    /// assert_eq!(trie.depth(), 3);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    /// Returns the total number of nodes in the trie, including the root
+    ///
+    /// Useful for checking how well routes share compressed prefixes.
+    pub fn node_count(&self) -> usize {
+        self.root.node_count()
+    }
+
+    /// Restores the compression of the whole trie, after values were taken out without pruning
+    ///
+    /// Merges every node that holds no value and has a single child into that child, and
+    /// drops nodes left with neither values nor children, as [`Trie::remove`] does along its
+    /// path. This is the batch counterpart for [`Trie::unset`] and emptied
+    /// [`Trie::entry_slot`]s: afterwards the trie has the nodes a fresh insertion of its
+    /// routes would create.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "users");
+    /// trie.insert("/api/v1/posts", "posts");
+    /// trie.unset("/api/v1/posts");
+    /// assert_eq!(trie.node_count(), 4);
+    ///
+    /// trie.compact();
+    /// assert_eq!(trie.node_count(), 2);
+    /// ```
+    pub fn compact(&mut self) {
+        self.root.compact();
+        if let Some(max) = self.max_prefix_len {
+            self.root.chunk_subtree(max);
+        }
+    }
+
+    /// Returns the longest path prefix shared by every stored route
+    ///
+    /// When routes diverge in the middle of a segment, the prefix is cut back to the last
+    /// separator, so it can be stripped as a base path. It is the full path of a route when every
+    /// other route lies below it, and empty when the trie is empty or routes diverge at
+    /// the first character.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", 1);
+    /// trie.insert("/api/v1/posts", 2);
+    /// assert_eq!(trie.longest_common_prefix(), "/api/v1/");
+    ///
+    /// trie.insert("/auth/login", 3);
+    /// assert_eq!(trie.longest_common_prefix(), "/");
+    /// ```
+    pub fn longest_common_prefix(&self) -> String {
+        let mut prefix = String::new();
+        let mut node = &self.root;
+
+        loop {
+            prefix.push_str(&node.prefix);
+            if node.exact_value.is_some() || node.wildcard_value.is_some() {
+                return prefix;
+            }
+
+            let mut children = node.children.values();
+            match (children.next(), children.next()) {
+                (Some(child), None) => node = child,
+                (None, _) => return prefix,
+                (Some(_), Some(_)) => {
+                    // Routes diverge here, possibly mid-segment
+                    let segment_start = prefix.rfind(self.separator).map_or(0, |index| index + 1);
+                    prefix.truncate(segment_start);
+                    return prefix;
+                }
+            }
+        }
+    }
+
+    /// Returns an estimate of the memory used by the trie, in bytes
+    ///
+    /// Counts the trie itself, every node and the buffers holding prefixes and child maps.
+    /// Allocator overhead and memory owned by the values (e.g. a `String`'s buffer) aren't
+    /// included, so treat the result as a lower bound for budgeting rather than an exact
+    /// figure.
+    pub fn memory_usage(&self) -> usize {
+        core::mem::size_of::<Self>() + self.root.heap_usage()
+    }
+
+    /// Creates a lookup matching routes with the trie's settings
+    fn lookup<'a, 't, S: Storage>(&self) -> Lookup<'a, 't, T, S> {
+        Lookup {
+            named_params: self.named_params,
+            prioritized: self.prioritized,
+            ..Lookup::new(self.policy, self.separator)
+        }
+    }
+
+    /// Applies the trie's path normalization options to a path before it is used
+    fn normalize<'p>(&self, path: &'p str) -> Cow<'p, str> {
+        let path = self.normalize_unfolded(path);
+        if self.case_insensitive {
+            case::fold_case(path)
+        } else {
+            path
+        }
+    }
+
+    /// Applies every normalization option but case folding, for slicing the parts of a path
+    /// that are handed back to the caller
+    fn normalize_unfolded<'p>(&self, path: &'p str) -> Cow<'p, str> {
+        let path = if self.require_leading_slash && !path.starts_with('/') {
+            Cow::Owned(["/", path].concat())
+        } else {
+            Cow::Borrowed(path)
+        };
+        let path = if self.collapse_slashes {
+            collapse::collapse_separators(path, self.separator as u8)
+        } else {
+            path
+        };
+        if self.percent_decoding {
+            percent::percent_decode(path, self.separator as u8)
+        } else {
+            path
+        }
+    }
+
+    /// Applies the trie's path normalization to a raw byte path, like [`Trie::normalize`]
+    fn normalize_bytes<'p>(&self, path: &'p [u8]) -> Cow<'p, [u8]> {
+        let path = if self.require_leading_slash && !path.starts_with(b"/") {
+            Cow::Owned([b"/", path].concat())
+        } else {
+            Cow::Borrowed(path)
+        };
+        let path = if self.collapse_slashes {
+            collapse::collapse_separators_bytes(path, self.separator as u8)
+        } else {
+            path
+        };
+        let path = if self.percent_decoding {
+            percent::percent_decode_bytes(path, self.separator as u8)
+        } else {
+            path
+        };
+        if self.case_insensitive {
+            case::fold_case_bytes(path)
+        } else {
+            path
+        }
+    }
+
+    /// Parses a path to determine if it's a wildcard and extract the clean path
+    ///
+    /// A bare `*` is the root catch-all, the same as `/*`. An escaped suffix like `/\*`, as
+    /// in the key of a literal `/*` route, loses one backslash and names an exact route.
+    fn parse_path<'p>(&self, path: &'p str) -> (Cow<'p, str>, bool) {
+        if path == "*" {
+            (Cow::Borrowed(""), true)
+        } else if let Some(prefix) = path
+            .strip_suffix('*')
+            .and_then(|path| path.strip_suffix(self.separator))
+        {
+            (Cow::Borrowed(prefix), true)
+        } else if let Some(escape) = Self::star_suffix(path, self.separator)
+            && path[escape..].starts_with('\\')
+        {
+            let mut literal = path.to_string();
+            literal.remove(escape);
+            (Cow::Owned(literal), false)
+        } else {
+            (Cow::Borrowed(path), false)
+        }
+    }
+
+    /// Restores the wildcard suffix stripped by `parse_path`
+    ///
+    /// An exact route that would read as a wildcard has its suffix escaped instead, so a literal
+    /// `/*` route stored with [`Trie::insert_exact`] gets the key `/\*`, and a literal `/\*`
+    /// gets `/\\*`. Every slot thus has a distinct key, which `parse_path` maps back to it.
+    fn format_key(clean_path: &str, is_wildcard: bool, separator: char) -> String {
+        Self::into_key(clean_path.to_string(), is_wildcard, separator)
+    }
+
+    /// Turns the full path of a slot into its key like [`Trie::format_key`], reusing the
+    /// allocation
+    fn into_key(mut path: String, is_wildcard: bool, separator: char) -> String {
+        if is_wildcard {
+            path.push(separator);
+            path.push('*');
+        } else if let Some(escape) = Self::star_suffix(&path, separator) {
+            path.insert(escape, '\\');
+        }
+        path
+    }
+
+    /// Returns where a trailing `*` and the backslashes right before it start, if they form a
+    /// whole segment
+    fn star_suffix(path: &str, separator: char) -> Option<usize> {
+        let stem = path.strip_suffix('*')?.trim_end_matches('\\');
+        (stem.is_empty() || stem.ends_with(separator)).then_some(stem.len())
+    }
+
+    /// Returns the node whose full path is `clean_path`, creating or splitting nodes as needed
+    ///
+    /// Prefixes along the path longer than [`Trie::with_max_prefix_len`] are chunked.
+    fn node_mut(&mut self, clean_path: &str) -> &mut RadixNode<T> {
+        self.root.node_mut_chunked(clean_path, self.max_prefix_len)
+    }
+
+    /// Removes the value in an explicit slot, pruning nodes like [`Trie::remove`]
+    ///
+    /// Prefixes merged past [`Trie::with_max_prefix_len`] by the pruning are chunked again.
+    fn remove_slot(&mut self, clean_path: &str, is_wildcard: bool) -> Option<T> {
+        self.root
+            .remove_chunked(clean_path, is_wildcard, self.max_prefix_len)
+    }
+
+    /// Returns the slot targeted by the given path, if its node exists
+    fn find_slot_mut(&mut self, path: &str) -> Option<&mut Option<T>> {
+        let path = self.normalize(path);
+        let (clean_path, is_wildcard) = self.parse_path(&path);
+        Some(self.root.find_node_mut(&clean_path)?.slot_mut(is_wildcard))
+    }
+
+    /// Returns the number of routes, counting the exact and wildcard routes of a path
+    /// separately
+    ///
+    /// The sum of [`Trie::count_exact`] and [`Trie::count_wildcard`]. Every node is visited.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api", "api_root");
+    /// trie.insert("/api/*", "api_fallback");
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.root.value_count()
+    }
+
+    /// Checks if the trie stores no values
+    ///
+    /// Nodes left without values, e.g. by [`Trie::unset`], don't count, so this stops at the
+    /// first value found rather than checking the root alone.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users_handler");
+    /// assert!(!trie.is_empty());
+    ///
+    /// trie.unset("/api/users");
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        !self.root.has_values()
+    }
+}
+
+impl<T> core::ops::Index<&str> for Trie<T> {
+    type Output = T;
+
+    /// Returns the value for the given path, with the same precedence as [`Trie::get`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if no route matches the path. Use [`Trie::get`] when the route may be missing.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// assert_eq!(trie["/api/users"], "api_fallback");
+    /// ```
+    fn index(&self, path: &str) -> &T {
+        self.get(path)
+            .unwrap_or_else(|| panic!("no route matches path `{path}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_path_matching() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/api/posts", "posts_handler");
+
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("/api/posts"), Some(&"posts_handler"));
+        assert_eq!(trie.get("/api/other"), None);
+    }
+
+    #[test]
+    fn test_wildcard_matching() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_handler");
+
+        assert_eq!(trie.get("/api/users"), Some(&"api_handler"));
+        assert_eq!(trie.get("/api/posts/123"), Some(&"api_handler"));
+        assert_eq!(trie.get("/auth/login"), None);
+    }
+
+    #[test]
+    fn test_wildcard_applies_past_missing_children() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        assert_eq!(
+            trie.get("/api/deeply/nested/missing"),
+            Some(&"api_fallback")
+        );
+
+        // Descending into existing children that don't lead anywhere keeps the wildcard
+        trie.insert("/api/deeply/other", "other");
+        trie.insert("/api/deeply/nested/exact", "exact");
+        trie.insert("/api/*/posts", "posts");
+        for path in [
+            "/api/deeply/nested/missing",
+            "/api/deeply/nested/exact/more",
+            "/api/deeply/nested",
+            "/api/deeply/o",
+            "/api/x/posts/missing",
+        ] {
+            assert_eq!(trie.get(path), Some(&"api_fallback"), "{path}");
+            assert!(trie.matches_any(path), "{path}");
+        }
+        assert_eq!(trie.get("/api/deeply/nested/exact"), Some(&"exact"));
+        assert_eq!(trie.get("/apx/deeply"), None);
+    }
+
+    #[test]
+    fn test_exact_takes_precedence_over_wildcard() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "wildcard_handler");
+        trie.insert("/api/users", "exact_handler");
+
+        assert_eq!(trie.get("/api/users"), Some(&"exact_handler"));
+        assert_eq!(trie.get("/api/posts"), Some(&"wildcard_handler"));
+    }
+
+    #[test]
+    fn test_path_compression() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/users", "v1_users");
+        trie.insert("/api/v1/posts", "v1_posts");
+        trie.insert("/api/v2/users", "v2_users");
+
+        assert_eq!(trie.get("/api/v1/users"), Some(&"v1_users"));
+        assert_eq!(trie.get("/api/v1/posts"), Some(&"v1_posts"));
+        assert_eq!(trie.get("/api/v2/users"), Some(&"v2_users"));
+    }
+
+    #[test]
+    fn test_extend_with_tries() {
+        let mut base = Trie::new();
+        base.insert("/", "home");
+        base.insert("/api/users", "users_handler");
+
+        let mut plugin = Trie::new();
+        plugin.insert("/plugins/blog/*", "blog");
+        plugin.insert("/plugins/blog/feed", "feed");
+        plugin.insert_exact("/plugins/glob/*", "literal");
+
+        base.extend([plugin]);
+        assert_eq!(base.iter().count(), 5);
+        assert_eq!(base.get("/"), Some(&"home"));
+        assert_eq!(base.get("/api/users"), Some(&"users_handler"));
+        assert_eq!(base.get("/plugins/blog/post/1"), Some(&"blog"));
+        assert_eq!(base.get("/plugins/blog/feed"), Some(&"feed"));
+        assert_eq!(base.get_exact("/plugins/glob/*"), Some(&"literal"));
+        assert_eq!(base.get("/plugins/glob/anything"), None);
+
+        // The last trie wins on conflicts
+        let mut first = Trie::new();
+        first.insert("/api/users", "first");
+        let mut second = Trie::with_case_insensitive(true);
+        second.insert("/API/*", "second");
+        second.insert("/api/users", "second");
+        base.extend([first, second]);
+        assert_eq!(base.get("/api/users"), Some(&"second"));
+        assert_eq!(base.get("/api/posts"), Some(&"second"));
+        assert_eq!(base.iter().count(), 6);
+
+        // Priorities carry over, and keys aren't normalized a second time
+        let mut routes = Trie::with_named_params(true);
+        routes.insert("/u/*/p", "star");
+        routes.insert_with_priority("/u/:id/p", "param", 5);
+        let mut merged = Trie::with_named_params(true);
+        merged.extend([routes]);
+        assert_eq!(merged.get("/u/1/p"), Some(&"param"));
+
+        let mut escaped = Trie::with_percent_decoding(true);
+        escaped.insert("/files/%2541", "escaped");
+        let mut merged = Trie::with_percent_decoding(true);
+        merged.extend([escaped]);
+        assert_eq!(merged.get("/files/%2541"), Some(&"escaped"));
+        assert_eq!(merged.get("/files/A"), None);
+    }
+
+    #[test]
+    fn test_logical_eq_and_hash() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let routes = [
+            ("/api/*", 1),
+            ("/api/users", 2),
+            ("/api/users/*", 3),
+            ("/a", 4),
+        ];
+        let mut forward = Trie::new();
+        for (path, value) in routes {
+            forward.insert(path, value);
+        }
+        let mut backward = Trie::with_capacity(16);
+        for (path, value) in routes.into_iter().rev() {
+            backward.insert(path, value);
+        }
+        // A leftover intermediate node changes the shape, but not the routes
+        backward.insert("/api/u", 5);
+        backward.unset("/api/u");
+        assert_ne!(forward.node_count(), backward.node_count());
+
+        let hasher = RandomState::new();
+        assert_eq!(forward, backward);
+        assert_eq!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+
+        backward.insert("/a", 5);
+        assert_ne!(forward, backward);
+        assert_ne!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+        assert_ne!(forward, Trie::new());
+
+        // Priorities change which route `get` returns, so they count too
+        backward.insert("/a", 4);
+        backward.insert_with_priority("/api/*", 1, 3);
+        assert_ne!(forward, backward);
+        assert_ne!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+        forward.insert_with_priority("/api/*", 1, 3);
+        assert_eq!(forward, backward);
+        assert_eq!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+    }
+
+    #[test]
+    fn test_removal() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "handler");
+
+        assert_eq!(trie.get("/api/users"), Some(&"handler"));
+        assert_eq!(trie.remove("/api/users"), Some("handler"));
+        assert_eq!(trie.get("/api/users"), None);
+    }
+
+    #[test]
+    fn test_exact_and_wildcard_at_same_path() {
+        for policy in [MatchPolicy::ExactFirst, MatchPolicy::WildcardFirst] {
+            let mut trie = Trie::with_policy(policy);
+            trie.insert("/files", "exact");
+            trie.insert("/files/*", "wildcard");
+
+            let expected = match policy {
+                MatchPolicy::ExactFirst => "exact",
+                MatchPolicy::WildcardFirst => "wildcard",
+            };
+            assert_eq!(trie.get("/files"), Some(&expected));
+            assert_eq!(trie.get_exact("/files"), Some(&"exact"));
+            assert_eq!(trie.get("/files/"), Some(&"wildcard"));
+            assert_eq!(
+                trie.get_with_rest("/files/"),
+                Some((&"wildcard", Some(String::new())))
+            );
+            assert_eq!(
+                trie.get_with_rest("/files/a/b"),
+                Some((&"wildcard", Some("a/b".to_string())))
+            );
+            assert_eq!(trie.resolve("/files/").unwrap().matched_path(), "/files/*");
+            assert_eq!(trie.get("/file"), None);
+            assert_eq!(trie.get("/filesx"), None);
+            assert_eq!(trie.get_with_rest("/filesx/a"), None);
+            assert!(!trie.matches_any("/filesx"));
+
+            // An outer wildcard still covers what the inner one doesn't, here and for patterns
+            let mut nested = trie.clone();
+            nested.insert("/*", "root");
+            nested.insert("/file?/*", "char");
+            assert_eq!(nested.get("/filesx"), Some(&"root"));
+            assert_eq!(nested.get("/filex/a"), Some(&"char"));
+            assert_eq!(nested.get("/filexy"), Some(&"root"));
+            assert_eq!(
+                nested.get_with_rest("/filesx"),
+                Some((&"root", Some("filesx".to_string())))
+            );
+            assert_eq!(nested.freeze().get("/filesx"), Some(&"root"));
+            assert_eq!(trie.node_count(), 2);
+
+            // Either route can be removed without disturbing the other
+            let mut without_exact = Trie::with_policy(policy);
+            without_exact.insert("/files/*", "wildcard");
+            let mut without_wildcard = trie.clone();
+            assert_eq!(trie.remove("/files"), Some("exact"));
+            assert_eq!(trie, without_exact);
+            assert_eq!(trie.get("/files"), Some(&"wildcard"));
+            assert_eq!(without_wildcard.remove("/files/*"), Some("wildcard"));
+            assert_eq!(without_wildcard.get("/files"), Some(&"exact"));
+            assert_eq!(without_wildcard.get("/files/"), None);
+        }
+    }
+
+    #[test]
+    fn test_insert_aliases() {
+        let mut trie = Trie::new();
+        trie.insert("/auth/callback", "callback".to_string());
+        trie.insert_aliases(&["/login", "/signin", "/auth/*"], "auth".to_string());
+
+        for path in ["/login", "/signin", "/auth/other"] {
+            assert_eq!(trie.get(path), Some(&"auth".to_string()), "{path}");
+        }
+        assert_eq!(trie.get_exact("/login"), Some(&"auth".to_string()));
+        assert_eq!(trie.get_exact("/auth"), None);
+        assert_eq!(trie.get("/auth/callback"), Some(&"callback".to_string()));
+        assert_eq!(trie.iter().count(), 4);
+
+        trie.insert_aliases(&[], "unused".to_string());
+        assert_eq!(trie.iter().count(), 4);
+    }
+
+    #[test]
+    fn test_insert_with_priority() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/files/*/raw", "star");
+        trie.insert_with_priority("/files/:name/raw", "param", 5);
+        trie.insert("/files/?/raw", "char");
+
+        // `?` is tried first structurally, but loses to the higher priority
+        assert_eq!(trie.get("/files/a/raw"), Some(&"param"));
+        assert_eq!(trie.get("/files/abc/raw"), Some(&"param"));
+        assert_eq!(
+            trie.resolve("/files/a/raw").unwrap().matched_path(),
+            "/files/:name/raw"
+        );
+
+        trie.insert_with_priority("/files/*/raw", "star", 9);
+        assert_eq!(trie.get("/files/abc/raw"), Some(&"star"));
+
+        // Equal priorities keep the structural order
+        trie.insert("/files/*/raw", "star");
+        trie.insert("/files/:name/raw", "param");
+        assert_eq!(trie.get("/files/a/raw"), Some(&"char"));
+        assert_eq!(trie.get("/files/abc/raw"), Some(&"star"));
+
+        // Exact matches win regardless of priority
+        trie.insert_with_priority("/files/:name/raw", "param", i32::MAX);
+        trie.insert("/files/readme/raw", "readme");
+        assert_eq!(trie.get("/files/readme/raw"), Some(&"readme"));
+
+        // Priorities follow their values through node splits and removals
+        trie.insert("/files/abc/*", "abc");
+        trie.remove("/files/readme/raw");
+        assert_eq!(trie.get("/files/x/raw"), Some(&"param"));
+        trie.remove("/files/:name/raw");
+        trie.insert("/files/:name/raw", "param");
+        assert_eq!(trie.get("/files/x/raw"), Some(&"char"));
+
+        // A value filled into a slot emptied through `entry_slot` doesn't inherit its priority
+        trie.insert_with_priority("/files/:name/raw", "param", 5);
+        assert_eq!(trie.entry_slot("/files/:name/raw").take(), Some("param"));
+        *trie.entry_slot("/files/:name/raw") = Some("param");
+        assert_eq!(trie.get("/files/x/raw"), Some(&"char"));
+    }
+
+    #[test]
+    fn test_remove_if() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Route {
+            handler: &'static str,
+            disabled: bool,
+        }
+
+        let route = |handler, disabled| Route { handler, disabled };
+        let mut trie = Trie::new();
+        trie.insert("/api/*", route("api", true));
+        trie.insert("/api/users", route("users", false));
+
+        assert_eq!(trie.remove_if("/api/users", |route| route.disabled), None);
+        assert_eq!(trie.get("/api/users"), Some(&route("users", false)));
+
+        assert_eq!(
+            trie.remove_if("/api/*", |route| route.disabled),
+            Some(route("api", true))
+        );
+        assert_eq!(trie.get("/api/posts"), None);
+
+        // The predicate only sees the targeted slot, not covering wildcards
+        let mut called = false;
+        assert_eq!(
+            trie.remove_if("/api/posts", |_| {
+                called = true;
+                true
+            }),
+            None
+        );
+        assert!(!called);
+        assert_eq!(
+            trie.remove_if("/api/users", |_| true),
+            Some(route("users", false))
+        );
+        assert!(trie.is_empty());
+
+        // The tested slot is the one removed, without decoding the path a second time
+        let mut trie = Trie::with_percent_decoding(true);
+        trie.insert("/a%2541", route("encoded", true));
+        trie.insert("/aA", route("plain", false));
+        assert_eq!(
+            trie.remove_if("/a%2541", |route| route.disabled),
+            Some(route("encoded", true))
+        );
+        assert_eq!(trie.get("/aA"), Some(&route("plain", false)));
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_wildcard_removal() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "handler");
+
+        assert_eq!(trie.get("/api/users"), Some(&"handler"));
+        assert_eq!(trie.remove("/api/*"), Some("handler"));
+        assert_eq!(trie.get("/api/users"), None);
+    }
+
+    #[test]
+    fn test_empty_after_removing_everything() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 1);
+        trie.insert("/api/users", 2);
+        trie.insert("/api/users/*", 3);
+        trie.insert("/api/posts", 4);
+
+        for path in ["/api/users", "/api/*", "/api/posts", "/api/users/*"] {
+            assert!(!trie.is_empty());
+            assert!(trie.remove(path).is_some());
+        }
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+
+        // `unset` keeps the nodes, which no longer hold any value
+        trie.insert("/api/users", 2);
+        trie.insert("/api/posts", 4);
+        trie.unset("/api/users");
+        trie.unset("/api/posts");
+        assert!(trie.node_count() > 1);
+        assert!(trie.is_empty());
+
+        trie.insert_many([("/static/*".to_string(), 5)]);
+        assert_eq!(trie.iter().count(), 1);
+        assert_eq!(trie.get("/static/app.css"), Some(&5));
+    }
+
+    #[test]
+    fn test_root_path() {
+        let mut trie = Trie::new();
+        trie.insert("/", "root_handler");
+        assert_eq!(trie.get("/"), Some(&"root_handler"));
+    }
+
+    #[test]
+    fn test_root_wildcard() {
+        let mut trie = Trie::new();
+        trie.insert("/*", "root_handler");
+        assert_eq!(trie.get("/"), Some(&"root_handler"));
+    }
+
+    #[test]
+    fn test_empty_path() {
+        let mut trie = Trie::new();
+        trie.insert("", "empty_handler");
+        assert_eq!(trie.get(""), Some(&"empty_handler"));
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("long_prefix_one", "one");
+        trie.insert("long_prefix_two", "two");
+        trie.insert("long_prefix_three", "three");
 
         assert_eq!(trie.get("long_prefix_one"), Some(&"one"));
         assert_eq!(trie.get("long_prefix_two"), Some(&"two"));
         assert_eq!(trie.get("long_prefix_three"), Some(&"three"));
     }
+
+    #[test]
+    fn test_count_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/users", "v1_users");
+        trie.insert("/api/v1/posts", "v1_posts");
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/auth/login", "login");
+        trie.insert("/*", "root_fallback");
+
+        assert_eq!(trie.count_prefix("/api"), 3);
+        assert_eq!(trie.count_prefix("/api/"), 2);
+        assert_eq!(trie.count_prefix("/api/v1/u"), 1);
+        assert_eq!(trie.count_prefix("/a"), 4);
+        assert_eq!(trie.count_prefix(""), 5);
+        assert_eq!(trie.count_prefix("/nonexistent"), 0);
+        assert_eq!(trie.count_prefix("/api/v1/usersx"), 0);
+
+        assert_eq!(trie.count_prefix("/api"), trie.remove_prefix("/api"));
+    }
+
+    #[test]
+    fn test_contains_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", 1);
+        trie.insert("/api/posts/*", 2);
+
+        assert!(trie.contains_prefix("/api"));
+        assert!(trie.contains_prefix("/api/"));
+        assert!(trie.contains_prefix("/api/use"));
+        assert!(trie.contains_prefix("/api/users"));
+        assert!(trie.contains_prefix("/api/posts"));
+        assert!(trie.contains_prefix(""));
+        assert!(!trie.contains_prefix("/auth"));
+        assert!(!trie.contains_prefix("/api/users/7"));
+        assert!(!trie.contains_prefix("/api/usx"));
+
+        // Intermediate nodes left by `unset` hold no routes
+        trie.unset("/api/users");
+        assert!(!trie.contains_prefix("/api/users"));
+        assert!(trie.contains_prefix("/api"));
+        assert_eq!(
+            trie.contains_prefix("/api/u"),
+            trie.count_prefix("/api/u") > 0
+        );
+    }
+
+    #[test]
+    fn test_retain_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "users");
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/auth/login", "login");
+        trie.insert("/*", "root_fallback");
+        trie.insert("/apiv2", "apiv2");
+
+        trie.retain_prefix("/api/");
+        assert_eq!(trie.get("/api/users"), Some(&"users"));
+        assert_eq!(trie.get("/api/posts"), Some(&"api_fallback"));
+        assert_eq!(trie.get("/auth/login"), None);
+        assert_eq!(trie.get_exact("/apiv2"), None);
+
+        // Emptied nodes are pruned
+        let mut fresh = Trie::new();
+        fresh.insert("/api/users", "users");
+        fresh.insert("/api/*", "api_fallback");
+        assert_eq!(trie.node_count(), fresh.node_count());
+
+        trie.retain_prefix("/static");
+        assert!(trie.is_empty());
+
+        // Literal `/*` routes are matched on their own path, not the wildcard's key
+        let mut trie = Trie::new();
+        trie.insert("/api/a", "a");
+        trie.insert_exact("/x/*", "literal");
+        trie.insert_exact("/api/*", "api_literal");
+        let removed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = std::sync::Arc::clone(&removed);
+        trie.on_change(move |event| {
+            if let ChangeEvent::Removed { path } = event {
+                recorded.lock().unwrap().push(path.to_string());
+            }
+        });
+        trie.retain_prefix("/api");
+        assert_eq!(trie.get_exact("/x/*"), None);
+        assert_eq!(trie.get_exact("/api/*"), Some(&"api_literal"));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(*removed.lock().unwrap(), ["/x/\\*"]);
+    }
+
+    #[test]
+    fn test_depth_and_node_count() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.depth(), 1);
+        assert_eq!(trie.node_count(), 1);
+
+        trie.insert("/api/v1/users", "users");
+        trie.insert("/api/v1/posts", "posts");
+
+        // (root) -> "/api/v1/" -> { "users", "posts" }
+        assert_eq!(trie.depth(), 3);
+        assert_eq!(trie.node_count(), 4);
+        assert!(trie.node_count() < "/api/v1/users".len());
+
+        trie.insert("/auth", "auth");
+        assert_eq!(trie.depth(), 4); // (root) -> "/" -> "api/v1/" -> "users"
+        assert_eq!(trie.node_count(), 6);
+    }
+
+    #[test]
+    fn test_remove_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/users", "v1_users");
+        trie.insert("/api/v1/posts", "v1_posts");
+        trie.insert("/api/v1/*", "v1_fallback");
+        trie.insert("/api/v2/*", "v2_fallback");
+
+        assert_eq!(trie.remove_prefix("/api/v1"), 3);
+        assert_eq!(trie.get("/api/v1/users"), None);
+        assert_eq!(trie.get("/api/v1/posts"), None);
+        assert_eq!(trie.get("/api/v2/users"), Some(&"v2_fallback"));
+
+        // Only whole segments match, so `/api/v1` isn't a prefix of `/api/v10`
+        trie.insert("/api/v10/users", "v10_users");
+        trie.insert("/api/v10", "v10");
+        trie.insert("/api/v1/users", "v1_users");
+        trie.insert("/api/v1", "v1");
+        assert_eq!(trie.remove_prefix("/api/v1"), 2);
+        assert_eq!(trie.get("/api/v10/users"), Some(&"v10_users"));
+        assert_eq!(trie.get("/api/v10"), Some(&"v10"));
+        assert_eq!(trie.remove_prefix("/api/v"), 0);
+        assert_eq!(trie.remove_prefix("/api/v10/"), 1);
+        assert_eq!(trie.get("/api/v10"), Some(&"v10"));
+        assert_eq!(trie.remove_prefix("/api/v10"), 1);
+
+        // Diverging and already-removed prefixes remove nothing
+        assert_eq!(trie.remove_prefix("/api/vx"), 0);
+        assert_eq!(trie.remove_prefix("/api/v1"), 0);
+        assert_eq!(trie.remove_prefix(""), 1);
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/api/users", 1);
+        trie.insert("/api/*", 2);
+        trie.insert("/api", 3);
+        trie.insert("/auth/login", 4);
+        trie.insert_exact("/static/*", 5);
+        trie.insert_with_priority("/files/:name", 6, 7);
+        trie.insert("/files/*", 8);
+        let original: Vec<_> = trie
+            .iter_sorted()
+            .map(|(key, value)| (key, *value))
+            .collect();
+
+        let (even, odd) = trie.partition(|value| value % 2 == 0);
+        assert!(even.iter().all(|(_, value)| value % 2 == 0));
+        assert!(odd.iter().all(|(_, value)| value % 2 == 1));
+        let mut union: Vec<_> = even
+            .iter_sorted()
+            .chain(odd.iter_sorted())
+            .map(|(key, value)| (key, *value))
+            .collect();
+        union.sort();
+        assert_eq!(union, original);
+
+        // Slots and priorities are kept
+        assert_eq!(odd.get("/static/app.css"), None);
+        assert_eq!(odd.get_exact("/static/*"), Some(&5));
+        assert_eq!(even.get("/api/posts"), Some(&2));
+        assert_eq!(even.get("/files/a"), Some(&6));
+
+        let (all, none) = Trie::<i32>::new().partition(|_| true);
+        assert!(all.is_empty() && none.is_empty());
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/users", "v1_users");
+        trie.insert("/api/v1/posts", "v1_posts");
+        trie.insert("/api/v1/*", "v1_fallback");
+        trie.insert("/api/v2/users", "v2_users");
+        trie.insert("/api/*", "api_fallback");
+
+        let v1 = trie.split_off("/api/v1");
+        assert_eq!(v1.iter().count(), 3);
+        assert_eq!(v1.get("/users"), Some(&"v1_users"));
+        assert_eq!(v1.get("/posts"), Some(&"v1_posts"));
+        assert_eq!(v1.get("/other"), Some(&"v1_fallback"));
+        let keys: Vec<_> = v1.iter_sorted().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["/*", "/posts", "/users"]);
+
+        assert_eq!(trie.iter().count(), 2);
+        assert_eq!(trie.get("/api/v1/users"), Some(&"api_fallback"));
+        assert_eq!(trie.get("/api/v2/users"), Some(&"v2_users"));
+
+        // A prefix ending mid-node splits that node's prefix
+        let v2 = trie.split_off("/api/v2/us");
+        assert_eq!(v2.get("ers"), Some(&"v2_users"));
+        assert_eq!(trie.iter().count(), 1);
+
+        assert!(trie.split_off("/missing").is_empty());
+        assert_eq!(trie.split_off("").get("/api/*"), Some(&"api_fallback"));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_get_all_matches_mut() {
+        let mut trie = Trie::new();
+        trie.insert("/*", 0);
+        trie.insert("/api/*", 0);
+        trie.insert("/api/v1/*", 0);
+        trie.insert("/api/v1/users", 0);
+        trie.insert("/api/v2/*", 0);
+
+        for (step, hits) in trie
+            .get_all_matches_mut("/api/v1/users")
+            .into_iter()
+            .enumerate()
+        {
+            *hits += step + 1;
+        }
+        assert_eq!(trie.get("/api/v1/users"), Some(&1));
+        assert_eq!(trie.get("/api/v1/posts"), Some(&2));
+        assert_eq!(trie.get("/api/v3"), Some(&3));
+        assert_eq!(trie.get("/static"), Some(&4));
+        assert_eq!(trie.get("/api/v2/x"), Some(&0));
+
+        assert_eq!(trie.get_all_matches_mut("/api/v1/posts").len(), 3);
+        assert!(Trie::<u8>::new().get_all_matches_mut("/api").is_empty());
+    }
+
+    #[test]
+    fn test_get_all_matches() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/v1/*", "v1_fallback");
+        trie.insert("/api/v1/users", "users_handler");
+        trie.insert("/auth/*", "auth_fallback");
+
+        assert_eq!(
+            trie.get_all_matches("/api/v1/users"),
+            vec![&"users_handler", &"v1_fallback", &"api_fallback"]
+        );
+        assert_eq!(
+            trie.get_all_matches("/api/v1/posts"),
+            vec![&"v1_fallback", &"api_fallback"]
+        );
+        assert_eq!(trie.get_all_matches("/api/v2"), vec![&"api_fallback"]);
+        assert!(trie.get_all_matches("/static/app.css").is_empty());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut trie = Trie::new();
+        trie.insert("/*", 1);
+        trie.insert("/api/*", 2);
+        trie.insert("/api/v1/*", 3);
+        trie.insert("/api/v1/users", 4);
+
+        let all: Vec<_> = trie.candidates("/api/v1/users").collect();
+        assert_eq!(all, [&4, &3, &2, &1]);
+        assert_eq!(all, trie.get_all_matches("/api/v1/users"));
+        assert_eq!(trie.candidates("/api/v2").collect::<Vec<_>>(), [&2, &1]);
+        assert_eq!(trie.candidates("/api/v1/posts").next(), Some(&3));
+
+        // Consumers only inspect the candidates they advance to
+        let inspected = core::cell::Cell::new(0);
+        let found = trie.candidates("/api/v1/users").find(|&&value| {
+            inspected.set(inspected.get() + 1);
+            value < 4
+        });
+        assert_eq!(found, Some(&3));
+        assert_eq!(inspected.get(), 2);
+        assert_eq!(trie.candidates("/api/v1/users").take(1).count(), 1);
+
+        assert_eq!(Trie::<i32>::new().candidates("/api").next(), None);
+    }
+
+    #[test]
+    fn test_get_segments() {
+        let mut trie = Trie::new();
+        trie.insert("/*", "root");
+        trie.insert("/files/*", "files");
+        trie.insert("/files/images/*", "images");
+        trie.insert("/files/readme", "readme");
+
+        let segments = |path| {
+            trie.get_segments(path)
+                .map(|(value, segments)| (*value, segments))
+        };
+        let owned = |segments: &[&str]| segments.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            segments("/files/a/b/c"),
+            Some(("files", owned(&["a", "b", "c"])))
+        );
+        assert_eq!(
+            segments("/files/images/2024/cat.png"),
+            Some(("images", owned(&["2024", "cat.png"])))
+        );
+        assert_eq!(
+            segments("/static/app.css"),
+            Some(("root", owned(&["static", "app.css"])))
+        );
+        assert_eq!(
+            segments("/files/a//b/"),
+            Some(("files", owned(&["a", "", "b", ""])))
+        );
+        assert_eq!(segments("/files/"), Some(("files", vec![])));
+        assert_eq!(segments("/files/readme"), Some(("readme", vec![])));
+
+        let mut dotted = Trie::with_separator('.');
+        dotted.insert("com.example.*", "example");
+        assert_eq!(
+            dotted.get_segments("com.example.app.web"),
+            Some((&"example", owned(&["app", "web"])))
+        );
+        assert_eq!(dotted.get_segments("org.example"), None);
+    }
+
+    #[test]
+    fn test_get_with_rest() {
+        let mut trie = Trie::new();
+        trie.insert("/*", "root_fallback");
+        trie.insert("/static/*", "static_files");
+        trie.insert("/static/images/*", "images");
+        trie.insert("/static/index.html", "index");
+
+        let rest = |path| trie.get_with_rest(path).map(|(v, rest)| (*v, rest));
+        assert_eq!(
+            rest("/static/css/app.css"),
+            Some(("static_files", Some("css/app.css".to_string())))
+        );
+        assert_eq!(
+            rest("/static/images/logo/large.png"),
+            Some(("images", Some("logo/large.png".to_string())))
+        );
+        assert_eq!(
+            rest("/favicon.ico"),
+            Some(("root_fallback", Some("favicon.ico".to_string())))
+        );
+        assert_eq!(rest("/static"), Some(("static_files", Some(String::new()))));
+        assert_eq!(rest("/static/index.html"), Some(("index", None)));
+    }
+
+    #[test]
+    fn test_segment_wildcards_in_derived_lookups() {
+        let mut trie = Trie::new();
+        trie.insert("/*", "root_fallback");
+        trie.insert("/repos/*/files/*", "files");
+        trie.insert("/repos/*/files/readme", "readme");
+        trie.insert("/repos/*/*/raw", "raw");
+
+        // Each lookup sees the routes `get` matches, not only the literal prefixes of the path
+        assert_eq!(
+            trie.get_with_rest("/repos/app/files/src/main.rs"),
+            Some((&"files", Some("src/main.rs".to_string())))
+        );
+        assert_eq!(
+            trie.get_all_matches("/repos/app/files/readme"),
+            [&"readme", &"files", &"root_fallback"]
+        );
+        // The literal `files` segment wins over the `*` before `raw`, as it does in `get`
+        assert_eq!(trie.get("/repos/app/files/raw"), Some(&"files"));
+        assert_eq!(
+            trie.candidates("/repos/app/files/raw").collect::<Vec<_>>(),
+            [&"files", &"raw", &"root_fallback"]
+        );
+        let found = trie.find_match("/repos/app/files/readme", |v| v.ends_with('s'));
+        assert_eq!(found, Some(&"files"));
+        assert_eq!(trie.get_all_matches("/repos/app/other"), [&"root_fallback"]);
+    }
+
+    #[test]
+    fn test_find_match() {
+        let mut trie = Trie::new();
+        trie.insert("/*", 0);
+        trie.insert("/api/*", 1);
+        trie.insert("/api/v1/*", 2);
+        trie.insert("/api/v1/users", 3);
+
+        assert_eq!(trie.find_match("/api/v1/users", |_| true), Some(&3));
+        assert_eq!(trie.find_match("/api/v1/users", |v| *v < 3), Some(&2));
+        assert_eq!(trie.find_match("/api/v1/users", |v| *v < 2), Some(&1));
+        assert_eq!(trie.find_match("/api/v1/users", |v| v % 2 == 0), Some(&2));
+        assert_eq!(trie.find_match("/api/v1/users", |v| *v == 0), Some(&0));
+        assert_eq!(trie.find_match("/api/v1/users", |v| *v > 3), None);
+    }
+
+    #[test]
+    fn test_multi_byte_common_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("/café/a", "cafe_a");
+        trie.insert("/café/b", "cafe_b");
+        trie.insert("/🎉a", "party_a");
+        trie.insert("/🎉b", "party_b");
+        trie.insert("/🎈/*", "balloons");
+        trie.insert("/naïve", "naive");
+
+        assert_eq!(trie.get("/café/a"), Some(&"cafe_a"));
+        assert_eq!(trie.get("/café/b"), Some(&"cafe_b"));
+        assert_eq!(trie.get("/cafe/a"), None);
+        assert_eq!(trie.get("/🎉a"), Some(&"party_a"));
+        assert_eq!(trie.get("/🎉b"), Some(&"party_b"));
+        assert_eq!(trie.get("/🎈/red"), Some(&"balloons"));
+        assert_eq!(trie.get("/naïve"), Some(&"naive"));
+
+        assert_eq!(trie.remove("/🎉a"), Some("party_a"));
+        assert_eq!(trie.get("/🎉b"), Some(&"party_b"));
+    }
+
+    #[test]
+    fn test_wildcard_first_policy() {
+        let mut trie = Trie::with_policy(MatchPolicy::WildcardFirst);
+        trie.insert("/api/*", "wildcard_handler");
+        trie.insert("/api/users", "exact_handler");
+        trie.insert("/auth/login", "login_handler");
+
+        assert_eq!(trie.policy(), MatchPolicy::WildcardFirst);
+        assert_eq!(trie.get("/api/users"), Some(&"wildcard_handler"));
+        assert_eq!(trie.get("/api/posts"), Some(&"wildcard_handler"));
+        assert_eq!(trie.get("/auth/login"), Some(&"login_handler"));
+
+        // Lookups built on `get` follow the same precedence
+        trie.insert("/*", "root_handler");
+        assert_eq!(
+            trie.get_with_rest("/api/users"),
+            Some((&"wildcard_handler", Some("users".to_string())))
+        );
+        assert_eq!(
+            trie.get_all_matches("/api/users"),
+            [&"wildcard_handler", &"root_handler", &"exact_handler"]
+        );
+        assert_eq!(
+            trie.candidates("/api/users").collect::<Vec<_>>(),
+            trie.get_all_matches("/api/users")
+        );
+        let found = trie.find_match("/api/users", |handler| !handler.starts_with("wildcard"));
+        assert_eq!(found, Some(&"root_handler"));
+        assert_eq!(
+            trie.find_match("/api/users", |handler| handler.starts_with('e')),
+            Some(&"exact_handler")
+        );
+        let frozen = trie.freeze();
+        assert_eq!(
+            frozen.get_with_rest("/api/users"),
+            Some((&"wildcard_handler", Some("users".to_string())))
+        );
+
+        assert_eq!(Trie::<()>::new().policy(), MatchPolicy::ExactFirst);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 0);
+
+        // Inserts into the exact slot, ignoring the wildcard fallback
+        *trie.get_or_insert_with("/api/users", || 1) += 10;
+        assert_eq!(trie.get("/api/users"), Some(&11));
+
+        // Existing values are returned without calling `f`
+        let value = trie.get_or_insert_with("/api/users", || unreachable!());
+        assert_eq!(*value, 11);
+        *trie.get_or_insert_with("/api/*", || unreachable!()) += 5;
+        assert_eq!(trie.get("/api/posts"), Some(&5));
+    }
+
+    #[test]
+    fn test_insert_with_combines_values() {
+        let sum = |old, new| old + new;
+        let mut trie = Trie::new();
+        trie.insert("/*", 100);
+
+        trie.insert_with("/a", 3, sum);
+        trie.insert_with("/a", 5, sum);
+        assert_eq!(trie.get("/a"), Some(&8));
+
+        // A fresh slot just stores the value, ignoring the covering wildcard
+        trie.insert_with("/b", 7, |_, _| unreachable!());
+        assert_eq!(trie.get("/b"), Some(&7));
+
+        // The wildcard slot is combined on its own
+        trie.insert_with("/*", 1, sum);
+        assert_eq!(trie.get("/c"), Some(&101));
+        assert_eq!(trie.get("/a"), Some(&8));
+    }
+
+    #[test]
+    fn test_insert_many() {
+        let routes = [
+            ("/static/*", 1),
+            ("/api/v1/users", 2),
+            ("/api/*", 3),
+            ("/api/v1/posts", 4),
+            ("/api/v1/users", 5),
+            ("/", 6),
+        ];
+
+        let mut batch = Trie::new();
+        batch.insert_many(routes.iter().map(|(path, v)| (path.to_string(), *v)));
+        let mut sequential = Trie::new();
+        for (path, value) in routes {
+            sequential.insert(path, value);
+        }
+
+        assert_eq!(batch.node_count(), sequential.node_count());
+        for path in [
+            "/",
+            "/api/v1/users",
+            "/api/v1/posts",
+            "/api/x",
+            "/static/a",
+            "/x",
+        ] {
+            assert_eq!(batch.get(path), sequential.get(path), "{path}");
+        }
+        assert_eq!(batch.get("/api/v1/users"), Some(&5));
+    }
+
+    #[test]
+    fn test_insert_many_into_empty_trie_matches_sequential() {
+        let routes = [
+            "*",
+            "",
+            "/",
+            "/api",
+            "/api/*",
+            "/api/",
+            "/api/v1/users",
+            "/api/v1/*",
+            "/api/v10",
+            "/api/*/posts",
+            "/api/v1/users",
+            "/apiv2",
+            "/é/x",
+            "/é/y",
+            "users",
+        ];
+
+        for make in [
+            Trie::new,
+            || Trie::with_require_leading_slash(true),
+            || Trie::with_separator('.'),
+        ] {
+            let mut batch = make();
+            batch.insert_many(
+                routes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, path)| (path.to_string(), i)),
+            );
+            let mut sequential = make();
+            for (i, path) in routes.iter().enumerate() {
+                sequential.insert(path, i);
+            }
+
+            assert_eq!(batch.node_count(), sequential.node_count());
+            assert_eq!(
+                batch.iter_sorted().collect::<Vec<_>>(),
+                sequential.iter_sorted().collect::<Vec<_>>()
+            );
+            for path in routes
+                .iter()
+                .chain(&["/api/x/posts", "/api/v1", "/é", "/z"])
+            {
+                assert_eq!(batch.get(path), sequential.get(path), "{path}");
+            }
+        }
+
+        // A trie that already has routes keeps them
+        let mut trie = Trie::with_separator('.');
+        trie.insert("com.example", 0);
+        trie.insert_many([("com.*".to_string(), 1), ("com.example".to_string(), 2)]);
+        assert_eq!(trie.get("com.example"), Some(&2));
+        assert_eq!(trie.get("com.other"), Some(&1));
+    }
+
+    #[test]
+    fn test_remove_entry() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/*", "root_fallback");
+
+        assert_eq!(
+            trie.remove_entry("/api/users"),
+            Some(("/api/users".to_string(), "users_handler"))
+        );
+        assert_eq!(
+            trie.remove_entry("/api/*"),
+            Some(("/api/*".to_string(), "api_fallback"))
+        );
+        assert_eq!(
+            trie.remove_entry("/*"),
+            Some(("/*".to_string(), "root_fallback"))
+        );
+        assert_eq!(trie.remove_entry("/api/users"), None);
+        assert_eq!(trie.remove_entry("/ap"), None);
+
+        // The path is decoded once, so `%2541` names the route stored as `/a%41`
+        let mut trie = Trie::with_percent_decoding(true);
+        trie.insert("/a%2541", "encoded");
+        trie.insert("/aA", "plain");
+        assert_eq!(
+            trie.remove_entry("/a%2541"),
+            Some(("/a%41".to_string(), "encoded"))
+        );
+        assert_eq!(trie.get("/aA"), Some(&"plain"));
+    }
+
+    #[test]
+    fn test_single_segment_wildcard() {
+        let mut trie = Trie::new();
+        trie.insert("/a/*/b", "segment");
+
+        assert_eq!(trie.get("/a/x/b"), Some(&"segment"));
+        assert_eq!(trie.get("/a/yz/b"), Some(&"segment"));
+        assert_eq!(trie.get("/a/x/y/b"), None);
+        assert_eq!(trie.get("/a//b"), None);
+        assert_eq!(trie.get("/a/x/bc"), None);
+        assert_eq!(trie.get("/a/x"), None);
+    }
+
+    #[test]
+    fn test_single_segment_wildcard_precedence() {
+        let mut trie = Trie::new();
+        trie.insert("/a/*", "catch_all");
+        trie.insert("/a/*/b", "segment");
+        trie.insert("/a/x/b", "literal");
+        trie.insert("/users/*/posts/*", "user_posts");
+
+        // Literal beats single-segment wildcard, which beats the trailing catch-all
+        assert_eq!(trie.get("/a/x/b"), Some(&"literal"));
+        assert_eq!(trie.get("/a/y/b"), Some(&"segment"));
+        assert_eq!(trie.get("/a/y/c"), Some(&"catch_all"));
+        assert_eq!(trie.get("/a/x/c"), Some(&"catch_all"));
+
+        assert_eq!(trie.get("/users/7/posts"), Some(&"user_posts"));
+        assert_eq!(trie.get("/users/7/posts/42"), Some(&"user_posts"));
+        assert_eq!(trie.get("/users/7/comments"), None);
+    }
+
+    #[test]
+    fn test_char_wildcard() {
+        let mut trie = Trie::new();
+        trie.insert("/file-?.txt", "single");
+        trie.insert("/x?y", "glob");
+
+        assert_eq!(trie.get("/file-a.txt"), Some(&"single"));
+        assert_eq!(trie.get("/file-b.txt"), Some(&"single"));
+        assert_eq!(trie.get("/file-é.txt"), Some(&"single"));
+        assert_eq!(trie.get("/file-ab.txt"), None);
+        assert_eq!(trie.get("/file-.txt"), None);
+        assert!(trie.matches_any("/file-a.txt"));
+        assert!(!trie.matches_any("/file-ab.txt"));
+
+        // `?` never matches the separator
+        assert_eq!(trie.get("/xzy"), Some(&"glob"));
+        assert_eq!(trie.get("/x/y"), None);
+
+        // Literal beats `?`, which beats a single-segment wildcard
+        trie.insert("/file-a.txt", "literal");
+        trie.insert("/v/?", "char");
+        trie.insert("/v/*", "segment");
+        assert_eq!(trie.get("/file-a.txt"), Some(&"literal"));
+        assert_eq!(trie.get("/file-b.txt"), Some(&"single"));
+        assert_eq!(trie.get("/v/1"), Some(&"char"));
+        assert_eq!(trie.get("/v/12"), Some(&"segment"));
+
+        // Lookups built on `get` see `?` routes too, and skip one character per `?`
+        trie.insert("/logs-?é/*", "logs");
+        assert_eq!(
+            trie.get_with_rest("/logs-éé/2024/01"),
+            Some((&"logs", Some("2024/01".to_string())))
+        );
+        assert_eq!(trie.get_all_matches("/file-a.txt"), [&"literal", &"single"]);
+        assert_eq!(trie.find_match("/v/1", |v| *v != "char"), Some(&"segment"));
+        assert_eq!(trie.explain("/logs-xé/a").wildcards()[0].rest(), "a");
+    }
+
+    #[test]
+    fn test_on_change_events() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+
+        let mut trie = Trie::new();
+        trie.on_change(move |event| {
+            let event = match event {
+                ChangeEvent::Inserted { path, replaced } => (path.to_string(), Some(replaced)),
+                ChangeEvent::Removed { path } => (path.to_string(), None),
+            };
+            recorded.lock().unwrap().push(event);
+        });
+
+        trie.insert("/api/users", 1);
+        trie.insert("/api/users", 2);
+        trie.insert("/api/*", 3);
+        trie.remove("/api/users");
+        trie.remove("/api/users"); // Nothing removed, no event
+        trie.remove_entry("/api/*");
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ("/api/users".to_string(), Some(false)),
+                ("/api/users".to_string(), Some(true)),
+                ("/api/*".to_string(), Some(false)),
+                ("/api/users".to_string(), None),
+                ("/api/*".to_string(), None),
+            ]
+        );
+
+        // Filling an empty slot is reported, reusing a filled one isn't
+        let take = || core::mem::take(&mut *events.lock().unwrap());
+        take();
+        *trie.get_or_insert_with("/a", || 1) += 1;
+        *trie.entry("/a").or_insert(5) += 1;
+        *trie.entry("/b/*").or_default() += 1;
+        assert_eq!(
+            take(),
+            vec![
+                ("/a".to_string(), Some(false)),
+                ("/b/*".to_string(), Some(false))
+            ]
+        );
+
+        // Bulk removals report each route they take
+        for path in ["/b/c", "/b", "/bc", "/d/e/*", "/d/f", "/g"] {
+            trie.insert(path, 0);
+        }
+        take();
+        assert_eq!(trie.remove_prefix("/b"), 3);
+        let mut removed = take();
+        removed.sort();
+        let expected = ["/b", "/b/*", "/b/c"].map(|path| (path.to_string(), None));
+        assert_eq!(removed, expected);
+
+        let _ = trie.split_off("/d");
+        let mut removed = take();
+        removed.sort();
+        assert_eq!(
+            removed,
+            ["/d/e/*", "/d/f"].map(|path| (path.to_string(), None))
+        );
+
+        assert_eq!(trie.drain().count(), 3);
+        let mut removed = take();
+        removed.sort();
+        assert_eq!(
+            removed,
+            ["/a", "/bc", "/g"].map(|path| (path.to_string(), None))
+        );
+    }
+
+    #[test]
+    fn test_deep_trie_lookup() {
+        const DEPTH: usize = 2000;
+
+        let mut trie = Trie::new();
+        let mut path = String::new();
+        let mut paths = Vec::new();
+        for level in 0..DEPTH {
+            path.push_str("/a");
+            if level % 10 == 0 {
+                trie.insert(&format!("{path}/*"), level);
+            } else {
+                trie.insert(&path, level);
+            }
+            paths.push(path.clone());
+        }
+        assert_eq!(trie.depth(), DEPTH + 1);
+
+        // Compare with `candidates`, whose first match is what `get` returns
+        for query in [&paths[DEPTH - 1], &paths[DEPTH / 2], &paths[DEPTH / 3]] {
+            let deeper = format!("{query}/b/c");
+            assert_eq!(trie.get(query), trie.candidates(query).next());
+            assert_eq!(trie.get(&deeper), trie.candidates(&deeper).next());
+        }
+        assert_eq!(trie.get(&paths[DEPTH - 1]), Some(&(DEPTH - 1)));
+        assert_eq!(trie.get(&format!("{}/b", paths[DEPTH - 1])), Some(&1990));
+    }
+
+    #[test]
+    fn test_update() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", 10);
+        trie.insert("/api/users", 1);
+
+        assert!(trie.update("/api/users", |v| *v += 1));
+        assert!(trie.update("/api/*", |v| *v *= 2));
+        assert_eq!(trie.get("/api/users"), Some(&2));
+        assert_eq!(trie.get("/api/posts"), Some(&20));
+
+        // Absent paths are left untouched, even when a wildcard covers them
+        assert!(!trie.update("/api/posts", |v| *v = 0));
+        assert!(!trie.update("/missing", |v| *v = 0));
+        assert_eq!(trie.get("/api/posts"), Some(&20));
+        assert_eq!(trie.get("/missing"), None);
+    }
+
+    #[test]
+    fn test_require_leading_slash_normalizes() {
+        let mut trie = Trie::with_require_leading_slash(true);
+        trie.insert("api/users", "users_handler");
+        trie.insert("static/*", "static_files");
+
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("api/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("/static/app.css"), Some(&"static_files"));
+        assert_eq!(trie.remove("api/users"), Some("users_handler"));
+        assert_eq!(trie.get("/api/users"), None);
+
+        // Without the option the paths stay distinct
+        let mut trie = Trie::new();
+        trie.insert("api/users", "users_handler");
+        assert_eq!(trie.get("/api/users"), None);
+    }
+
+    #[test]
+    fn test_try_insert() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+
+        // A covering wildcard doesn't occupy the exact slot
+        assert_eq!(trie.try_insert("/api/users", "users_handler"), Ok(()));
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+
+        assert_eq!(trie.try_insert("/api/users", "other"), Err("other"));
+        assert_eq!(trie.try_insert("/api/*", "other"), Err("other"));
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("/api/posts"), Some(&"api_fallback"));
+    }
+
+    #[test]
+    fn test_insert_batch_atomic() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        let before: Vec<_> = trie
+            .iter_sorted()
+            .map(|(key, value)| (key, *value))
+            .collect();
+        let node_count = trie.node_count();
+
+        let batch = |paths: &[&'static str]| {
+            paths
+                .iter()
+                .map(|path| (path.to_string(), *path))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            trie.insert_batch_atomic(batch(&["/api/posts", "/api/posts/*", "/api/users", "/x"])),
+            Err((2, "/api/users"))
+        );
+        assert_eq!(
+            trie.insert_batch_atomic(batch(&["/a", "/b/*", "/a"])),
+            Err((2, "/a"))
+        );
+        let after: Vec<_> = trie
+            .iter_sorted()
+            .map(|(key, value)| (key, *value))
+            .collect();
+        assert_eq!(after, before);
+        assert_eq!(trie.node_count(), node_count);
+
+        // Covering wildcards don't conflict, and `/api/users/*` is a separate slot
+        assert_eq!(
+            trie.insert_batch_atomic(batch(&["/api/posts", "/api/users/*"])),
+            Ok(())
+        );
+        assert_eq!(trie.get("/api/posts"), Some(&"/api/posts"));
+        assert_eq!(trie.get("/api/users/7"), Some(&"/api/users/*"));
+        assert_eq!(trie.insert_batch_atomic(Vec::new()), Ok(()));
+    }
+
+    #[test]
+    fn test_insert_strict_rejects_missing_leading_slash() {
+        let mut trie = Trie::with_require_leading_slash(true);
+
+        let error = trie
+            .insert_strict("api/users", "users_handler")
+            .unwrap_err();
+        assert_eq!(error, PathError::MissingLeadingSlash);
+        assert_eq!(error.to_string(), "path must start with `/`");
+        assert!(trie.is_empty());
+
+        assert_eq!(trie.insert_strict("/api/users", "users_handler"), Ok(()));
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+    }
+
+    #[test]
+    fn test_validate_path() {
+        for path in [
+            "",
+            "/",
+            "*",
+            "/*",
+            "/api/*",
+            "/users/*/posts",
+            "/*/x/*",
+            "/api/",
+        ] {
+            assert_eq!(Trie::<()>::validate_path(path), Ok(()), "{path}");
+        }
+
+        let error_of = |path| Trie::<()>::validate_path(path).unwrap_err();
+        assert_eq!(error_of("//"), PathError::EmptySegment);
+        assert_eq!(error_of("/api//users"), PathError::EmptySegment);
+        assert_eq!(error_of("/api*"), PathError::WildcardNotAfterSeparator);
+        assert_eq!(
+            error_of("/api/v*/users"),
+            PathError::WildcardNotAfterSeparator
+        );
+        assert_eq!(error_of("/api/*.json"), PathError::MisplacedWildcard);
+        assert_eq!(error_of("/**"), PathError::MisplacedWildcard);
+        assert_eq!(
+            error_of("/api/*x").to_string(),
+            "`*` must be a whole segment, followed by `/` or the end of the path"
+        );
+    }
+
+    #[test]
+    fn test_prefix_values() {
+        let mut trie = Trie::new();
+        trie.insert("/a", 1);
+        trie.insert("/a/b", 2);
+        trie.insert("/a/b/c", 3);
+        trie.insert("/a/x", 4);
+        trie.insert("/a/b/*", 5);
+        trie.insert("/z", 6);
+
+        assert_eq!(trie.prefix_values("/a/b/c"), vec![&1, &2, &3]);
+        assert_eq!(trie.prefix_values("/a/b/c/d"), vec![&1, &2, &3]);
+        assert_eq!(trie.prefix_values("/a/b"), vec![&1, &2]);
+        assert_eq!(trie.prefix_values("/a/x"), vec![&1, &4]);
+        assert!(trie.prefix_values("/b").is_empty());
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let mut trie = Trie::new();
+        trie.insert("/a", 1);
+        trie.insert("/a/b", 2);
+        trie.insert("/a/b/*", 3);
+        trie.insert("/a/bc", 4);
+        trie.insert("/x", 5);
+
+        let keys = |path| {
+            trie.ancestors(path)
+                .into_iter()
+                .map(|(key, value)| (key, *value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            keys("/a/b/c"),
+            [("/a".to_string(), 1), ("/a/b".to_string(), 2)]
+        );
+        assert_eq!(
+            keys("/a/b"),
+            [("/a".to_string(), 1), ("/a/b".to_string(), 2)]
+        );
+        // `/a/b` is a split node for `/a/bc`, but `/a/` is only an intermediate node
+        assert_eq!(keys("/a/"), [("/a".to_string(), 1)]);
+        assert!(keys("/b").is_empty());
+
+        let mut trie = Trie::with_require_leading_slash(true);
+        trie.insert("a", 1);
+        assert_eq!(trie.ancestors("a/b"), [("/a".to_string(), &1)]);
+    }
+
+    #[test]
+    fn test_overlapping_routes() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", 1);
+        trie.insert("/api/posts", 2);
+        trie.insert("/api/posts/*", 3);
+        trie.insert("/auth/login", 4);
+
+        assert_eq!(
+            trie.overlapping_routes("/api/*"),
+            ["/api/posts", "/api/posts/*", "/api/users"]
+        );
+        assert_eq!(trie.overlapping_routes("/api/posts/7"), ["/api/posts/*"]);
+        assert!(trie.overlapping_routes("/api/comments").is_empty());
+        assert!(trie.overlapping_routes("/static/*").is_empty());
+
+        // Broader wildcards and the pattern's own slot both interact with it, as does the
+        // exact route at its prefix
+        trie.insert("/*", 5);
+        trie.insert("/api/*", 6);
+        assert_eq!(
+            trie.overlapping_routes("/api/posts/*"),
+            ["/*", "/api/*", "/api/posts", "/api/posts/*"]
+        );
+        assert_eq!(
+            trie.overlapping_routes("/api/posts"),
+            ["/*", "/api/*", "/api/posts", "/api/posts/*"]
+        );
+        assert_eq!(trie.overlapping_routes("*").len(), trie.iter().count());
+
+        // A wildcard only overlaps paths continuing past its prefix at a segment boundary
+        trie.insert("/files/*", 7);
+        assert_eq!(trie.overlapping_routes("/files/a"), ["/*", "/files/*"]);
+        assert_eq!(trie.overlapping_routes("/filesx"), ["/*"]);
+        assert_eq!(trie.overlapping_routes("/api/postsx"), ["/*", "/api/*"]);
+    }
+
+    #[test]
+    fn test_deepest_match() {
+        let mut trie = Trie::new();
+        trie.insert("/a", 1);
+        trie.insert("/a/b/c/*", 2);
+
+        // The deep wildcard wins over the shallow exact route
+        assert_eq!(trie.deepest_match("/a/b/c/d"), Some(&2));
+        assert_eq!(trie.get_closest("/a/b/c/d"), Some(&1));
+        assert_eq!(trie.deepest_match("/a/b"), Some(&1));
+        assert_eq!(trie.get("/a/b"), None);
+        assert_eq!(trie.deepest_match("/x"), None);
+
+        // An ancestor's wildcard covers the path before its exact route does, while the exact
+        // route for the path itself follows the match policy
+        trie.insert("/a/b/c", 3);
+        assert_eq!(trie.deepest_match("/a/b/c/d"), Some(&2));
+        assert_eq!(trie.deepest_match("/a/b/c"), Some(&3));
+        let mut wildcard_first = Trie::with_policy(MatchPolicy::WildcardFirst);
+        wildcard_first.insert("/a/b/c/*", 2);
+        wildcard_first.insert("/a/b/c", 3);
+        assert_eq!(wildcard_first.deepest_match("/a/b/c"), Some(&2));
+
+        // A wildcard doesn't cover a longer segment, just like in `get`
+        trie.insert("/files/*", 4);
+        assert_eq!(trie.deepest_match("/files/a"), Some(&4));
+        assert_eq!(trie.deepest_match("/filesx"), None);
+    }
+
+    #[test]
+    fn test_get_closest() {
+        let mut trie = Trie::new();
+        trie.insert("/a", 1);
+        trie.insert("/a/b/c", 3);
+        trie.insert("/a/b/*", 4);
+        trie.insert("/x/*", 5);
+
+        assert_eq!(trie.get_closest("/a/b"), Some(&1));
+        assert_eq!(trie.get_closest("/a/b/d"), Some(&1));
+        assert_eq!(trie.get_closest("/a/b/c/d"), Some(&3));
+
+        // Exact matches win, and wildcard routes are never used
+        assert_eq!(trie.get_closest("/a"), Some(&1));
+        assert_eq!(trie.get_closest("/a/b/c"), Some(&3));
+        assert_eq!(trie.get_closest("/x/y"), None);
+        assert_eq!(trie.get_closest("/"), None);
+        for path in ["/a", "/a/b", "/a/b/c/d", "/x/y"] {
+            assert_eq!(
+                trie.get_closest(path),
+                trie.prefix_values(path).last().copied()
+            );
+        }
+    }
+
+    #[test]
+    fn test_entry_slot() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "users_handler");
+
+        // The exact and wildcard slots of a path are separate
+        assert_eq!(trie.entry_slot("/api/users"), &mut Some("users_handler"));
+        assert_eq!(trie.entry_slot("/api/users/*"), &mut None);
+
+        *trie.entry_slot("/api/users/*") = Some("users_fallback");
+        assert_eq!(trie.get("/api/users/7"), Some(&"users_fallback"));
+
+        if let Some(handler) = trie.entry_slot("/api/users") {
+            *handler = "users_handler_v2";
+        }
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler_v2"));
+
+        // Splits the `/api/users` node to create the slot
+        trie.entry_slot("/api/u").replace("u_handler");
+        assert_eq!(trie.get("/api/u"), Some(&"u_handler"));
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler_v2"));
+
+        assert_eq!(
+            trie.entry_slot("/api/users").take(),
+            Some("users_handler_v2")
+        );
+        assert_eq!(trie.get("/api/users"), Some(&"users_fallback"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_group_by_segment() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", 1);
+        trie.insert("/api/posts", 2);
+        trie.insert("/auth/login", 3);
+        trie.insert("/api/*", 4);
+        trie.insert("/", 5);
+
+        let groups = trie.group_by_segment(0);
+        let mut keys: Vec<_> = groups.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["", "api", "auth"]);
+        assert_eq!(
+            groups["api"],
+            [
+                ("/api/*".to_string(), &4),
+                ("/api/posts".to_string(), &2),
+                ("/api/users".to_string(), &1),
+            ]
+        );
+        assert_eq!(groups["auth"], [("/auth/login".to_string(), &3)]);
+        assert_eq!(groups[""], [("/".to_string(), &5)]);
+
+        // Entries without a second segment are skipped
+        let groups = trie.group_by_segment(1);
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups["login"], [("/auth/login".to_string(), &3)]);
+        assert_eq!(groups["*"], [("/api/*".to_string(), &4)]);
+        assert!(trie.group_by_segment(2).is_empty());
+    }
+
+    #[test]
+    fn test_find_all() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Handler {
+            name: &'static str,
+            deprecated: bool,
+        }
+
+        let handler = |name, deprecated| Handler { name, deprecated };
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/*", handler("v1", true));
+        trie.insert("/api/v1/users", handler("v1_users", true));
+        trie.insert("/api/v2/users", handler("v2_users", false));
+        trie.insert("/health", handler("health", false));
+
+        let mut deprecated = trie.find_all(|handler| handler.deprecated);
+        deprecated.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(
+            deprecated,
+            [
+                ("/api/v1/*".to_string(), &handler("v1", true)),
+                ("/api/v1/users".to_string(), &handler("v1_users", true)),
+            ]
+        );
+        assert!(trie.find_all(|handler| handler.name.is_empty()).is_empty());
+        assert_eq!(trie.find_all(|_| true).len(), 4);
+    }
+
+    #[test]
+    fn test_get_or() {
+        static NOT_FOUND: &str = "not_found";
+
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+
+        assert_eq!(trie.get_or("/api/users", &NOT_FOUND), &"users_handler");
+        assert_eq!(trie.get_or("/api/posts", &NOT_FOUND), &"api_fallback");
+        assert!(core::ptr::eq(
+            trie.get_or("/static", &NOT_FOUND),
+            &NOT_FOUND
+        ));
+    }
+
+    #[test]
+    fn test_get_mounted() {
+        let mut admin = Trie::new();
+        admin.insert("/dashboard", "dashboard");
+        admin.insert("/users/*", "users");
+        admin.insert("", "index");
+
+        assert_eq!(
+            admin.get_mounted("/admin", "/admin/dashboard"),
+            Some(&"dashboard")
+        );
+        assert_eq!(
+            admin.get_mounted("/admin/", "/admin/dashboard"),
+            Some(&"dashboard")
+        );
+        assert_eq!(
+            admin.get_mounted("/admin", "/admin/users/42"),
+            Some(&"users")
+        );
+        assert_eq!(admin.get_mounted("/admin", "/admin"), Some(&"index"));
+        assert_eq!(
+            admin.get_mounted("/api/admin", "/api/admin/dashboard"),
+            Some(&"dashboard")
+        );
+
+        // Only a whole segment is stripped
+        assert_eq!(admin.get_mounted("/admin", "/adminfoo"), None);
+        assert_eq!(admin.get_mounted("/admin", "/admin-x/dashboard"), None);
+        assert_eq!(admin.get_mounted("/admin", "/dashboard"), None);
+        assert_eq!(admin.get_mounted("/admin", "/admin/missing"), None);
+
+        // An empty mount passes the whole path through
+        assert_eq!(admin.get_mounted("", "/dashboard"), Some(&"dashboard"));
+        assert_eq!(admin.get_mounted("/", "/dashboard"), Some(&"dashboard"));
+    }
+
+    #[test]
+    fn test_get_limited() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+
+        assert_eq!(
+            trie.get_limited("/api/users", 2),
+            Ok(Some(&"users_handler"))
+        );
+        assert_eq!(
+            trie.get_limited("/api/users/1", 3),
+            Ok(Some(&"api_fallback"))
+        );
+        assert_eq!(trie.get_limited("/static", 2), Ok(None));
+
+        let error = trie.get_limited("/api/users/1", 2).unwrap_err();
+        assert_eq!(error.max_segments(), 2);
+        assert_eq!(error.to_string(), "path has more than 2 segments");
+        let hostile = "/api".to_string() + &"/".repeat(1_000_000);
+        assert!(trie.get_limited(&hostile, 64).is_err());
+
+        // Segments are counted after normalization
+        let mut trie = Trie::with_require_leading_slash(true);
+        trie.insert("/api/users", "users_handler");
+        assert!(trie.get_limited("api/users", 1).is_err());
+        assert_eq!(trie.get_limited("api/users", 2), Ok(Some(&"users_handler")));
+        let mut trie = Trie::with_collapse_slashes(true);
+        trie.insert("/api/users", "users_handler");
+        assert_eq!(
+            trie.get_limited("//api//users", 2),
+            Ok(Some(&"users_handler"))
+        );
+    }
+
+    #[test]
+    fn test_get_or_nearest() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/*", "v1_handler");
+        trie.insert("/auth/login", "login");
+
+        assert_eq!(trie.get_or_nearest("/api/v1/x"), Ok(&"v1_handler"));
+        assert_eq!(trie.get_or_nearest("/api/v2/x"), Err("/api/".to_string()));
+        assert_eq!(
+            trie.get_or_nearest("/auth/loginx"),
+            Err("/auth/".to_string())
+        );
+        assert_eq!(trie.get_or_nearest("/auth"), Err("/auth".to_string()));
+        assert_eq!(
+            trie.get_or_nearest("/auth/logout"),
+            Err("/auth/".to_string())
+        );
+        assert_eq!(trie.get_or_nearest("/static"), Err("/".to_string()));
+        assert_eq!(Trie::<()>::new().get_or_nearest("/x"), Err(String::new()));
+
+        // The path is decoded once, so `%2541` doesn't reach the `/aA` route
+        let mut trie = Trie::with_percent_decoding(true);
+        trie.insert("/aA", "plain");
+        assert_eq!(trie.get_or_nearest("/a%2541"), Err("/".to_string()));
+        assert_eq!(trie.get_or_nearest("/a%41"), Ok(&"plain"));
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let keys = ["alpha", "bravo", "charlie", "/api/*", "/api/users", "*"];
+        let mut sized = Trie::with_capacity(16);
+        let mut plain = Trie::new();
+        for (i, key) in keys.iter().enumerate() {
+            sized.insert(key, i);
+            plain.insert(key, i);
+        }
+
+        assert_eq!(
+            sized.iter_sorted().collect::<Vec<_>>(),
+            plain.iter_sorted().collect::<Vec<_>>()
+        );
+        assert_eq!(sized.node_count(), plain.node_count());
+        for path in ["alpha", "bravo/x", "/api/users", "/api/posts", "delta"] {
+            assert_eq!(sized.get(path), plain.get(path), "{path}");
+        }
+
+        assert!(sized.root.children.capacity() >= 16);
+        // Maps without `std` are trees, which have no capacity
+        #[cfg(feature = "std")]
+        {
+            plain.reserve(32);
+            assert!(plain.root.children.capacity() >= 32);
+        }
+    }
+
+    #[test]
+    fn test_custom_separator() {
+        let mut trie = Trie::with_separator('.');
+        trie.insert("com.example.*", "example");
+        trie.insert("com.example.app", "app");
+        trie.insert("com.*.api", "any_api");
+        trie.insert("org/*", "slash_is_literal");
+
+        assert_eq!(trie.separator(), '.');
+        assert_eq!(trie.get("com.example.app"), Some(&"app"));
+        assert_eq!(trie.get("com.example.web.v2"), Some(&"example"));
+        assert_eq!(trie.get("com.other.api"), Some(&"any_api"));
+        assert_eq!(trie.get("com.other.web"), None);
+        assert_eq!(trie.get("org/*"), Some(&"slash_is_literal"));
+        assert_eq!(trie.get("org/x"), None);
+        assert_eq!(
+            trie.get_with_rest("com.example.web.v2"),
+            Some((&"example", Some("web.v2".to_string())))
+        );
+
+        let keys: Vec<_> = trie.iter_sorted().map(|(key, _)| key).collect();
+        assert_eq!(
+            keys,
+            ["com.*.api", "com.example.*", "com.example.app", "org/*"]
+        );
+        assert_eq!(
+            trie.remove_entry("com.example.*"),
+            Some(("com.example.*".to_string(), "example"))
+        );
+        assert_eq!(trie.get("com.example.web"), None);
+        assert_eq!(Trie::<()>::new().separator(), '/');
+    }
+
+    #[test]
+    #[should_panic(expected = "separator must be an ASCII character other than `*`")]
+    fn test_wildcard_separator_panics() {
+        let _ = Trie::<()>::with_separator('*');
+    }
+
+    #[test]
+    fn test_map_preserves_structure() {
+        let mut trie = Trie::with_separator('.');
+        trie.insert("a.*", 1);
+        trie.insert("a.b", 2);
+        trie.insert("a.*.c", 3);
+        trie.insert("", 4);
+        let node_count = trie.node_count();
+        let paths = ["a.b", "a.x", "a.x.c", "a", "", "z"];
+        let expected: Vec<_> = paths
+            .iter()
+            .map(|path| trie.get(path).map(i32::to_string))
+            .collect();
+
+        let mapped: Trie<String> = trie.map(|x| x.to_string());
+        assert_eq!(mapped.node_count(), node_count);
+        assert_eq!(mapped.separator(), '.');
+        for (path, expected) in paths.iter().zip(expected) {
+            assert_eq!(mapped.get(path), expected.as_ref(), "{path}");
+        }
+        let keys: Vec<_> = mapped.iter_sorted().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["", "a.*", "a.*.c", "a.b"]);
+    }
+
+    #[test]
+    fn test_debug_shows_logical_entries() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "h1");
+        trie.insert("/api/*", "h2");
+        trie.insert("/", "h3");
+
+        assert_eq!(
+            format!("{trie:?}"),
+            r#"{"/": "h3", "/api/*": "h2", "/api/users": "h1"}"#
+        );
+        assert_eq!(format!("{:?}", Trie::<()>::new()), "{}");
+    }
+
+    /// Returns the length of the longest prefix held by a node of the trie
+    fn longest_prefix<T>(trie: &Trie<T>) -> usize {
+        let mut stack = vec![&*trie.root];
+        let mut longest = 0;
+        while let Some(node) = stack.pop() {
+            longest = longest.max(node.prefix.len());
+            stack.extend(node.children.values().map(|child| &**child));
+        }
+        longest
+    }
+
+    #[test]
+    fn test_max_prefix_len_chunks_long_keys() {
+        let long_key = format!("/blobs/{}", "ab".repeat(50_000));
+        let mut trie = Trie::with_max_prefix_len(1000);
+        trie.insert(&long_key, 1);
+        trie.insert(&format!("{long_key}/*"), 2);
+        trie.insert("/blobs/*", 3);
+
+        assert_eq!(trie.get(&long_key), Some(&1));
+        assert_eq!(
+            trie.get_with_rest(&format!("{long_key}/x")),
+            Some((&2, Some("x".to_string())))
+        );
+        assert_eq!(trie.get(&long_key[..50_000]), Some(&3));
+        assert!(longest_prefix(&trie) <= 1000);
+        assert!(trie.node_count() > 100);
+
+        trie.remove(&format!("{long_key}/*"));
+        trie.remove("/blobs/*");
+        assert!(longest_prefix(&trie) <= 1000);
+        assert_eq!(trie.iter().collect::<Vec<_>>(), [(long_key, &1)]);
+
+        // Removing a prefix merges the nodes above it, which are chunked again
+        let mut trie = Trie::with_max_prefix_len(8);
+        trie.insert("/x/y", 1);
+        trie.insert("/abcdefghijklmnopqrstuvwx", 2);
+        assert_eq!(trie.remove_prefix("/x"), 1);
+        assert!(longest_prefix(&trie) <= 8);
+
+        // The limit can be set on a trie created with other options
+        let mut trie = Trie::with_separator('.');
+        trie.insert(&format!("com.{}.*", "a".repeat(100)), 1);
+        trie.set_max_prefix_len(10);
+        assert!(longest_prefix(&trie) <= 10);
+        trie.insert(&format!("com.{}", "b".repeat(100)), 2);
+        assert!(longest_prefix(&trie) <= 10);
+        assert_eq!(trie.get(&format!("com.{}.x", "a".repeat(100))), Some(&1));
+    }
+
+    #[test]
+    fn test_max_prefix_len_matches_unlimited() {
+        let routes = [
+            "/",
+            "/api/*",
+            "/api/users",
+            "/api/users/*",
+            "/api/v1/posts",
+            "/users/*/posts",
+            "/users/:id",
+            "/files/?.txt",
+            "/café/ménu",
+            "/café/*",
+        ];
+        let paths = [
+            "/",
+            "/api",
+            "/api/users",
+            "/api/users/42",
+            "/api/v1/posts",
+            "/api/v1/post",
+            "/users/7/posts",
+            "/users/alice",
+            "/files/a.txt",
+            "/café/ménu",
+            "/café/other",
+            "/unknown",
+        ];
+        let build = |mut trie: Trie<&'static str>| {
+            for route in routes {
+                trie.insert(route, route);
+            }
+            trie
+        };
+
+        let unlimited = build(Trie::new());
+        for max in [1, 2, 3, 7] {
+            let mut trie = build(Trie::with_max_prefix_len(max));
+            assert!(longest_prefix(&trie) <= max.max('é'.len_utf8()));
+            assert_eq!(trie, unlimited);
+            for path in paths {
+                assert_eq!(trie.get(path), unlimited.get(path), "{path} with {max}");
+                assert_eq!(trie.get_with_rest(path), unlimited.get_with_rest(path));
+            }
+
+            // Removals merge nodes, which are chunked again
+            trie.remove("/api/users");
+            trie.remove("/api/v1/posts");
+            trie.unset("/café/*");
+            trie.compact();
+            assert!(longest_prefix(&trie) <= max.max('é'.len_utf8()));
+            assert_eq!(trie.get("/api/users/42"), Some(&"/api/users/*"));
+            assert_eq!(trie.get("/café/ménu"), Some(&"/café/ménu"));
+        }
+    }
+
+    #[test]
+    fn test_remove_prunes_empty_nodes() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/api/posts", "posts_handler");
+        let node_count = trie.node_count();
+
+        // Removing a value from a node with children keeps the routes below it, while the
+        // emptied `/api` node is merged into its only child
+        assert_eq!(trie.remove("/api/*"), Some("api_fallback"));
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("/api/posts"), Some(&"posts_handler"));
+        assert_eq!(trie.get("/api/other"), None);
+        assert_eq!(trie.node_count(), node_count - 1);
+
+        // Removing a leaf drops it and merges the now single-child parent
+        assert_eq!(trie.remove("/api/posts"), Some("posts_handler"));
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler"));
+        let mut fresh = Trie::new();
+        fresh.insert("/api/users", "users_handler");
+        assert_eq!(trie.node_count(), fresh.node_count());
+
+        assert_eq!(trie.remove("/api/users"), Some("users_handler"));
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_unset_keeps_structure() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        let node_count = trie.node_count();
+
+        assert_eq!(trie.unset("/api/users"), Some("users_handler"));
+        assert_eq!(trie.unset("/api/users"), None);
+        assert_eq!(trie.node_count(), node_count);
+        assert_eq!(trie.get("/api/users"), Some(&"api_fallback"));
+
+        trie.insert("/api/users", "users_handler_v2");
+        assert_eq!(trie.node_count(), node_count);
+        assert_eq!(trie.get("/api/users"), Some(&"users_handler_v2"));
+    }
+
+    #[test]
+    fn test_compact_after_unset() {
+        let routes = (0..20).map(|i| format!("/api/v1/resource{i}/items"));
+        let mut trie = Trie::new();
+        for (i, route) in routes.clone().enumerate() {
+            trie.insert(&route, i);
+        }
+        trie.insert("/api/v1/*", 100);
+        trie.insert("/static/*", 200);
+        for route in routes.clone().skip(2) {
+            trie.unset(&route);
+        }
+        *trie.entry_slot("/static/*") = None;
+
+        let mut fresh = Trie::new();
+        for (i, route) in routes.take(2).enumerate() {
+            fresh.insert(&route, i);
+        }
+        fresh.insert("/api/v1/*", 100);
+        assert!(trie.node_count() > fresh.node_count());
+
+        trie.compact();
+        assert_eq!(trie.node_count(), fresh.node_count());
+        assert_eq!(trie, fresh);
+        assert_eq!(trie.get("/api/v1/resource1/items"), Some(&1));
+        assert_eq!(trie.get("/api/v1/resource7/items"), Some(&100));
+
+        let mut empty = Trie::new();
+        empty.insert("/api/users", 1);
+        empty.unset("/api/users");
+        empty.compact();
+        assert_eq!(empty.node_count(), 1);
+    }
+
+    #[test]
+    fn test_matches_any_agrees_with_get() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", 1);
+        trie.insert("/api/*/posts", 2);
+        trie.insert("/static/*", 3);
+        trie.insert("/exact", 4);
+        trie.insert("", 5);
+
+        for path in [
+            "",
+            "/",
+            "/api/users",
+            "/api/users/1",
+            "/api/42/posts",
+            "/api/42/comments",
+            "/api",
+            "/static",
+            "/static/app.css",
+            "/staticfoo",
+            "/exact",
+            "/exact/more",
+            "/missing",
+        ] {
+            assert_eq!(trie.matches_any(path), trie.get(path).is_some(), "{path}");
+        }
+        assert!(!Trie::<()>::new().matches_any("/"));
+    }
+
+    #[test]
+    fn test_bytes_api_matches_str_api() {
+        let routes = [
+            "/api/users",
+            "/api/*",
+            "/api/*/posts",
+            "/static/*",
+            "/exact",
+            "",
+        ];
+        let mut trie = Trie::new();
+        let mut bytes_trie = Trie::new();
+        for (value, route) in routes.iter().enumerate() {
+            trie.insert(route, value);
+            assert_eq!(bytes_trie.insert_bytes(route.as_bytes(), value), Ok(()));
+        }
+
+        for path in [
+            "",
+            "/api/users",
+            "/api/posts",
+            "/api/42/posts",
+            "/static/app.css",
+            "/staticfoo",
+            "/exact",
+            "/exact/more",
+            "/missing",
+            "/caf\u{e9}",
+        ] {
+            assert_eq!(trie.get_bytes(path.as_bytes()), trie.get(path), "{path}");
+            assert_eq!(bytes_trie.get(path), trie.get(path), "{path}");
+        }
+
+        // Invalid UTF-8 can only be matched by wildcards
+        assert_eq!(trie.get_bytes(b"/static/\xff"), Some(&3));
+        assert_eq!(trie.get_bytes(b"/exact\xff"), None);
+        assert_eq!(
+            trie.insert_bytes(b"/\xc3", 0),
+            Err(crate::PathError::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn test_memory_usage_grows_with_trie() {
+        let empty = Trie::<u32>::new();
+        assert_eq!(empty.memory_usage(), core::mem::size_of::<Trie<u32>>());
+
+        let mut small = Trie::new();
+        small.insert("/api/users", 1);
+        let mut large = Trie::new();
+        for i in 0..100 {
+            large.insert(&format!("/api/users/{i}"), i);
+        }
+
+        assert!(small.memory_usage() > empty.memory_usage());
+        assert!(large.memory_usage() > small.memory_usage());
+    }
+
+    #[test]
+    fn test_root_route_matrix() {
+        let routes = ["", "/", "/*", "*"];
+        let paths = ["", "/", "/x", "x", "*"];
+
+        // Which route each path resolves to when only that route is registered
+        let expected: [[bool; 5]; 4] = [
+            [true, false, false, false, false],
+            [false, true, false, false, false],
+            [true, true, true, true, true],
+            [true, true, true, true, true],
+        ];
+        for (route, expected) in routes.iter().zip(expected) {
+            let mut trie = Trie::new();
+            trie.insert(route, ());
+            for (path, matches) in paths.iter().zip(expected) {
+                assert_eq!(trie.get(path).is_some(), matches, "{route:?} {path:?}");
+            }
+        }
+
+        // All four routes together each keep their own slot, with `*` aliasing `/*`
+        let mut trie = Trie::new();
+        trie.insert("", "empty");
+        trie.insert("/", "slash");
+        trie.insert("/*", "catch_all");
+        trie.insert("*", "star");
+        assert_eq!(trie.len(), 3);
+
+        assert_eq!(trie.get(""), Some(&"empty"));
+        assert_eq!(trie.get("/"), Some(&"slash"));
+        assert_eq!(trie.get("/x"), Some(&"star"));
+        assert_eq!(trie.get("*"), Some(&"star"));
+        let keys: Vec<_> = trie.iter_sorted().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["", "/", "/*"]);
+
+        assert_eq!(trie.remove("*"), Some("star"));
+        assert_eq!(trie.get("/x"), None);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/api/*", "api_fallback");
+
+        assert_eq!(trie["/api/users"], "users_handler");
+        assert_eq!(trie["/api/posts"], "api_fallback");
+    }
+
+    #[test]
+    #[should_panic(expected = "no route matches path `/static/app.css`")]
+    fn test_index_panics_on_missing_route() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        let _ = trie["/static/app.css"];
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        let prefix_of = |routes: &[&str]| {
+            let mut trie = Trie::new();
+            for route in routes {
+                trie.insert(route, ());
+            }
+            trie.longest_common_prefix()
+        };
+
+        assert_eq!(prefix_of(&[]), "");
+        assert_eq!(prefix_of(&["/api/v1/users"]), "/api/v1/users");
+        assert_eq!(prefix_of(&["/api/v1/users", "/api/v1/posts"]), "/api/v1/");
+        assert_eq!(prefix_of(&["/api/v1/users", "/api/v1/uploads"]), "/api/v1/");
+        assert_eq!(
+            prefix_of(&["/api/v1/users", "/api/v1/posts", "/auth/login"]),
+            "/"
+        );
+        assert_eq!(prefix_of(&["/api", "/api/users"]), "/api");
+        assert_eq!(prefix_of(&["/api/*", "/api/users"]), "/api");
+        assert_eq!(prefix_of(&["/api", "api"]), "");
+    }
+
+    #[test]
+    fn test_insert_with_explicit_slot() {
+        let mut trie = Trie::new();
+        trie.insert_exact("/a/*", "literal");
+        assert_eq!(trie.get_exact("/a/*"), Some(&"literal"));
+        assert_eq!(trie.get("/a/*"), Some(&"literal"));
+        assert_eq!(trie.get("/a/b"), None);
+        assert_eq!(trie.get_exact("/a"), None);
+
+        // The wildcard for `/a` lives in a different slot from the literal `/a/*`
+        trie.insert_wildcard("/a", "wildcard");
+        assert_eq!(trie.get("/a/b"), Some(&"wildcard"));
+        assert_eq!(trie.get_exact("/a/*"), Some(&"literal"));
+        assert_eq!(trie.get_exact("/a/b"), None);
+        trie.insert("/a/*", "replaced");
+        assert_eq!(trie.get("/a/b"), Some(&"replaced"));
+
+        // A prefix ending in `/*` is stored verbatim
+        trie.insert_wildcard("/a/*", "nested");
+        assert_eq!(trie.get_exact("/a/*"), Some(&"literal"));
+        assert_eq!(trie.get("/a/*/x"), Some(&"nested"));
+    }
+
+    #[test]
+    fn test_literal_wildcard_keys_are_distinct() {
+        let mut wildcard = Trie::new();
+        wildcard.insert("/x/*", "route");
+        let mut literal = Trie::new();
+        literal.insert_exact("/x/*", "route");
+        assert_ne!(wildcard, literal);
+        assert_eq!(format!("{literal:?}"), r#"{"/x/\\*": "route"}"#);
+
+        // Backslashes already in front of the `*` are escaped too, and keys map back to their slot
+        let mut trie = Trie::new();
+        for (path, value) in [("/x/*", 1), ("/x/\\*", 2), ("*", 3), ("/x", 4)] {
+            trie.insert_exact(path, value);
+        }
+        trie.insert("/x/*", 5);
+        trie.insert("/*", 6);
+        let keys: Vec<_> = trie.iter_sorted().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["/*", "/x", "/x/*", "/x/\\*", "/x/\\\\*", "\\*"]);
+
+        let mut copy = Trie::new();
+        for (key, value) in trie.iter() {
+            copy.insert(&key, *value);
+        }
+        assert_eq!(copy, trie);
+        assert_eq!(copy.get_exact("/x/\\*"), Some(&2));
+        assert_eq!(copy.remove("/x/\\*"), Some(1));
+        assert_eq!(copy.remove("/x/*"), Some(5));
+    }
 }
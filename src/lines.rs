@@ -0,0 +1,87 @@
+use std::io::{self, BufRead};
+
+use crate::Trie;
+
+impl Trie<String> {
+    /// Reads routes from `path=value` or `path<TAB>value` lines, one route per line
+    ///
+    /// Each line is split at its first tab, or at its first `=` if it has no tab, and both
+    /// sides are trimmed. Blank lines and lines starting with `#` are skipped. A later line
+    /// for the same route replaces the value of an earlier one.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] on a line without a separator or with an
+    /// empty path, naming its line number, and with the reader's error if it fails.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let config = "# Routes\n/api/*=api_fallback\n\n/api/users\tusers_handler\n";
+    /// let trie = Trie::load_lines(config.as_bytes())?;
+    ///
+    /// assert_eq!(trie.get("/api/users"), Some(&"users_handler".to_string()));
+    /// assert_eq!(trie.get("/api/posts"), Some(&"api_fallback".to_string()));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn load_lines<R: BufRead>(r: R) -> io::Result<Trie<String>> {
+        let mut trie = Trie::new();
+        for (index, line) in r.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let route = line.split_once('\t').or_else(|| line.split_once('='));
+            let Some((path, value)) = route.filter(|(path, _)| !path.trim().is_empty()) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "line {}: expected `path=value` or `path<TAB>value`",
+                        index + 1
+                    ),
+                ));
+            };
+            trie.insert(path.trim(), value.trim().to_string());
+        }
+        Ok(trie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::Trie;
+
+    #[test]
+    fn test_load_lines() {
+        let config = "\
+# Public routes
+/api/*=api_fallback
+/api/users\tusers_handler
+
+  # Static assets
+/static/* = static_files
+/api/users=users_v2
+/query\ta=b
+";
+        let trie = Trie::load_lines(config.as_bytes()).unwrap();
+        let get = |path| trie.get(path).map(String::as_str);
+        assert_eq!(get("/api/users"), Some("users_v2"));
+        assert_eq!(get("/api/posts"), Some("api_fallback"));
+        assert_eq!(get("/static/app.css"), Some("static_files"));
+        assert_eq!(get("/query"), Some("a=b"));
+        assert_eq!(trie.iter().count(), 4);
+
+        for (config, line) in [
+            ("/api/*=api\n\n/api/users\n", 3),
+            ("# comment\n=orphan\n", 2),
+        ] {
+            let error = Trie::load_lines(config.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().starts_with(&format!("line {line}:")));
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::vec::Vec;
+
+use crate::{ChangeEvent, Trie};
+
+/// A radix trie that stores multiple values per path
+///
+/// Shares the structure and matching rules of [`Trie`], but each exact or wildcard
+/// slot holds every value inserted at that path, in insertion order.
+///
+/// # Examples
+/// ```rust
+/// # use wildcard_trie::MultiTrie;
+/// let mut trie = MultiTrie::new();
+/// trie.insert("/api/*", "auth");
+/// trie.insert("/api/*", "logging");
+///
+/// assert_eq!(trie.get("/api/users"), &["auth", "logging"]);
+/// ```
+#[derive(Debug)]
+pub struct MultiTrie<T>(Trie<Vec<T>>);
+
+impl<T> Default for MultiTrie<T> {
+    fn default() -> Self {
+        Self(Trie::new())
+    }
+}
+
+impl<T> MultiTrie<T> {
+    /// Creates a new empty trie
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a value at the given path
+    ///
+    /// Paths ending with `/*` are treated as wildcard routes that match any sub-path.
+    pub fn insert(&mut self, path: &str, value: T) {
+        self.0.get_or_insert_with(path, Vec::new).push(value);
+    }
+
+    /// Registers a callback that is notified when a path gains its first value or loses its
+    /// last one
+    ///
+    /// See [`Trie::on_change`]; appending to or removing from a path that keeps other values
+    /// isn't reported.
+    pub fn on_change(&mut self, f: impl FnMut(ChangeEvent<'_>) + Send + Sync + 'static) {
+        self.0.on_change(f);
+    }
+
+    /// Retrieves all values for the given path, with exact > wildcard precedence.
+    ///
+    /// Returns an empty slice if no route matches.
+    pub fn get(&self, path: &str) -> &[T] {
+        self.0.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Removes the first value equal to `value` at the given path, returning it if it existed
+    pub fn remove(&mut self, path: &str, value: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let values = self.0.find_slot_mut(path)?.as_mut()?;
+        let index = values.iter().position(|v| v == value)?;
+        let removed = values.remove(index);
+
+        // Remove empty slots so lookups fall back to wildcards again
+        if values.is_empty() {
+            self.0.remove(path);
+        }
+        Some(removed)
+    }
+
+    /// Removes all values at the given path, returning them in insertion order
+    pub fn remove_all(&mut self, path: &str) -> Vec<T> {
+        self.0.remove(path).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiple_values_in_insertion_order() {
+        let mut trie = MultiTrie::new();
+        trie.insert("/api/users", "auth");
+        trie.insert("/api/users", "logging");
+        trie.insert("/api/users", "handler");
+
+        assert_eq!(trie.get("/api/users"), &["auth", "logging", "handler"]);
+        assert!(trie.get("/api/posts").is_empty());
+    }
+
+    #[test]
+    fn test_exact_takes_precedence_over_wildcard() {
+        let mut trie = MultiTrie::new();
+        trie.insert("/api/*", "api_auth");
+        trie.insert("/api/*", "api_logging");
+        trie.insert("/api/users", "users_handler");
+
+        assert_eq!(trie.get("/api/users"), &["users_handler"]);
+        assert_eq!(trie.get("/api/posts"), &["api_auth", "api_logging"]);
+    }
+
+    #[test]
+    fn test_remove_specific_value() {
+        let mut trie = MultiTrie::new();
+        trie.insert("/api/*", "fallback");
+        trie.insert("/api/users", "auth");
+        trie.insert("/api/users", "handler");
+
+        assert_eq!(trie.remove("/api/users", &"auth"), Some("auth"));
+        assert_eq!(trie.remove("/api/users", &"auth"), None);
+        assert_eq!(trie.get("/api/users"), &["handler"]);
+
+        // Removing the last value falls back to the wildcard
+        assert_eq!(trie.remove("/api/users", &"handler"), Some("handler"));
+        assert_eq!(trie.get("/api/users"), &["fallback"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_change_reports_first_and_last_value() {
+        use alloc::string::{String, ToString};
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::<(String, bool)>::new()));
+        let recorded = Arc::clone(&events);
+        let mut trie = MultiTrie::new();
+        trie.on_change(move |event| {
+            let event = match event {
+                ChangeEvent::Inserted { path, .. } => (path.to_string(), true),
+                ChangeEvent::Removed { path } => (path.to_string(), false),
+            };
+            recorded.lock().unwrap().push(event);
+        });
+
+        trie.insert("/api/*", "auth");
+        trie.insert("/api/*", "logging");
+        trie.remove("/api/*", &"auth");
+        trie.remove("/api/*", &"logging");
+        assert_eq!(
+            *events.lock().unwrap(),
+            [("/api/*".to_string(), true), ("/api/*".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let mut trie = MultiTrie::new();
+        trie.insert("/api/*", "auth");
+        trie.insert("/api/*", "logging");
+
+        assert_eq!(trie.remove_all("/api/*"), vec!["auth", "logging"]);
+        assert!(trie.remove_all("/api/*").is_empty());
+        assert!(trie.get("/api/users").is_empty());
+    }
+}
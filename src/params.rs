@@ -0,0 +1,202 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::Trie;
+
+/// A route matched by [`Trie::match_route`], along with the values of its `:name` parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteMatch<'a, T> {
+    value: &'a T,
+    params: Vec<(String, String)>,
+}
+
+impl<'a, T> RouteMatch<'a, T> {
+    /// Returns the value of the matched route
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+
+    /// Returns the path segment bound to the parameter with the given name
+    ///
+    /// If the route uses the name more than once, the first binding is returned.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params()
+            .find_map(|(param, segment)| (param == name).then_some(segment))
+    }
+
+    /// Iterates over the `(name, segment)` bindings of the route's parameters, in path order
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, segment)| (name.as_str(), segment.as_str()))
+    }
+}
+
+impl<T> Trie<T> {
+    /// Retrieves the route for the given path, binding each of its `:name` parameters
+    ///
+    /// In a trie built with [`Trie::with_named_params`], a segment of a stored route starting
+    /// with `:` matches any single non-empty segment, like a `*` segment, and binds it to the
+    /// name that follows. Matching is the same as [`Trie::get`], so literal segments still
+    /// take precedence over parameters. Without named parameters no route has bindings.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_named_params(true);
+    /// trie.insert("/users/:uid/posts/:pid", "post_handler");
+    ///
+    /// let matched = trie.match_route("/users/7/posts/42").unwrap();
+    /// assert_eq!(matched.value(), &"post_handler");
+    /// assert_eq!(matched.param("uid"), Some("7"));
+    /// assert_eq!(matched.param("pid"), Some("42"));
+    /// ```
+    pub fn match_route(&self, path: &str) -> Option<RouteMatch<'_, T>> {
+        let (value, clean_path, _) = self.resolve_slot(path)?;
+
+        // Literal segments match themselves and wildcards consume exactly one segment, so the
+        // route's segments line up with the path's. The first segment follows no separator,
+        // so it's never a parameter.
+        if !self.named_params {
+            return Some(RouteMatch {
+                value,
+                params: Vec::new(),
+            });
+        }
+        // Segments are bound in the case the path was given in, even when it's matched folded
+        let path = self.normalize_unfolded(path);
+        let params = clean_path
+            .split(self.separator)
+            .zip(path.split(self.separator))
+            .skip(1)
+            .filter_map(|(pattern, segment)| {
+                let name = pattern.strip_prefix(':')?;
+                Some((name.to_string(), segment.to_string()))
+            })
+            .collect();
+        Some(RouteMatch { value, params })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    fn params<'a>(trie: &'a Trie<&str>, path: &str) -> Option<(&'a str, Vec<(String, String)>)> {
+        trie.match_route(path).map(|matched| {
+            let params = matched
+                .params()
+                .map(|(name, segment)| (name.to_string(), segment.to_string()))
+                .collect();
+            (*matched.value(), params)
+        })
+    }
+
+    fn bindings(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, segment)| (name.to_string(), segment.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_match_route_binds_params() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/users/:uid/posts/:pid", "post_handler");
+        trie.insert("/users/:uid", "user_handler");
+        trie.insert("/users/me", "me_handler");
+        trie.insert("/users/:uid/files/*", "files_handler");
+
+        let matched = trie.match_route("/users/7/posts/42").unwrap();
+        assert_eq!(matched.value(), &"post_handler");
+        assert_eq!(matched.param("uid"), Some("7"));
+        assert_eq!(matched.param("pid"), Some("42"));
+        assert_eq!(matched.param("other"), None);
+        assert_eq!(
+            params(&trie, "/users/7/posts/42"),
+            Some(("post_handler", bindings(&[("uid", "7"), ("pid", "42")])))
+        );
+
+        assert_eq!(
+            params(&trie, "/users/7"),
+            Some(("user_handler", bindings(&[("uid", "7")])))
+        );
+        assert_eq!(params(&trie, "/users/me"), Some(("me_handler", vec![])));
+        assert_eq!(
+            params(&trie, "/users/7/files/a/b.txt"),
+            Some(("files_handler", bindings(&[("uid", "7")])))
+        );
+
+        // Parameters only match whole, non-empty segments
+        assert_eq!(params(&trie, "/users//posts/42"), None);
+        assert_eq!(params(&trie, "/users/7/posts"), None);
+        assert_eq!(trie.get("/users/7/posts/42"), Some(&"post_handler"));
+    }
+
+    #[test]
+    fn test_params_with_shared_name_prefixes() {
+        // `:id` and `:idx` share the `:id` node, so `:idx` continues in a child
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/a/:id", "id");
+        trie.insert("/a/:idx/b", "idx");
+        trie.insert("/a/:item/c", "item");
+
+        assert_eq!(
+            params(&trie, "/a/1"),
+            Some(("id", bindings(&[("id", "1")])))
+        );
+        assert_eq!(
+            params(&trie, "/a/1/b"),
+            Some(("idx", bindings(&[("idx", "1")])))
+        );
+        assert_eq!(
+            params(&trie, "/a/1/c"),
+            Some(("item", bindings(&[("item", "1")])))
+        );
+        assert_eq!(params(&trie, "/a/1/d"), None);
+        assert!(trie.matches_any("/a/1/b"));
+        assert!(!trie.matches_any("/a/1/d"));
+    }
+
+    #[test]
+    fn test_params_in_derived_lookups() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/users/*", "users_fallback");
+        trie.insert("/users/:uid/files/*", "files");
+        trie.insert("/users/:uid", "user");
+
+        assert_eq!(
+            trie.get_with_rest("/users/7/files/a/b.txt"),
+            Some((&"files", Some("a/b.txt".to_string())))
+        );
+        assert_eq!(
+            trie.get_all_matches("/users/7"),
+            [&"user", &"users_fallback"]
+        );
+        assert_eq!(
+            trie.find_match("/users/7/files/x", |v| v.starts_with('u')),
+            Some(&"users_fallback")
+        );
+        let report = trie.explain("/users/7/files/x");
+        assert_eq!(report.wildcards()[0].matched_path(), "/users/:uid/files/*");
+    }
+
+    #[test]
+    fn test_colons_literal_by_default() {
+        let mut trie = Trie::new();
+        trie.insert("/ns/:meta", "meta");
+        trie.insert("/ns/:meta/*", "meta_fallback");
+        trie.insert("/users/*/posts", "posts");
+
+        // A `:` segment is literal text, while `*` segments still match any segment
+        assert_eq!(params(&trie, "/ns/:meta"), Some(("meta", vec![])));
+        assert_eq!(trie.get("/ns/other"), None);
+        assert!(!trie.matches_any("/ns/other"));
+        assert_eq!(
+            trie.get_with_rest("/ns/:meta/a/b"),
+            Some((&"meta_fallback", Some("a/b".to_string())))
+        );
+        assert_eq!(trie.get("/users/7/posts"), Some(&"posts"));
+        assert_eq!(trie.freeze().get("/ns/:meta"), Some(&"meta"));
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Decodes the `%XX` escapes of a path, borrowing it when it has none
+///
+/// Escapes of the separator are kept as is, so an encoded separator stays part of its segment
+/// instead of splitting it. A path that would decode to invalid UTF-8 is left undecoded.
+pub(crate) fn percent_decode(path: Cow<'_, str>, separator: u8) -> Cow<'_, str> {
+    match decode(path.as_bytes(), separator).map(String::from_utf8) {
+        Some(Ok(decoded)) => Cow::Owned(decoded),
+        _ => path,
+    }
+}
+
+/// Decodes the `%XX` escapes of a raw byte path like [`percent_decode`], into any bytes
+pub(crate) fn percent_decode_bytes(path: Cow<'_, [u8]>, separator: u8) -> Cow<'_, [u8]> {
+    match decode(&path, separator) {
+        Some(decoded) => Cow::Owned(decoded),
+        None => path,
+    }
+}
+
+/// Decodes the escapes of `path` other than those of the separator, or returns `None` if it
+/// has nothing to decode
+///
+/// A `%` not followed by two hex digits is kept as is.
+fn decode(path: &[u8], separator: u8) -> Option<Vec<u8>> {
+    let escape_at = |index: usize| {
+        let [b'%', high, low, ..] = path[index..] else {
+            return None;
+        };
+        let digit = |b: u8| char::from(b).to_digit(16);
+        let byte = (digit(high)? * 16 + digit(low)?) as u8;
+        (byte != separator).then_some(byte)
+    };
+    let first = (0..path.len()).find(|&index| escape_at(index).is_some())?;
+
+    let mut decoded = Vec::with_capacity(path.len());
+    decoded.extend_from_slice(&path[..first]);
+    let mut index = first;
+    while index < path.len() {
+        match escape_at(index) {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(path[index]);
+                index += 1;
+            }
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_percent_decoding() {
+        let mut trie = Trie::with_percent_decoding(true);
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/files/*", "files");
+        trie.insert("/users/*/posts", "user_posts");
+        trie.insert("/caf%C3%A9", "cafe");
+
+        assert_eq!(trie.get("/api/%75sers"), Some(&"users_handler"));
+        assert_eq!(trie.get("/api/%75%73ers"), Some(&"users_handler"));
+        assert_eq!(trie.get("/%61pi/users"), Some(&"users_handler"));
+        assert_eq!(trie.get("/café"), Some(&"cafe"));
+        assert_eq!(
+            trie.iter_sorted().next(),
+            Some(("/api/users".to_string(), &"users_handler"))
+        );
+
+        // An encoded separator stays within its segment
+        assert_eq!(trie.get("/users/a%2Fb/posts"), Some(&"user_posts"));
+        assert_eq!(trie.get("/api%2Fusers"), None);
+        assert_eq!(
+            trie.get_with_rest("/files/a%2fb"),
+            Some((&"files", Some("a%2fb".to_string())))
+        );
+
+        // Malformed escapes and escapes of invalid UTF-8 are kept as is
+        assert_eq!(trie.get("/api/users%"), None);
+        assert_eq!(trie.get("/files/%zz"), Some(&"files"));
+        assert_eq!(trie.get("/files/%FF"), Some(&"files"));
+        assert_eq!(trie.get_bytes(b"/files/%FF"), Some(&"files"));
+
+        let plain = {
+            let mut trie = Trie::new();
+            trie.insert("/api/users", 1);
+            trie
+        };
+        assert_eq!(plain.get("/api/%75sers"), None);
+    }
+}
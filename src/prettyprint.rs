@@ -1,15 +1,56 @@
-use std::fmt::Debug;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Debug;
 
 use crate::{RadixNode, Trie};
 
-impl<T: Debug> RadixNode<T> {
+/// The characters used to draw the branches of the tree
+struct Charset {
+    /// Connector for a child followed by siblings
+    branch: &'static str,
+    /// Connector for the last child
+    last: &'static str,
+    /// Indentation below a child followed by siblings
+    pipe: &'static str,
+}
+
+/// Box-drawing characters, the default
+const UNICODE: Charset = Charset {
+    branch: "├── ",
+    last: "└── ",
+    pipe: "│   ",
+};
+
+/// Plain ASCII, for terminals and logs that mangle box-drawing characters
+const ASCII: Charset = Charset {
+    branch: "|-- ",
+    last: "`-- ",
+    pipe: "|   ",
+};
+
+/// Formats a value of a node, given the name of its slot (`exact` or `wildcard`)
+type Label<T> = fn(&'static str, &T) -> String;
+
+impl<T> RadixNode<T> {
     /// Pretty prints the trie structure for debugging
-    fn pretty_print(&self, prefix: &str, is_last: bool, is_root: bool) -> String {
+    fn pretty_print(
+        &self,
+        prefix: &str,
+        is_last: bool,
+        is_root: bool,
+        charset: &Charset,
+        label: Label<T>,
+    ) -> String {
         let mut output = String::new();
 
         // Node connector (except for root)
         if !is_root {
-            let connector = if is_last { "└── " } else { "├── " };
+            let connector = if is_last {
+                charset.last
+            } else {
+                charset.branch
+            };
             output.push_str(&format!("{prefix}{connector}"));
         }
 
@@ -21,22 +62,22 @@ impl<T: Debug> RadixNode<T> {
         }
 
         // Node values
-        self.append_values_to_output(&mut output);
+        self.append_values_to_output(&mut output, label);
         output.push('\n');
 
         // Child nodes
-        self.append_children_to_output(&mut output, prefix, is_last, is_root);
+        self.append_children_to_output(&mut output, prefix, is_last, is_root, charset, label);
 
         output
     }
 
-    fn append_values_to_output(&self, output: &mut String) {
+    fn append_values_to_output(&self, output: &mut String, label: Label<T>) {
         let mut values = Vec::new();
         if let Some(ref val) = self.exact_value {
-            values.push(format!("exact: {val:?}"));
+            values.push(label("exact", val));
         }
         if let Some(ref val) = self.wildcard_value {
-            values.push(format!("wildcard: {val:?}"));
+            values.push(label("wildcard", val));
         }
         if !values.is_empty() {
             output.push_str(&format!(" [{}]", values.join(", ")));
@@ -49,11 +90,13 @@ impl<T: Debug> RadixNode<T> {
         prefix: &str,
         is_last: bool,
         is_root: bool,
+        charset: &Charset,
+        label: Label<T>,
     ) {
         let child_prefix = if is_root {
             String::new()
         } else {
-            format!("{}{}", prefix, if is_last { "    " } else { "│   " })
+            format!("{}{}", prefix, if is_last { "    " } else { charset.pipe })
         };
 
         let mut children: Vec<_> = self.children.iter().collect();
@@ -61,7 +104,13 @@ impl<T: Debug> RadixNode<T> {
 
         for (i, (_, child)) in children.iter().enumerate() {
             let is_last_child = i == children.len() - 1;
-            output.push_str(&child.pretty_print(&child_prefix, is_last_child, false));
+            output.push_str(&child.pretty_print(
+                &child_prefix,
+                is_last_child,
+                false,
+                charset,
+                label,
+            ));
         }
     }
 }
@@ -70,12 +119,52 @@ impl<T: Debug> Trie<T> {
     /// Returns a pretty-printed representation of the trie structure
     pub fn pretty_print(&self) -> String
     where
-        T: std::fmt::Debug,
+        T: core::fmt::Debug,
     {
+        self.render(&UNICODE, |slot, val| format!("{slot}: {val:?}"))
+    }
+
+    /// Returns a pretty-printed representation of the trie structure, drawn with ASCII only
+    ///
+    /// Like [`Trie::pretty_print`], but with `|--` and `` `-- `` connectors that stay readable
+    /// in any terminal encoding. Paths and values are still printed as-is.
+    pub fn pretty_print_ascii(&self) -> String {
+        self.render(&ASCII, |slot, val| format!("{slot}: {val:?}"))
+    }
+}
+
+impl<T> Trie<T> {
+    /// Returns the tree layout of [`Trie::pretty_print`] without the values
+    ///
+    /// Each node only shows which values it holds, as `[exact]`, `[wildcard]` or
+    /// `[exact, wildcard]`, so the structure can be inspected for value types that aren't
+    /// `Debug`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// #[derive(Clone)]
+    /// struct Handler;
+    ///
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", Handler);
+    /// trie.insert("/api", Handler);
+    ///
+    /// assert_eq!(
+    ///     trie.structure_string(),
+    ///     "(root)\n└── \"/api\" [exact, wildcard]\n"
+    /// );
+    /// ```
+    pub fn structure_string(&self) -> String {
+        self.render(&UNICODE, |slot, _| slot.to_string())
+    }
+
+    /// Renders the trie structure with the given connectors and value labels
+    fn render(&self, charset: &Charset, label: Label<T>) -> String {
         if self.is_empty() {
             "(empty trie)\n".to_string()
         } else {
-            self.0.pretty_print("", true, true)
+            self.root.pretty_print("", true, true, charset, label)
         }
     }
 }
@@ -100,4 +189,63 @@ mod test {
 
         assert!(trie.pretty_print().contains("wildcard"));
     }
+
+    #[test]
+    fn test_pretty_print_ascii() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/v1/users", "users_v1");
+        trie.insert("/api/v1/posts", "posts_v1");
+        trie.insert("/static/*", "static_files");
+
+        let output = trie.pretty_print_ascii();
+        assert!(output.is_ascii());
+        assert_eq!(
+            output,
+            concat!(
+                "(root)\n",
+                "`-- \"/\"\n",
+                "    |-- \"api\" [wildcard: \"api_fallback\"]\n",
+                "    |   `-- \"/v1/\"\n",
+                "    |       |-- \"posts\" [exact: \"posts_v1\"]\n",
+                "    |       `-- \"users\" [exact: \"users_v1\"]\n",
+                "    `-- \"static\" [wildcard: \"static_files\"]\n",
+            )
+        );
+        assert_eq!(
+            trie.pretty_print(),
+            output
+                .replace("|--", "├──")
+                .replace("`--", "└──")
+                .replace('|', "│")
+        );
+    }
+
+    #[test]
+    fn test_structure_string() {
+        // Deliberately not `Debug`
+        #[derive(Clone)]
+        struct Handler;
+
+        let mut trie = Trie::new();
+        trie.insert("/api/*", Handler);
+        trie.insert("/api", Handler);
+        trie.insert("/api/v1/users", Handler);
+        trie.insert("/api/v1/posts", Handler);
+        trie.insert("/static/*", Handler);
+
+        assert_eq!(
+            trie.structure_string(),
+            concat!(
+                "(root)\n",
+                "└── \"/\"\n",
+                "    ├── \"api\" [exact, wildcard]\n",
+                "    │   └── \"/v1/\"\n",
+                "    │       ├── \"posts\" [exact]\n",
+                "    │       └── \"users\" [exact]\n",
+                "    └── \"static\" [wildcard]\n",
+            )
+        );
+        assert_eq!(Trie::<Handler>::new().structure_string(), "(empty trie)\n");
+    }
 }
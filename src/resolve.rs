@@ -0,0 +1,431 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Ambiguous, ChangeEvent, Lookup, Trie, matched_slot};
+
+/// A route matched by [`Trie::resolve`], along with the key it was registered under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<'a, T> {
+    value: &'a T,
+    matched_path: String,
+    is_wildcard: bool,
+}
+
+impl<'a, T> Match<'a, T> {
+    /// Returns the value of the matched route
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+
+    /// Returns the key of the matched route, as yielded by [`Trie::iter`]
+    ///
+    /// Wildcard routes include their `/*` suffix, and single-segment wildcards keep their `*`.
+    pub fn matched_path(&self) -> &str {
+        &self.matched_path
+    }
+
+    /// Returns whether the path was matched by a trailing wildcard route
+    pub fn is_wildcard(&self) -> bool {
+        self.is_wildcard
+    }
+}
+
+/// The key of a route matched by [`Trie::resolve_ref`], as pieces borrowed from the trie
+///
+/// Holds the prefixes of the nodes along the descent to the matched route, so it borrows the
+/// trie and keeps it from being modified while alive. Formatting it with [`Display`] writes
+/// the same key as [`Match::matched_path`] without building a `String` first.
+///
+/// [`Display`]: fmt::Display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedPath<'a> {
+    pieces: Vec<&'a str>,
+    is_wildcard: bool,
+    separator: char,
+}
+
+impl<'a> MatchedPath<'a> {
+    /// Returns the non-empty node prefixes making up the key, from the root down
+    ///
+    /// A wildcard route's `/*` suffix isn't stored in the trie, so it isn't among the pieces.
+    pub fn pieces(&self) -> &[&'a str] {
+        &self.pieces
+    }
+
+    /// Returns whether the path was matched by a trailing wildcard route
+    pub fn is_wildcard(&self) -> bool {
+        self.is_wildcard
+    }
+}
+
+/// Writes the pieces in order, followed by the `/*` suffix of a wildcard route
+impl fmt::Display for MatchedPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pieces
+            .iter()
+            .try_for_each(|piece| f.write_str(piece))?;
+        if self.is_wildcard {
+            write!(f, "{}*", self.separator)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Trie<T> {
+    /// Retrieves the route for the given path, along with the key it was registered under
+    ///
+    /// Matches exactly like [`Trie::get`], so `resolve(path).map(|m| m.value())` is the same as
+    /// `get(path)`. Useful for logging which configured route served a request.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let matched = trie.resolve("/api/posts/42").unwrap();
+    /// assert_eq!(matched.value(), &"api_fallback");
+    /// assert_eq!(matched.matched_path(), "/api/*");
+    /// assert!(matched.is_wildcard());
+    /// ```
+    pub fn resolve(&self, path: &str) -> Option<Match<'_, T>> {
+        let (value, clean_path, is_wildcard) = self.resolve_slot(path)?;
+        Some(Match {
+            value,
+            matched_path: Self::format_key(&clean_path, is_wildcard, self.separator),
+            is_wildcard,
+        })
+    }
+
+    /// Retrieves the route for the given path like [`Trie::resolve`], with its key as borrowed
+    /// pieces
+    ///
+    /// The key isn't joined into a `String`, which suits hot paths that only sometimes log the
+    /// matched route. The returned [`MatchedPath`] borrows the trie's node prefixes, so it
+    /// lives no longer than the shared borrow of the trie.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    /// trie.insert("/api/users", "users_handler");
+    ///
+    /// let (value, matched) = trie.resolve_ref("/api/posts/42").unwrap();
+    /// assert_eq!(value, &"api_fallback");
+    /// assert_eq!(matched.to_string(), "/api/*");
+    /// ```
+    pub fn resolve_ref(&self, path: &str) -> Option<(&T, MatchedPath<'_>)> {
+        let (value, pieces, is_wildcard) = self.resolve_pieces(path)?;
+        let matched = MatchedPath {
+            pieces,
+            is_wildcard,
+            separator: self.separator,
+        };
+        Some((value, matched))
+    }
+
+    /// Retrieves a value like [`Trie::get`], failing instead of picking a winner among
+    /// equally specific wildcard routes
+    ///
+    /// Routes compete when the `?`, `*` and `:name` wildcards at the same position of their
+    /// paths all match. [`Trie::get`] settles this by priority, then by that fixed order;
+    /// here routes sharing the highest priority are an error listing their keys. A literal
+    /// segment still beats any wildcard, and a deeper route a catch-all above it, so those
+    /// aren't ambiguous. Only the first conflict along the descent is reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Ambiguous`] when two or more equally specific routes match the path.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::with_named_params(true);
+    /// trie.insert("/users/*/profile", "any_profile");
+    /// trie.insert("/users/:id/profile", "profile_by_id");
+    /// trie.insert("/users/me/profile", "my_profile");
+    ///
+    /// assert_eq!(trie.get_strict("/users/me/profile"), Ok(Some(&"my_profile")));
+    /// assert_eq!(trie.get_strict("/users"), Ok(None));
+    ///
+    /// let error = trie.get_strict("/users/42/profile").unwrap_err();
+    /// assert_eq!(error.keys(), ["/users/*/profile", "/users/:id/profile"]);
+    /// ```
+    pub fn get_strict(&self, path: &str) -> Result<Option<&T>, Ambiguous> {
+        let mut trail = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut lookup = Lookup {
+            trail: Some(&mut trail),
+            conflicts: Some(&mut conflicts),
+            ..self.lookup()
+        };
+        let value = self.root.get(self.normalize(path).as_bytes(), &mut lookup);
+        if conflicts.is_empty() {
+            Ok(value)
+        } else {
+            Err(Ambiguous::new(conflicts))
+        }
+    }
+
+    /// Removes the value that [`Trie::get`] would return for the given path, returning it
+    ///
+    /// This unregisters whatever currently serves the path: the exact route if there is one,
+    /// otherwise the wildcard that matches it. The change callback reports the key of the
+    /// removed route, as given by [`Match::matched_path`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// assert_eq!(trie.remove_match("/api/users"), Some("api_fallback"));
+    /// assert_eq!(trie.get("/api/users"), None);
+    /// ```
+    pub fn remove_match(&mut self, path: &str) -> Option<T> {
+        let (_, clean_path, is_wildcard) = self.resolve_slot(path)?;
+        let removed = self.remove_slot(&clean_path, is_wildcard)?;
+        let key = Self::format_key(&clean_path, is_wildcard, self.separator);
+        self.notify(ChangeEvent::Removed { path: &key });
+        Some(removed)
+    }
+
+    /// Retrieves the value for the given path like [`Trie::get`], along with the full path of
+    /// the node holding it and whether it's in the wildcard slot
+    pub(crate) fn resolve_slot(&self, path: &str) -> Option<(&T, String, bool)> {
+        let (value, pieces, is_wildcard) = self.resolve_pieces(path)?;
+        Some((value, pieces.concat(), is_wildcard))
+    }
+
+    /// Retrieves the value for the given path like [`Trie::get`], along with the prefixes of
+    /// the nodes down to the one holding it and whether it's in the wildcard slot
+    fn resolve_pieces(&self, path: &str) -> Option<(&T, Vec<&str>, bool)> {
+        self.resolve_normalized(&self.normalize(path), None)
+    }
+
+    /// Resolves an already normalized path like [`Trie::resolve_pieces`], treating the slots
+    /// of the values `accept` rejects as empty
+    pub(crate) fn resolve_normalized(
+        &self,
+        path: &str,
+        accept: Option<&dyn Fn(&T) -> bool>,
+    ) -> Option<(&T, Vec<&str>, bool)> {
+        let mut trail = Vec::new();
+        let mut lookup = Lookup {
+            trail: Some(&mut trail),
+            accept,
+            ..self.lookup()
+        };
+        let value = self.root.get(path.as_bytes(), &mut lookup)?;
+
+        let (depth, is_wildcard) = matched_slot(&trail, value);
+        let pieces = trail[..=depth]
+            .iter()
+            .map(|node| node.prefix.as_str())
+            .filter(|prefix| !prefix.is_empty())
+            .collect();
+        Some((value, pieces, is_wildcard))
+    }
+
+    /// Returns every route that matches the given path like [`Trie::resolve_slot`], in the
+    /// order [`Trie::get`] prefers them
+    ///
+    /// Each route is the one `get` would return if the routes before it were removed, so the
+    /// first is what `get` returns. Routes are looked up one at a time as the iterator advances.
+    pub(crate) fn matching_slots<'a>(
+        &'a self,
+        path: &str,
+    ) -> impl Iterator<Item = (&'a T, String, bool)> + use<'a, T> {
+        let path = self.normalize(path).into_owned();
+        let mut matched = BTreeSet::new();
+        core::iter::from_fn(move || {
+            let accept = |value: &T| !matched.contains(&core::ptr::from_ref(value));
+            let (value, pieces, is_wildcard) = self.resolve_normalized(&path, Some(&accept))?;
+            matched.insert(core::ptr::from_ref(value));
+            Some((value, pieces.concat(), is_wildcard))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MatchPolicy, Trie};
+
+    fn resolved<'a>(trie: &'a Trie<&str>, path: &str) -> Option<(&'a str, String, bool)> {
+        trie.resolve(path).map(|matched| {
+            let key = matched.matched_path().into();
+            (*matched.value(), key, matched.is_wildcard())
+        })
+    }
+
+    #[test]
+    fn test_resolve_reports_matched_route() {
+        let mut trie = Trie::new();
+        trie.insert("/*", "root_fallback");
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/api/*/posts", "posts_handler");
+        trie.insert("/api/x/comments", "comments_handler");
+
+        let expect = |value: &'static str, key: &str, is_wildcard| {
+            Some((value, key.to_string(), is_wildcard))
+        };
+        assert_eq!(
+            resolved(&trie, "/api/users"),
+            expect("users_handler", "/api/users", false)
+        );
+        assert_eq!(
+            resolved(&trie, "/api/users/42"),
+            expect("api_fallback", "/api/*", true)
+        );
+        assert_eq!(
+            resolved(&trie, "/static/app.css"),
+            expect("root_fallback", "/*", true)
+        );
+
+        // The literal `/api/x/` branch is tried and abandoned before the segment wildcard
+        assert_eq!(
+            resolved(&trie, "/api/x/posts"),
+            expect("posts_handler", "/api/*/posts", false)
+        );
+        assert_eq!(
+            resolved(&trie, "/api/x/other"),
+            expect("api_fallback", "/api/*", true)
+        );
+
+        trie.remove("/*");
+        assert_eq!(resolved(&trie, "/static/app.css"), None);
+    }
+
+    #[test]
+    fn test_remove_match() {
+        let mut trie = Trie::new();
+        trie.insert("/api/*", "api_fallback");
+        assert_eq!(trie.remove_match("/api/users"), Some("api_fallback"));
+        assert_eq!(trie.get("/api/posts"), None);
+        assert!(trie.is_empty());
+
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/users/*/posts", "posts_handler");
+        assert_eq!(trie.remove_match("/api/users"), Some("users_handler"));
+        assert_eq!(trie.get("/api/users"), Some(&"api_fallback"));
+        assert_eq!(trie.remove_match("/users/42/posts"), Some("posts_handler"));
+        assert_eq!(trie.get("/users/*/posts"), None);
+        assert_eq!(trie.remove_match("/other"), None);
+
+        // The root catch-all keeps its key under leading slash normalization
+        let mut trie = Trie::with_require_leading_slash(true);
+        trie.insert("*", "root_fallback");
+        assert_eq!(trie.remove_match("static/app.css"), Some("root_fallback"));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_agrees_with_get() {
+        let mut trie = Trie::with_policy(MatchPolicy::WildcardFirst);
+        for route in ["", "/", "/a/*", "/a/b", "/a/*/c", "/a/b/*/d/*", "/z"] {
+            trie.insert(route, route);
+        }
+
+        for path in [
+            "",
+            "/",
+            "/a",
+            "/a/b",
+            "/a/x/c",
+            "/a/b/c",
+            "/a/b/x/d",
+            "/a/b/x/d/e",
+            "/z",
+            "/zz",
+        ] {
+            let matched = trie.resolve(path);
+            assert_eq!(
+                matched.as_ref().map(|m| m.value()),
+                trie.get(path),
+                "{path}"
+            );
+            if let Some(matched) = matched {
+                // Each value is its own route's key
+                assert_eq!(matched.matched_path(), *matched.value(), "{path}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_strict() {
+        let mut trie = Trie::with_named_params(true);
+        trie.insert("/files/?/raw", "char");
+        trie.insert("/files/*/raw", "star");
+        trie.insert("/files/:name/raw", "param");
+        trie.insert("/files/readme/raw", "readme");
+        trie.insert("/files/*", "files");
+        trie.insert("/users/*/posts", "posts");
+
+        let keys = |trie: &Trie<_>, path| trie.get_strict(path).unwrap_err().keys().to_vec();
+        assert_eq!(
+            keys(&trie, "/files/a/raw"),
+            ["/files/?/raw", "/files/*/raw", "/files/:name/raw"]
+        );
+        assert_eq!(
+            keys(&trie, "/files/abc/raw"),
+            ["/files/*/raw", "/files/:name/raw"]
+        );
+        assert_eq!(
+            trie.get_strict("/files/a/raw").unwrap_err().to_string(),
+            "path matches equally specific routes /files/?/raw, /files/*/raw, /files/:name/raw"
+        );
+
+        // Literals, deeper routes and lone wildcards resolve cleanly
+        for path in [
+            "/files/readme/raw",
+            "/files/a/other",
+            "/users/7/posts",
+            "/none",
+        ] {
+            assert_eq!(trie.get_strict(path), Ok(trie.get(path)), "{path}");
+        }
+
+        // A higher priority settles the tie
+        trie.insert_with_priority("/files/:name/raw", "param", 1);
+        assert_eq!(trie.get_strict("/files/abc/raw"), Ok(Some(&"param")));
+        trie.insert_with_priority("/files/*/raw", "star", 1);
+        assert_eq!(
+            keys(&trie, "/files/a/raw"),
+            ["/files/*/raw", "/files/:name/raw"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_ref_pieces_join_to_key() {
+        let mut trie = Trie::with_separator('.');
+        for route in ["com.example.*", "com.example.app", "com.*.api", "org.*"] {
+            trie.insert(route, route);
+        }
+
+        for path in [
+            "com.example.app",
+            "com.example.web",
+            "com.acme.api",
+            "org.x",
+        ] {
+            let (value, matched) = trie.resolve_ref(path).unwrap();
+            assert_eq!(Some(value), trie.get(path), "{path}");
+            assert_eq!(matched.to_string(), *value, "{path}");
+            assert_eq!(matched.is_wildcard(), value.ends_with(".*"), "{path}");
+
+            let joined = matched.pieces().concat();
+            assert_eq!(joined, value.strip_suffix(".*").unwrap_or(value), "{path}");
+        }
+        assert_eq!(
+            trie.resolve_ref("com.example.app").unwrap().1.pieces(),
+            ["com.", "example", ".app"]
+        );
+        assert!(trie.resolve_ref("net.example").is_none());
+    }
+}
@@ -0,0 +1,524 @@
+#[cfg(feature = "snapshot")]
+use alloc::string::String;
+#[cfg(feature = "snapshot")]
+use alloc::sync::Arc;
+#[cfg(feature = "snapshot")]
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "snapshot")]
+use crate::ChangeEvent;
+use crate::{RadixNode, Trie};
+
+/// How the nodes of a trie hold their children
+///
+/// A [`Trie`] owns its nodes inline, so its layout and auto traits are those of its values.
+/// Only a `SnapshotTrie` keeps them behind `Arc` to share them with its snapshots.
+pub(crate) trait Storage: Sized {
+    /// A child node in this storage
+    type Node<T>;
+
+    /// Heap memory allocated for each node, on top of its entry in the parent
+    fn allocation_size<T>() -> usize;
+
+    /// Takes ownership of a node
+    fn new<T>(node: RadixNode<T, Self>) -> Self::Node<T>;
+
+    /// Returns the node
+    fn get<T>(node: &Self::Node<T>) -> &RadixNode<T, Self>;
+
+    /// Returns the node for modification
+    fn get_mut<T>(node: &mut Self::Node<T>) -> &mut RadixNode<T, Self>;
+
+    /// Returns the node, giving up ownership of it
+    fn into_inner<T>(node: Self::Node<T>) -> RadixNode<T, Self>;
+}
+
+/// Nodes owned by their parent, as in every [`Trie`]
+#[derive(Debug, Clone)]
+pub(crate) struct Owned;
+
+impl Storage for Owned {
+    type Node<T> = RadixNode<T, Owned>;
+
+    fn allocation_size<T>() -> usize {
+        0
+    }
+
+    fn new<T>(node: RadixNode<T, Self>) -> RadixNode<T, Self> {
+        node
+    }
+
+    fn get<T>(node: &RadixNode<T, Self>) -> &RadixNode<T, Self> {
+        node
+    }
+
+    fn get_mut<T>(node: &mut RadixNode<T, Self>) -> &mut RadixNode<T, Self> {
+        node
+    }
+
+    fn into_inner<T>(node: RadixNode<T, Self>) -> RadixNode<T, Self> {
+        node
+    }
+}
+
+/// Nodes behind `Arc`, shared between a [`SnapshotTrie`] and its snapshots
+///
+/// A shared node is copied before it's modified or taken apart, see [`Child::make_mut`].
+#[cfg(feature = "snapshot")]
+#[derive(Debug, Clone)]
+pub(crate) struct Shared;
+
+#[cfg(feature = "snapshot")]
+impl Storage for Shared {
+    type Node<T> = Arc<RadixNode<T, Shared>>;
+
+    fn allocation_size<T>() -> usize {
+        core::mem::size_of::<RadixNode<T, Self>>() + 2 * core::mem::size_of::<usize>()
+    }
+
+    fn new<T>(node: RadixNode<T, Self>) -> Arc<RadixNode<T, Self>> {
+        Arc::new(node)
+    }
+
+    fn get<T>(node: &Arc<RadixNode<T, Self>>) -> &RadixNode<T, Self> {
+        node
+    }
+
+    fn get_mut<T>(node: &mut Arc<RadixNode<T, Self>>) -> &mut RadixNode<T, Self> {
+        Arc::get_mut(node).expect(UNSHARED)
+    }
+
+    fn into_inner<T>(node: Arc<RadixNode<T, Self>>) -> RadixNode<T, Self> {
+        Arc::into_inner(node).expect(UNSHARED)
+    }
+}
+
+/// Why a node is never shared when it's modified or taken apart
+#[cfg(feature = "snapshot")]
+const UNSHARED: &str = "a SnapshotTrie copies shared nodes before modifying them";
+
+/// A child node, held as its storage dictates
+pub(crate) struct Child<T, S: Storage = Owned> {
+    node: S::Node<T>,
+}
+
+impl<T, S: Storage> Child<T, S> {
+    /// Takes ownership of a node
+    pub(crate) fn new(node: RadixNode<T, S>) -> Self {
+        Self { node: S::new(node) }
+    }
+
+    /// Returns the node
+    pub(crate) fn into_inner(self) -> RadixNode<T, S> {
+        S::into_inner(self.node)
+    }
+}
+
+impl<T: core::fmt::Debug, S: Storage + core::fmt::Debug> core::fmt::Debug for Child<T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Child").field("node", &**self).finish()
+    }
+}
+
+/// Copies the node and every node below it, sharing nothing with the original
+impl<T: Clone, S: Storage + Clone> Clone for Child<T, S> {
+    fn clone(&self) -> Self {
+        Self::new(RadixNode::clone(self))
+    }
+}
+
+impl<T, S: Storage> Deref for Child<T, S> {
+    type Target = RadixNode<T, S>;
+
+    fn deref(&self) -> &RadixNode<T, S> {
+        S::get(&self.node)
+    }
+}
+
+impl<T, S: Storage> DerefMut for Child<T, S> {
+    fn deref_mut(&mut self) -> &mut RadixNode<T, S> {
+        S::get_mut(&mut self.node)
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl<T> Child<T, Shared> {
+    /// Returns another handle to the same node
+    fn share(&self) -> Self {
+        Self {
+            node: Arc::clone(&self.node),
+        }
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl<T: Clone> Child<T, Shared> {
+    /// Returns the node for modification, copying it first if it's shared
+    ///
+    /// The copy shares its children with the original, unlike [`Child::clone`].
+    fn make_mut(&mut self) -> &mut RadixNode<T, Shared> {
+        if Arc::get_mut(&mut self.node).is_none() {
+            let node = &*self.node;
+            self.node = Arc::new(RadixNode {
+                prefix: node.prefix.clone(),
+                children: node
+                    .children
+                    .iter()
+                    .map(|(&c, child)| (c, child.share()))
+                    .collect(),
+                exact_value: node.exact_value.clone(),
+                wildcard_value: node.wildcard_value.clone(),
+                exact_priority: node.exact_priority,
+                wildcard_priority: node.wildcard_priority,
+            });
+        }
+        Arc::get_mut(&mut self.node).expect("node was just copied")
+    }
+
+    /// Copies the shared nodes that inserting or removing the route at `path` can modify
+    ///
+    /// These are the nodes along the path, including the last one a split can cut, and the
+    /// children of those with at most two: a removal can leave such a node with a single
+    /// child, which it absorbs. The rest stays shared.
+    fn unshare_path(&mut self, path: &str) {
+        let mut node = self.make_mut();
+        let mut path = path;
+        loop {
+            if node.children.len() <= 2 {
+                node.children.values_mut().for_each(|child| {
+                    child.make_mut();
+                });
+            }
+            let Some(remaining_path) = path.strip_prefix(node.prefix.as_str()) else {
+                return;
+            };
+            let Some(child) = first_char_child(node, remaining_path) else {
+                return;
+            };
+            node = child.make_mut();
+            path = remaining_path;
+        }
+    }
+
+    /// Copies every shared node in this subtree
+    fn unshare_all(&mut self) {
+        let mut stack = Vec::from([self]);
+        while let Some(child) = stack.pop() {
+            stack.extend(child.make_mut().children.values_mut());
+        }
+    }
+}
+
+/// Returns the child of `node` that `path` continues into, if any
+#[cfg(feature = "snapshot")]
+fn first_char_child<'a, T>(
+    node: &'a mut RadixNode<T, Shared>,
+    path: &str,
+) -> Option<&'a mut Child<T, Shared>> {
+    let first_char = path.chars().next()?;
+    node.children.get_mut(&first_char)
+}
+
+/// Copies the routes and settings of the trie, without its change callback
+impl<T: Clone> Clone for Trie<T> {
+    fn clone(&self) -> Self {
+        self.with_root(self.root.clone())
+    }
+}
+
+impl<T> Trie<T> {
+    /// Creates a trie with the given root and this trie's settings, but no change callback
+    fn with_root(&self, root: Child<T>) -> Trie<T> {
+        Trie {
+            root,
+            policy: self.policy,
+            require_leading_slash: self.require_leading_slash,
+            case_insensitive: self.case_insensitive,
+            percent_decoding: self.percent_decoding,
+            collapse_slashes: self.collapse_slashes,
+            named_params: self.named_params,
+            separator: self.separator,
+            max_prefix_len: self.max_prefix_len,
+            prioritized: self.prioritized,
+            on_change: None,
+        }
+    }
+}
+
+/// A route table whose snapshots share its nodes, created with the `snapshot` feature
+///
+/// Taking a [`SnapshotTrie::snapshot`] is `O(1)`: both tries share every node behind `Arc`.
+/// Either one then copies a shared node only when inserting or removing a route through it,
+/// so a modification copies the nodes on its path rather than the whole table. Values must be
+/// [`Clone`], since a shared node is copied along with them.
+///
+/// Only this type keeps its nodes behind `Arc`; a [`Trie`] owns them inline, whether or not
+/// the feature is enabled. Lookups match paths exactly like the [`Trie`] it was created from,
+/// and [`SnapshotTrie::into_trie`] turns it back into one for everything else.
+///
+/// # Examples
+/// ```rust
+/// # use wildcard_trie::{SnapshotTrie, Trie};
+/// let mut trie = SnapshotTrie::from(Trie::new());
+/// trie.insert("/api/users", "users_handler");
+///
+/// let snapshot = trie.snapshot();
+/// trie.insert("/api/users", "users_handler_v2");
+///
+/// assert_eq!(snapshot.get("/api/users"), Some(&"users_handler"));
+/// assert_eq!(trie.get("/api/users"), Some(&"users_handler_v2"));
+/// ```
+#[cfg(feature = "snapshot")]
+#[derive(Debug)]
+pub struct SnapshotTrie<T> {
+    /// The settings and change callback, with an empty root
+    trie: Trie<T>,
+    /// The routes, sharing nodes with snapshots
+    root: Child<T, Shared>,
+}
+
+#[cfg(feature = "snapshot")]
+impl<T> SnapshotTrie<T> {
+    /// Retrieves a value for the given path, like [`Trie::get`]
+    pub fn get(&self, path: &str) -> Option<&T> {
+        let mut lookup = self.trie.lookup();
+        self.root
+            .get(&self.trie.normalize_bytes(path.as_bytes()), &mut lookup)
+    }
+
+    /// Returns the number of routes, like [`Trie::len`]
+    pub fn len(&self) -> usize {
+        self.root.value_count()
+    }
+
+    /// Checks if the trie stores no values, like [`Trie::is_empty`]
+    pub fn is_empty(&self) -> bool {
+        !self.root.has_values()
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl<T: Clone> SnapshotTrie<T> {
+    /// Creates an empty trie
+    pub fn new() -> Self {
+        Self::from(Trie::new())
+    }
+
+    /// Returns a copy of the routes and settings of this trie, without its change callback
+    ///
+    /// The copy shares every node with this trie, so taking it is `O(1)`.
+    pub fn snapshot(&self) -> SnapshotTrie<T> {
+        SnapshotTrie {
+            trie: self
+                .trie
+                .with_root(Child::new(RadixNode::new(String::new()))),
+            root: self.root.share(),
+        }
+    }
+
+    /// Inserts a value at the given path like [`Trie::insert`], copying the shared nodes it
+    /// modifies
+    pub fn insert(&mut self, path: &str, value: T) {
+        let path = self.trie.normalize(path);
+        let (clean_path, is_wildcard) = self.trie.parse_path(&path);
+        self.root.unshare_path(&clean_path);
+        let replaced = self
+            .root
+            .node_mut_chunked(&clean_path, self.trie.max_prefix_len)
+            .store_value(value, is_wildcard);
+        self.trie.notify(ChangeEvent::Inserted {
+            path: &path,
+            replaced: replaced.is_some(),
+        });
+    }
+
+    /// Removes the value at the given path like [`Trie::remove`], copying the shared nodes it
+    /// modifies
+    pub fn remove(&mut self, path: &str) -> Option<T> {
+        let path = self.trie.normalize(path);
+        let (clean_path, is_wildcard) = self.trie.parse_path(&path);
+        self.root.unshare_path(&clean_path);
+        let removed =
+            self.root
+                .remove_chunked(&clean_path, is_wildcard, self.trie.max_prefix_len)?;
+        self.trie.notify(ChangeEvent::Removed { path: &path });
+        Some(removed)
+    }
+
+    /// Returns the routes as a plain [`Trie`], copying the nodes still shared with snapshots
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::SnapshotTrie;
+    /// let mut trie = SnapshotTrie::new();
+    /// trie.insert("/api/*", 1);
+    /// let snapshot = trie.snapshot();
+    ///
+    /// let mut plain = trie.into_trie();
+    /// plain.values_mut().for_each(|value| *value += 1);
+    /// assert_eq!(plain.get("/api/users"), Some(&2));
+    /// assert_eq!(snapshot.get("/api/users"), Some(&1));
+    /// ```
+    pub fn into_trie(self) -> Trie<T> {
+        let Self { mut trie, mut root } = self;
+        root.unshare_all();
+        trie.root = Child::new(root.into_inner().map(&mut core::convert::identity));
+        trie
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl<T: Clone> Default for SnapshotTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Takes over the routes and settings of the trie, along with its change callback
+#[cfg(feature = "snapshot")]
+impl<T> From<Trie<T>> for SnapshotTrie<T> {
+    fn from(mut trie: Trie<T>) -> Self {
+        let root = core::mem::replace(&mut trie.root, Child::new(RadixNode::new(String::new())));
+        Self {
+            root: Child::new(root.into_inner().map(&mut core::convert::identity)),
+            trie,
+        }
+    }
+}
+
+/// Clones the trie with [`SnapshotTrie::snapshot`], sharing every node
+#[cfg(feature = "snapshot")]
+impl<T: Clone> Clone for SnapshotTrie<T> {
+    fn clone(&self) -> Self {
+        self.snapshot()
+    }
+}
+
+#[cfg(all(test, feature = "snapshot"))]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::string::{String, ToString};
+    use alloc::sync::Arc;
+    use core::cell::Cell;
+
+    use super::SnapshotTrie;
+    use crate::{MatchPolicy, Trie};
+
+    /// Returns the routes of a trie, leaving it and its snapshots untouched
+    fn routes<T: Clone>(trie: &SnapshotTrie<T>) -> BTreeMap<String, T> {
+        trie.snapshot().into_trie().into_iter().collect()
+    }
+
+    #[test]
+    fn test_snapshot_independent_of_mutations() {
+        let mut trie = SnapshotTrie::from(Trie::with_policy(MatchPolicy::WildcardFirst));
+        trie.insert("/api/*", "api_fallback");
+        trie.insert("/api/users", "users_handler");
+        trie.insert("/static/*", "static_files");
+
+        let snapshot = trie.snapshot();
+        trie.insert("/api/users", "users_handler_v2");
+        trie.insert("/api/posts", "posts_handler");
+        trie.remove("/static/*");
+        trie.remove("/api/*");
+
+        assert_eq!(
+            routes(&snapshot),
+            BTreeMap::from([
+                ("/api/*".to_string(), "api_fallback"),
+                ("/api/users".to_string(), "users_handler"),
+                ("/static/*".to_string(), "static_files"),
+            ])
+        );
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.get("/api/users"), Some(&"api_fallback"));
+
+        // Mutating the snapshot leaves the original alone as well
+        let mut snapshot = snapshot;
+        snapshot.insert("/admin", "admin_handler");
+        assert_eq!(trie.get("/admin"), None);
+        assert_eq!(
+            routes(&trie),
+            BTreeMap::from([
+                ("/api/posts".to_string(), "posts_handler"),
+                ("/api/users".to_string(), "users_handler_v2"),
+            ])
+        );
+
+        // A plain trie taken out of either is independent too, and keeps the settings
+        let mut plain = snapshot.clone().into_trie();
+        plain.values_mut().for_each(|value| *value = "changed");
+        assert_eq!(plain.policy(), MatchPolicy::WildcardFirst);
+        assert_eq!(snapshot.get("/admin"), Some(&"admin_handler"));
+    }
+
+    #[test]
+    fn test_snapshot_shares_untouched_nodes() {
+        let mut trie = SnapshotTrie::new();
+        trie.insert("/api/users", 1);
+        trie.insert("/static/app.css", 2);
+        trie.insert("/blog/1", 3);
+
+        let snapshot = trie.snapshot();
+        assert!(Arc::ptr_eq(&trie.root.node, &snapshot.root.node));
+
+        trie.insert("/api/posts", 4);
+        assert!(!Arc::ptr_eq(&trie.root.node, &snapshot.root.node));
+
+        // Only the modified path and its children were copied
+        let child = |trie: &SnapshotTrie<i32>, first_char| {
+            trie.root.children[&'/'].children[&first_char].node.clone()
+        };
+        assert!(Arc::ptr_eq(&child(&trie, 's'), &child(&snapshot, 's')));
+        assert!(!Arc::ptr_eq(&child(&trie, 'a'), &child(&snapshot, 'a')));
+    }
+
+    #[test]
+    fn test_plain_trie_owns_its_nodes() {
+        fn assert_send<T: Send>() {}
+
+        // A `Cell` is `Send` but not `Sync`, which nodes behind `Arc` would require
+        assert_send::<Trie<Cell<u32>>>();
+        let mut trie = Trie::new();
+        trie.insert("/counter", Cell::new(0));
+        std::thread::spawn(move || trie["/counter"].set(1))
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_snapshots_match_a_model_through_splits_and_merges() {
+        let paths = [
+            "/", "/a", "/ab", "/abc", "/abd", "/a/*", "/ab/c", "/b", "/ba/*", "/bab",
+        ];
+        for max_prefix_len in [None, Some(2)] {
+            let base = max_prefix_len.map_or_else(Trie::new, Trie::with_max_prefix_len);
+            let mut tries = Vec::from([(SnapshotTrie::from(base), BTreeMap::new())]);
+            let mut seed = 7u32;
+            for step in 0..400usize {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let roll = (seed >> 16) as usize;
+                let path = paths[roll % paths.len()];
+                let index = roll / paths.len() % tries.len();
+                let (trie, model) = &mut tries[index];
+                if roll.is_multiple_of(3) {
+                    assert_eq!(trie.remove(path), model.remove(path), "{path}");
+                } else {
+                    trie.insert(path, step);
+                    model.insert(path.to_string(), step);
+                }
+                if step.is_multiple_of(25) {
+                    let (trie, model) = &tries[index];
+                    tries.push((trie.snapshot(), model.clone()));
+                }
+            }
+            for (trie, model) in tries {
+                assert_eq!(routes(&trie), model);
+                assert_eq!(trie.len(), model.len());
+                let plain = trie.into_trie();
+                assert_eq!(plain.into_iter().collect::<BTreeMap<_, _>>(), model);
+            }
+        }
+    }
+}
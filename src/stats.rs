@@ -0,0 +1,336 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{RadixNode, Trie};
+
+/// Shape of a [`Trie`], returned by [`Trie::stats`]
+///
+/// Compares how well the stored keys branch: a trie whose nodes mostly have a single child
+/// behaves like a linked list, and gains little from prefix compression.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrieStats {
+    /// Number of nodes, including the root (see [`Trie::node_count`])
+    pub node_count: usize,
+    /// Number of nodes from the root to the deepest leaf, including the root (see
+    /// [`Trie::depth`])
+    pub max_depth: usize,
+    /// Number of stored values, counting exact and wildcard routes separately
+    pub value_count: usize,
+    /// Average number of children of the nodes that have any, or 0 for an empty trie
+    pub avg_branching_factor: f64,
+    /// Number of nodes in the longest run of parent-child nodes that each have a single child
+    pub longest_chain: usize,
+}
+
+impl<T> RadixNode<T> {
+    /// Adds this subtree to `stats`, where `depth` and `chain` count the nodes above this one
+    ///
+    /// `chain` is the length of the run of single-child nodes ending at the parent. Returns the
+    /// number of nodes with children in this subtree.
+    fn collect_stats(&self, depth: usize, chain: usize, stats: &mut TrieStats) -> usize {
+        let depth = depth + 1;
+        stats.node_count += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+        stats.value_count +=
+            usize::from(self.exact_value.is_some()) + usize::from(self.wildcard_value.is_some());
+
+        let chain = if self.children.len() == 1 {
+            chain + 1
+        } else {
+            0
+        };
+        stats.longest_chain = stats.longest_chain.max(chain);
+
+        let mut branching_nodes = usize::from(!self.children.is_empty());
+        for child in self.children.values() {
+            branching_nodes += child.collect_stats(depth, chain, stats);
+        }
+        branching_nodes
+    }
+
+    /// Returns the number of filled exact or wildcard slots in this subtree
+    fn slot_count(&self, is_wildcard: bool) -> usize {
+        let mut count = 0;
+        let mut stack = Vec::from([self]);
+        while let Some(node) = stack.pop() {
+            count += usize::from(node.slot(is_wildcard).is_some());
+            stack.extend(node.children.values().map(|child| &**child));
+        }
+        count
+    }
+}
+
+impl<T> Trie<T> {
+    /// Returns statistics about the shape of the trie, gathered in a single pass
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "users");
+    /// trie.insert("/api/v1/posts", "posts");
+    ///
+    /// // (root) -> "/api/v1/" -> "users" | "posts"
+    /// let stats = trie.stats();
+    /// assert_eq!(stats.node_count, 4);
+    /// assert_eq!(stats.max_depth, 3);
+    /// assert_eq!(stats.value_count, 2);
+    /// assert_eq!(stats.avg_branching_factor, 1.5);
+    /// assert_eq!(stats.longest_chain, 1);
+    /// ```
+    pub fn stats(&self) -> TrieStats {
+        let mut stats = TrieStats::default();
+        let branching_nodes = self.root.collect_stats(0, 0, &mut stats);
+        if branching_nodes > 0 {
+            // Every node but the root is the child of exactly one node
+            stats.avg_branching_factor = (stats.node_count - 1) as f64 / branching_nodes as f64;
+        }
+        stats
+    }
+
+    /// Returns the number of exact routes, including literal `/*` routes stored with
+    /// [`Trie::insert_exact`]
+    ///
+    /// Together with [`Trie::count_wildcard`] this gives the ratio of specific to catch-all
+    /// routes; the two add up to [`Trie::len`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users");
+    /// trie.insert("/api/posts", "posts");
+    /// trie.insert("/api/*", "api_fallback");
+    ///
+    /// assert_eq!(trie.count_exact(), 2);
+    /// assert_eq!(trie.count_wildcard(), 1);
+    /// ```
+    pub fn count_exact(&self) -> usize {
+        self.root.slot_count(false)
+    }
+
+    /// Returns the number of trailing wildcard routes, like `/api/*`
+    ///
+    /// Routes with only single-segment wildcards, like `/users/*/posts`, are exact routes.
+    pub fn count_wildcard(&self) -> usize {
+        self.root.slot_count(true)
+    }
+
+    /// Summarizes the routes by their path up to the `n + 1`-th separator, with the number of
+    /// routes under each, in lexicographic order
+    ///
+    /// The depth of a path is the number of separators in it, so at depth 1 `/api/v1/users`
+    /// and `/api/*` are both counted under `/api`. Routes whose path is shallower than `n` are
+    /// left out.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "users");
+    /// trie.insert("/api/v1/posts", "posts");
+    /// trie.insert("/auth/login", "login");
+    ///
+    /// assert_eq!(
+    ///     trie.at_depth(1),
+    ///     [("/api".to_string(), 2), ("/auth".to_string(), 1)]
+    /// );
+    /// ```
+    pub fn at_depth(&self, n: usize) -> Vec<(String, usize)> {
+        let mut counts = BTreeMap::new();
+        let mut stack = Vec::from([(String::new(), 0, &*self.root)]);
+        while let Some((path, depth, node)) = stack.pop() {
+            // Cut the path at the separator that would take it deeper than `n`, where `depth`
+            // never exceeds `n` for the nodes on the stack
+            let mut separators = node.prefix.match_indices(self.separator);
+            if let Some((index, _)) = separators.nth(n - depth) {
+                let key = [path.as_str(), &node.prefix[..index]].concat();
+                *counts.entry(key).or_default() += node.value_count();
+                continue;
+            }
+
+            let full_path = [path.as_str(), &node.prefix].concat();
+            let depth = depth + node.prefix.matches(self.separator).count();
+            if depth == n {
+                let values = usize::from(node.exact_value.is_some())
+                    + usize::from(node.wildcard_value.is_some());
+                if values > 0 {
+                    *counts.entry(full_path.clone()).or_default() += values;
+                }
+            }
+            for child in node.children.values() {
+                stack.push((full_path.clone(), depth, child));
+            }
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Returns the full paths of the branches where the routes first diverge, in lexicographic
+    /// order
+    ///
+    /// Nodes shared by every route, like the `/` all paths usually start with, are skipped.
+    /// The result lists the compressed prefixes of the nodes below, not logical segments: with
+    /// `/api` and `/auth` routes both branches start at `/a`. A single route is its own branch.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/users", "users");
+    /// trie.insert("/api/posts", "posts");
+    /// trie.insert("/static/*", "static_files");
+    ///
+    /// assert_eq!(trie.top_level_prefixes(), ["/api/", "/static"]);
+    /// ```
+    pub fn top_level_prefixes(&self) -> Vec<String> {
+        let mut path = self.root.prefix.clone();
+        let mut node = &*self.root;
+        while node.exact_value.is_none() && node.wildcard_value.is_none() {
+            let mut children = node.children.values();
+            let (Some(child), None) = (children.next(), children.next()) else {
+                break;
+            };
+            path.push_str(&child.prefix);
+            node = child;
+        }
+
+        if node.children.is_empty() {
+            // A lone route, or none at all
+            return Vec::from_iter(node.has_values().then_some(path));
+        }
+        let mut prefixes: Vec<_> = node
+            .children
+            .values()
+            .map(|child| [path.as_str(), &child.prefix].concat())
+            .collect();
+        prefixes.sort_unstable();
+        prefixes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Trie, TrieStats};
+
+    #[test]
+    fn test_at_depth() {
+        let mut trie = Trie::new();
+        trie.insert("/api/v1/users", 1);
+        trie.insert("/api/v1/posts", 2);
+        trie.insert("/api/v2/*", 3);
+        trie.insert("/api", 4);
+        trie.insert("/apix/docs", 5);
+        trie.insert("/auth/login", 6);
+        trie.insert("/", 7);
+
+        let counts = |n| trie.at_depth(n);
+        assert_eq!(
+            counts(1),
+            [
+                ("/".to_string(), 1),
+                ("/api".to_string(), 4),
+                ("/apix".to_string(), 1),
+                ("/auth".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            counts(2),
+            [
+                ("/api/v1".to_string(), 2),
+                ("/api/v2".to_string(), 1),
+                ("/apix/docs".to_string(), 1),
+                ("/auth/login".to_string(), 1),
+            ]
+        );
+        assert_eq!(counts(0), [(String::new(), 7)]);
+        assert!(counts(4).is_empty());
+    }
+
+    #[test]
+    fn test_top_level_prefixes() {
+        let mut trie = Trie::new();
+        assert!(trie.top_level_prefixes().is_empty());
+
+        trie.insert("/api/v1/users", 1);
+        assert_eq!(trie.top_level_prefixes(), ["/api/v1/users"]);
+
+        trie.insert("/api/v1/posts", 2);
+        trie.insert("/auth/login", 3);
+        trie.insert("/static/*", 4);
+        trie.insert("/admin", 5);
+        assert_eq!(trie.top_level_prefixes(), ["/a", "/static"]);
+
+        // A route on the shared part stops the descent there
+        trie.insert("/", 6);
+        assert_eq!(trie.top_level_prefixes(), ["/a", "/static"]);
+        trie.remove("/static/*");
+        assert_eq!(trie.top_level_prefixes(), ["/a"]);
+    }
+
+    #[test]
+    fn test_count_exact_and_wildcard() {
+        let mut trie = Trie::new();
+        trie.insert("/api", 1);
+        trie.insert("/api/*", 2);
+        trie.insert("/api/users", 3);
+        trie.insert("/users/*/posts", 4);
+        trie.insert("/*", 5);
+        trie.insert_exact("/static/*", 6);
+        trie.insert("/static/*", 7);
+
+        assert_eq!(trie.count_exact(), 4);
+        assert_eq!(trie.count_wildcard(), 3);
+        assert_eq!(trie.len(), 7);
+        assert_eq!(trie.len(), trie.iter().count());
+
+        trie.unset("/api/*");
+        trie.remove("/api");
+        assert_eq!((trie.count_exact(), trie.count_wildcard()), (3, 2));
+        assert_eq!(trie.len(), 5);
+        assert_eq!(Trie::<u8>::new().len(), 0);
+    }
+
+    #[test]
+    fn test_stats_degenerate_vs_bushy() {
+        // Each key extends the previous one, so every node has a single child
+        let mut chain = Trie::new();
+        let mut path = String::new();
+        for segment in ["a", "b", "c", "d", "e"] {
+            path = format!("{path}/{segment}");
+            chain.insert(&path, path.len());
+        }
+        assert_eq!(
+            chain.stats(),
+            TrieStats {
+                node_count: 6,
+                max_depth: 6,
+                value_count: 5,
+                avg_branching_factor: 1.0,
+                longest_chain: 5,
+            }
+        );
+
+        // Sibling keys all branch off the shared `/` node
+        let mut bushy = Trie::new();
+        for segment in ["a", "b", "c", "d", "e"] {
+            bushy.insert(&format!("/{segment}"), 0);
+            bushy.insert(&format!("/{segment}/*"), 1);
+        }
+        assert_eq!(
+            bushy.stats(),
+            TrieStats {
+                node_count: 7,
+                max_depth: 3,
+                value_count: 10,
+                avg_branching_factor: 3.0,
+                longest_chain: 1,
+            }
+        );
+
+        let stats = bushy.stats();
+        assert_eq!(stats.node_count, bushy.node_count());
+        assert_eq!(stats.max_depth, bushy.depth());
+        assert_eq!(Trie::<()>::new().stats().avg_branching_factor, 0.0);
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::string::String;
+
+use crate::Trie;
+
+/// A mutable view of a [`Trie`] scoped to a path prefix
+///
+/// Paths passed to a `SubTrie` are relative to its prefix and joined with the trie's
+/// separator, so `"bar"` and `"/bar"` both refer to `{prefix}/bar`, and `"*"` is a catch-all
+/// for the whole prefix.
+/// Lookups behave exactly as on the parent trie, including wildcards registered above the
+/// prefix.
+///
+/// # Examples
+/// ```rust
+/// # use wildcard_trie::Trie;
+/// let mut trie = Trie::new();
+/// let mut plugin = trie.subtrie_mut("/plugins/foo").unwrap();
+/// plugin.insert("bar", "bar_handler");
+/// plugin.insert("*", "foo_fallback");
+///
+/// assert_eq!(trie.get("/plugins/foo/bar"), Some(&"bar_handler"));
+/// assert_eq!(trie.get("/plugins/foo/baz"), Some(&"foo_fallback"));
+/// ```
+#[derive(Debug)]
+pub struct SubTrie<'a, T> {
+    trie: &'a mut Trie<T>,
+    /// The prefix all paths are relative to, without a trailing separator
+    prefix: String,
+}
+
+impl<'a, T> SubTrie<'a, T> {
+    /// Creates a view of `trie` scoped to `prefix`
+    pub(crate) fn new(trie: &'a mut Trie<T>, prefix: &str) -> Self {
+        let prefix = prefix.trim_end_matches(trie.separator).into();
+        Self { trie, prefix }
+    }
+
+    /// Returns the prefix this view is scoped to
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Inserts a value at the given relative path
+    pub fn insert(&mut self, path: &str, value: T) {
+        let path = self.full_path(path);
+        self.trie.insert(&path, value);
+    }
+
+    /// Retrieves a value for the given relative path, with exact > wildcard precedence.
+    pub fn get(&self, path: &str) -> Option<&T> {
+        self.trie.get(&self.full_path(path))
+    }
+
+    /// Removes a value at the given relative path, returning it if it existed
+    pub fn remove(&mut self, path: &str) -> Option<T> {
+        let path = self.full_path(path);
+        self.trie.remove(&path)
+    }
+
+    /// Joins a relative path onto this view's prefix
+    fn full_path(&self, path: &str) -> String {
+        let mut full_path = self.prefix.clone();
+        if !path.is_empty() && !path.starts_with(self.trie.separator) {
+            full_path.push(self.trie.separator);
+        }
+        full_path.push_str(path);
+        full_path
+    }
+}
+
+impl<T> Trie<T> {
+    /// Returns a view of the trie scoped to `prefix`, for registering routes relative to it
+    ///
+    /// Returns `None` if `prefix` is a wildcard route (ends with `/*`), since there is no
+    /// single path for relative routes to hang off.
+    pub fn subtrie_mut(&mut self, prefix: &str) -> Option<SubTrie<'_, T>> {
+        if self.parse_path(prefix).1 {
+            return None;
+        }
+        Some(SubTrie::new(self, prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_subtrie_insert_visible_from_parent() {
+        let mut trie = Trie::new();
+        trie.insert("/plugins/other", "other");
+
+        let mut plugin = trie.subtrie_mut("/plugins/foo").unwrap();
+        assert_eq!(plugin.prefix(), "/plugins/foo");
+        plugin.insert("bar", "bar_handler");
+        plugin.insert("/baz", "baz_handler");
+        plugin.insert("", "index");
+        assert_eq!(plugin.get("bar"), Some(&"bar_handler"));
+        assert_eq!(plugin.remove("baz"), Some("baz_handler"));
+
+        assert_eq!(trie.get("/plugins/foo/bar"), Some(&"bar_handler"));
+        assert_eq!(trie.get("/plugins/foo/baz"), None);
+        assert_eq!(trie.get("/plugins/foo"), Some(&"index"));
+        assert_eq!(trie.get("/plugins/other"), Some(&"other"));
+    }
+
+    #[test]
+    fn test_subtrie_wildcards() {
+        let mut trie = Trie::new();
+        let mut plugin = trie.subtrie_mut("/plugins/foo/").unwrap();
+        plugin.insert("*", "foo_fallback");
+        assert_eq!(plugin.get("anything/below"), Some(&"foo_fallback"));
+
+        assert_eq!(trie.get("/plugins/foo/x"), Some(&"foo_fallback"));
+        assert!(trie.subtrie_mut("/plugins/*").is_none());
+    }
+
+    #[test]
+    fn test_subtrie_custom_separator() {
+        let mut trie = Trie::with_separator('.');
+        let mut example = trie.subtrie_mut("com.example.").unwrap();
+        assert_eq!(example.prefix(), "com.example");
+        example.insert("app", "app");
+        example.insert("*", "fallback");
+
+        assert_eq!(trie.get("com.example.app"), Some(&"app"));
+        assert_eq!(trie.get("com.example.web"), Some(&"fallback"));
+        assert!(trie.subtrie_mut("com.*").is_none());
+    }
+}
@@ -0,0 +1,121 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{RadixNode, Trie};
+
+/// A read-only view of a node of a [`Trie`], passed to the callback of [`Trie::visit`]
+#[derive(Debug)]
+pub struct VisitNode<'a, T> {
+    node: &'a RadixNode<T>,
+    path: String,
+    depth: usize,
+}
+
+impl<'a, T> VisitNode<'a, T> {
+    /// Returns the full path of the node, from the root
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the part of the path stored in this node, after its parent's path
+    pub fn prefix(&self) -> &'a str {
+        &self.node.prefix
+    }
+
+    /// Returns the number of nodes above this one, so the root has depth 0
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the value of the exact route at this node's path, if any
+    pub fn exact_value(&self) -> Option<&'a T> {
+        self.node.exact_value.as_ref()
+    }
+
+    /// Returns the value of the wildcard route below this node's path, if any
+    pub fn wildcard_value(&self) -> Option<&'a T> {
+        self.node.wildcard_value.as_ref()
+    }
+
+    /// Returns the number of children of this node
+    pub fn child_count(&self) -> usize {
+        self.node.children.len()
+    }
+}
+
+impl<T> Trie<T> {
+    /// Calls `f` on every node of the trie, parents before their children
+    ///
+    /// Exposes the node structure for custom analyses, including intermediate nodes without
+    /// values. Siblings are visited in no particular order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use wildcard_trie::Trie;
+    /// let mut trie = Trie::new();
+    /// trie.insert("/api/v1/users", "users");
+    /// trie.insert("/api/v1/posts", "posts");
+    ///
+    /// // Count the nodes that branch
+    /// let mut branching = 0;
+    /// trie.visit(|node| branching += usize::from(node.child_count() > 1));
+    /// assert_eq!(branching, 1);
+    /// ```
+    pub fn visit<'a>(&'a self, mut f: impl FnMut(&VisitNode<'a, T>)) {
+        let mut stack = Vec::from([(self.root.prefix.clone(), 0, &*self.root)]);
+        while let Some((path, depth, node)) = stack.pop() {
+            for child in node.children.values() {
+                stack.push(([path.as_str(), &child.prefix].concat(), depth + 1, child));
+            }
+            f(&VisitNode { node, path, depth });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_visit_nodes() {
+        let mut trie = Trie::new();
+        trie.insert("/api/users", 1);
+        trie.insert("/api/posts", 2);
+        trie.insert("/api/*", 3);
+        trie.insert("/static/*", 4);
+
+        let mut nodes = Vec::new();
+        trie.visit(|node| {
+            nodes.push((
+                node.path().to_string(),
+                node.depth(),
+                node.exact_value().copied(),
+                node.wildcard_value().copied(),
+                node.child_count(),
+            ));
+        });
+        nodes.sort();
+        assert_eq!(
+            nodes,
+            [
+                (String::new(), 0, None, None, 1),
+                ("/".to_string(), 1, None, None, 2),
+                ("/api".to_string(), 2, None, Some(3), 1),
+                ("/api/".to_string(), 3, None, None, 2),
+                ("/api/posts".to_string(), 4, Some(2), None, 0),
+                ("/api/users".to_string(), 4, Some(1), None, 0),
+                ("/static".to_string(), 2, None, Some(4), 0),
+            ]
+        );
+        assert_eq!(nodes.len(), trie.node_count());
+        assert_eq!(
+            nodes.iter().map(|(_, depth, ..)| depth + 1).max(),
+            Some(trie.depth())
+        );
+
+        let mut prefixes = Vec::new();
+        trie.visit(|node| prefixes.push(node.prefix()));
+        prefixes.sort_unstable();
+        assert_eq!(prefixes, ["", "/", "/", "api", "posts", "static", "users"]);
+    }
+}
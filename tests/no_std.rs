@@ -0,0 +1,28 @@
+//! Smoke test that the public API is usable from a `#![no_std]` crate.
+//!
+//! Build the library itself without `std` with `cargo build --no-default-features`.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std;
+
+use alloc::string::String;
+use wildcard_trie::Trie;
+
+#[test]
+fn test_no_std_api() {
+    let mut trie: Trie<String> = Trie::new();
+    trie.insert("/api/*", String::from("api_handler"));
+    trie.insert("/api/users", String::from("users_handler"));
+
+    assert_eq!(
+        trie.get("/api/users").map(String::as_str),
+        Some("users_handler")
+    );
+    assert_eq!(
+        trie.get("/api/posts").map(String::as_str),
+        Some("api_handler")
+    );
+    assert_eq!(trie.remove("/api/users").as_deref(), Some("users_handler"));
+}